@@ -0,0 +1,261 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{CurrentNetwork, Developer, FeeDisplay, Scan, SpentFilter, TlsOptions};
+
+use snarkvm::prelude::{Ciphertext, Field, PrivateKey, Record, ViewKey};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Display the private and public balance of an account.
+///
+/// The private balance sums the unspent `credits.aleo` records of the account, found by scanning the
+/// chain from genesis, or from the checkpoint of a previous scan. The public balance is the `account`
+/// mapping of `credits.aleo`.
+#[derive(Debug, Parser)]
+pub struct Balance {
+    /// The private key of the account.
+    #[clap(short, long)]
+    private_key: String,
+    /// The endpoint to query the records and the public balance from.
+    #[clap(short, long)]
+    query: String,
+    /// A file recording the unspent records of the account and the last scanned block height, to resume the
+    /// scan from.
+    #[clap(long, requires = "spent_filter")]
+    checkpoint: Option<PathBuf>,
+    /// A file persisting the filter of serial numbers spent in the scanned blocks, across scans.
+    #[clap(long)]
+    spent_filter: Option<PathBuf>,
+    #[clap(flatten)]
+    tls: TlsOptions,
+}
+
+/// The records of an account found by previous scans, along with the last scanned block height.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BalanceCheckpoint {
+    /// The last scanned block height, or `None` if nothing was scanned yet.
+    height: Option<u32>,
+    /// The owned records that were unspent as of the last scan, along with their serial numbers.
+    records: Vec<(Field<CurrentNetwork>, Record<CurrentNetwork, Ciphertext<CurrentNetwork>>)>,
+}
+
+impl BalanceCheckpoint {
+    /// Loads the checkpoint from the given path, or returns an empty checkpoint if it does not exist yet.
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        match serde_json::from_str(&std::fs::read_to_string(path)?) {
+            Ok(checkpoint) => Ok(checkpoint),
+            Err(error) => bail!("❌ The checkpoint '{}' is invalid - {error}", path.display()),
+        }
+    }
+
+    /// Saves the checkpoint to the given path.
+    fn save(&self, path: &Path) -> Result<()> {
+        Ok(std::fs::write(path, serde_json::to_string(self)?)?)
+    }
+
+    /// Returns the block height to resume scanning from, slightly below the last scanned block height, to pick up
+    /// any blocks that were reorganized.
+    fn start_height(&self) -> u32 {
+        self.height.map_or(0, |height| height.saturating_sub(Scan::REORG_OVERLAP))
+    }
+
+    /// Adds the records found by a scan up to the given height, skipping the records found by a previous scan.
+    fn extend(
+        &mut self,
+        height: u32,
+        records: Vec<(Field<CurrentNetwork>, Record<CurrentNetwork, Ciphertext<CurrentNetwork>>)>,
+    ) {
+        for (serial_number, record) in records {
+            if !self.records.iter().any(|(known, _)| *known == serial_number) {
+                self.records.push((serial_number, record));
+            }
+        }
+        self.height = Some(height);
+    }
+}
+
+impl Balance {
+    /// Sums the spendable records and the public balance of the account.
+    pub fn parse(self) -> Result<String> {
+        // Configure the TLS verification of the HTTPS endpoints, before any of them is reached.
+        self.tls.install()?;
+
+        let private_key = PrivateKey::<CurrentNetwork>::from_str(&self.private_key)?;
+        let view_key = ViewKey::try_from(private_key)?;
+        let address = view_key.to_address();
+
+        // Load the records of the previous scans, and the filter of the serial numbers spent in them.
+        let mut checkpoint = match &self.checkpoint {
+            Some(path) => BalanceCheckpoint::load(path)?,
+            None => BalanceCheckpoint::default(),
+        };
+        let mut spent_filter = match &self.spent_filter {
+            Some(path) => SpentFilter::load(path)?,
+            None => SpentFilter::new(),
+        };
+
+        // Scan the blocks since the checkpoint for the records of the account.
+        let latest_height = Developer::fetch_latest_height(&self.query)?;
+        let (start_height, end_height) = (checkpoint.start_height(), latest_height);
+        let candidates =
+            Scan::fetch_candidates(private_key, &view_key, &self.query, start_height, end_height, &mut spent_filter)?;
+        checkpoint.extend(latest_height, candidates);
+
+        // Keep the unspent records, as the records spent since the checkpoint are found by the spent filter.
+        let records = std::mem::take(&mut checkpoint.records).into_iter();
+        let records = records.map(|(serial_number, record)| (serial_number, (serial_number, record))).collect();
+        checkpoint.records = Scan::unspent(records, &spent_filter, &self.query)?;
+
+        // Record the unspent records and the spent serial numbers, for the next scan to resume from.
+        if let Some(path) = &self.checkpoint {
+            checkpoint.save(path)?;
+        }
+        if let Some(path) = &self.spent_filter {
+            spent_filter.save(path)?;
+        }
+
+        // Skip the records of other programs, which hold no microcredits.
+        let mut private = Vec::with_capacity(checkpoint.records.len());
+        for (_, record) in &checkpoint.records {
+            if let Ok(microcredits) = record.decrypt(&view_key)?.microcredits() {
+                private.push(microcredits);
+            }
+        }
+
+        // Fetch the public balance of the account, which is absent if it never held one.
+        let endpoint = format!("{}/testnet3/program/credits.aleo/mapping/account/{address}", self.query);
+        let public = match TlsOptions::http_agent().get(&endpoint).call() {
+            Ok(response) => Self::parse_public_balance(response.into_json()?)?,
+            Err(error) => bail!("❌ Unable to fetch the public balance: {}", TlsOptions::describe_error(error)),
+        };
+
+        Ok(Self::report(&private, public))
+    }
+
+    /// Parses the value of the `account` mapping, e.g. `100u64`, which is `None` if the account has no entry.
+    fn parse_public_balance(value: Option<String>) -> Result<u64> {
+        match value {
+            Some(value) => match value.strip_suffix("u64").map(u64::from_str) {
+                Some(Ok(balance)) => Ok(balance),
+                _ => bail!("❌ The public balance '{value}' is not a valid amount of microcredits"),
+            },
+            None => Ok(0),
+        }
+    }
+
+    /// Returns the report of the balance, given the amounts of the spendable records and the public balance.
+    fn report(private: &[u64], public: u64) -> String {
+        let private_total = private.iter().fold(0u64, |total, amount| total.saturating_add(*amount));
+        format!(
+            "🔒 Private balance: {} in {} spendable records\n🌐 Public balance: {}\n💰 Total balance: {}",
+            FeeDisplay::Both.format(private_total),
+            private.len(),
+            FeeDisplay::Both.format(public),
+            FeeDisplay::Both.format(private_total.saturating_add(public))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{developer::test_helpers::sample_ciphertext, Command, CLI};
+
+    use snarkvm::prelude::TestRng;
+
+    #[test]
+    fn clap_snarkos_balance() {
+        let arg_vec = vec!["snarkos", "developer", "balance", "--private-key", "PRIVATE_KEY", "--query", "QUERY"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Balance(balance)) = cli.command {
+            assert_eq!(balance.private_key, "PRIVATE_KEY");
+            assert_eq!(balance.query, "QUERY");
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure the checkpoint is resumed with the spent serial numbers of the previous scans.
+        let args = ["snarkos", "developer", "balance", "--private-key", "KEY", "--query", "QUERY"];
+        assert!(CLI::try_parse_from([&args[..], &["--checkpoint", "balance.json"]].concat()).is_err());
+        let arg_vec = [&args[..], &["--checkpoint", "balance.json", "--spent-filter", "spent.bin"]].concat();
+        if let Command::Developer(Developer::Balance(balance)) = CLI::parse_from(arg_vec).command {
+            assert_eq!(balance.checkpoint, Some(PathBuf::from("balance.json")));
+            assert_eq!(balance.spent_filter, Some(PathBuf::from("spent.bin")));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure the private key and query endpoint are required.
+        assert!(CLI::try_parse_from(vec!["snarkos", "developer", "balance", "--query", "QUERY"]).is_err());
+        assert!(CLI::try_parse_from(vec!["snarkos", "developer", "balance", "--private-key", "KEY"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_public_balance() {
+        assert_eq!(Balance::parse_public_balance(Some("1500000u64".to_string())).unwrap(), 1_500_000);
+        // Ensure an account without an entry in the mapping has no public balance.
+        assert_eq!(Balance::parse_public_balance(None).unwrap(), 0);
+        assert!(Balance::parse_public_balance(Some("1500000field".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_balance_report() {
+        let report = Balance::report(&[1_000_000, 250_000], 2_000_001);
+        assert_eq!(
+            report,
+            "🔒 Private balance: 1.250000 credits (1250000 microcredits) in 2 spendable records\n\
+             🌐 Public balance: 2.000001 credits (2000001 microcredits)\n\
+             💰 Total balance: 3.250001 credits (3250001 microcredits)"
+        );
+    }
+
+    #[test]
+    fn test_balance_checkpoint() {
+        let mut rng = TestRng::default();
+        let private_key = PrivateKey::<CurrentNetwork>::new(&mut rng).unwrap();
+        let records = (0..3u64).map(|i| (Field::from_u64(i), sample_ciphertext(&private_key, i, &mut rng)));
+        let records = records.collect::<Vec<_>>();
+
+        // Ensure a missing checkpoint scans from genesis.
+        let path = std::env::temp_dir().join(format!("snarkos_balance_checkpoint_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut checkpoint = BalanceCheckpoint::load(&path).unwrap();
+        assert_eq!(checkpoint.start_height(), 0);
+
+        // Ensure the records found again in the overlap of the next scan are not counted twice.
+        checkpoint.extend(100, records[..2].to_vec());
+        checkpoint.extend(150, records[1..].to_vec());
+        assert_eq!(checkpoint.records, records);
+        assert_eq!(checkpoint.start_height(), 150 - Scan::REORG_OVERLAP);
+
+        // Ensure the checkpoint persists, and a corrupted checkpoint is an error.
+        checkpoint.save(&path).unwrap();
+        let loaded = BalanceCheckpoint::load(&path).unwrap();
+        assert_eq!((loaded.height, loaded.records), (Some(150), records));
+        std::fs::write(&path, "invalid").unwrap();
+        assert!(BalanceCheckpoint::load(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
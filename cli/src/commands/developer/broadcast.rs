@@ -0,0 +1,436 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{
+    load_program,
+    Convert,
+    CurrentAleo,
+    CurrentNetwork,
+    Deploy,
+    DeployManifest,
+    DeployManifestEntry,
+    Developer,
+    ScheduledTransaction,
+};
+
+use snarkvm::prelude::{block::Transaction, Process, ProgramID};
+
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Broadcasts a stored transaction, or the transactions of a `developer deploy --output-dir` directory.
+///
+/// The transactions of a directory are broadcast one at a time, in the order of its `manifest.json`.
+/// As nodes accept each transaction on its own, a batch cannot be broadcast atomically. Instead, the
+/// first failure stops the batch, and the transactions that were and were not broadcast are reported.
+#[derive(Debug, Parser)]
+pub struct Broadcast {
+    /// The path to the stored transaction file, or to an output directory of `developer deploy`.
+    path: PathBuf,
+    /// The endpoint used to broadcast the transaction.
+    #[clap(short, long, required_unless_present = "verify_only")]
+    endpoint: Option<String>,
+    /// Verifies the proofs of the transaction locally, without broadcasting it. This is not the full
+    /// `check_transaction` of a node: whether the fee's state root is in the chain, and whether its records are
+    /// unspent, is not checked.
+    #[clap(long, conflicts_with = "endpoint")]
+    verify_only: bool,
+    /// The endpoint to load the programs that the verified transaction imports or calls from.
+    #[clap(short, long, requires = "verify_only")]
+    query: Option<String>,
+    /// The number of times a transient broadcast failure is retried, with exponential backoff.
+    #[clap(long, default_value_t = 0, requires = "endpoint")]
+    retries: u32,
+    /// Refuses to broadcast a scheduled transaction before its `not before` time, instead of waiting until then.
+    #[clap(long)]
+    no_wait: bool,
+}
+
+impl Broadcast {
+    /// Broadcasts the stored transaction, or verifies it if `--verify-only` is given.
+    pub fn parse(self) -> Result<String> {
+        // Determine if the transactions of an output directory should be broadcast as a batch.
+        if self.path.is_dir() {
+            ensure!(!self.verify_only, "❌ --verify-only expects a transaction file, not a directory");
+            return self.broadcast_batch();
+        }
+
+        // Read the stored transaction, along with its `not before` time if it is scheduled.
+        let contents = std::fs::read(&self.path)?;
+        let (transaction, not_before) = match serde_json::from_slice::<ScheduledTransaction>(&contents) {
+            Ok(scheduled) => (scheduled.transaction, Some(scheduled.not_before)),
+            Err(_) => (Convert::read_transaction(&contents)?, None),
+        };
+
+        // Determine if the transaction should only be verified.
+        if self.verify_only {
+            return Self::verify(&transaction, self.query.as_deref());
+        }
+
+        // Ensure a scheduled transaction is not broadcast before its `not before` time.
+        if let Some(not_before) = not_before {
+            let delay = Self::delay_until(not_before, SystemTime::now().duration_since(UNIX_EPOCH)?);
+            if !delay.is_zero() {
+                ensure!(
+                    !self.no_wait,
+                    "❌ Transaction {} may not be broadcast before {not_before} ({}s from now)",
+                    transaction.id(),
+                    delay.as_secs_f64().ceil()
+                );
+                println!("⏳ Waiting {}s to broadcast transaction {}...", delay.as_secs_f64().ceil(), transaction.id());
+                std::thread::sleep(delay);
+            }
+        }
+
+        // Determine the operation the transaction performs.
+        let operation = match &transaction {
+            Transaction::Deploy(_, _, deployment, _) => deployment.program_id().to_string(),
+            transaction => transaction.id().to_string(),
+        };
+
+        // Broadcast the transaction, retrying transient failures if retries are enabled.
+        Developer::handle_transaction(self.endpoint, false, None, transaction, operation, self.retries)
+    }
+
+    /// Broadcasts the transactions of the output directory in the order of its manifest, stopping at the first failure.
+    fn broadcast_batch(&self) -> Result<String> {
+        let endpoint = self.endpoint.clone().unwrap_or_default();
+        let manifest: DeployManifest =
+            serde_json::from_str(&std::fs::read_to_string(self.path.join(Deploy::OUTPUT_MANIFEST))?)?;
+        let program_ids = manifest.deployments.iter().map(|entry| entry.program_id.clone()).collect::<Vec<_>>();
+
+        for (index, entry) in manifest.deployments.iter().enumerate() {
+            // Read the transaction, ensuring it is the one recorded in the manifest, then broadcast it.
+            let result = self.read_batch_transaction(entry).and_then(|transaction| {
+                Developer::post_transaction(&endpoint, &transaction, &entry.transaction_id, self.retries)
+            });
+            if let Err(error) = result {
+                bail!(Self::batch_failure(&program_ids[..index], &entry.program_id, error, &program_ids[index + 1..]))
+            }
+            println!(
+                "✅ Successfully broadcast {} ('{}') to {endpoint}.",
+                entry.transaction_id,
+                entry.program_id.bold()
+            );
+        }
+        Ok(format!("✅ Broadcast all {} transactions of {}", program_ids.len(), self.path.display()))
+    }
+
+    /// Reads the transaction of the given manifest entry, ensuring it is the one recorded in the manifest.
+    fn read_batch_transaction(&self, entry: &DeployManifestEntry) -> Result<Transaction<CurrentNetwork>> {
+        let contents = std::fs::read(self.path.join(format!("{}.tx", entry.program_id)))?;
+        let transaction = Convert::read_transaction(&contents)?;
+        let transaction_id = transaction.id().to_string();
+        ensure!(
+            transaction_id == entry.transaction_id,
+            "the transaction file holds {transaction_id}, but the manifest lists {}",
+            entry.transaction_id
+        );
+        Ok(transaction)
+    }
+
+    /// Returns the report of a batch that stopped at the given program, listing the programs that were
+    /// broadcast before it, and the programs that were not submitted after it.
+    fn batch_failure(broadcast: &[String], failed: &str, error: anyhow::Error, not_submitted: &[String]) -> String {
+        let list = |program_ids: &[String]| match program_ids.is_empty() {
+            true => "none".to_string(),
+            false => program_ids.join(", "),
+        };
+        format!(
+            "❌ Failed to broadcast '{failed}': {error}\n  Broadcast: {}\n  Not submitted: {}",
+            list(broadcast),
+            list(not_submitted)
+        )
+    }
+
+    /// Returns the time remaining until the given UNIX timestamp (in seconds), from the current UNIX time.
+    fn delay_until(not_before: u64, now: Duration) -> Duration {
+        Duration::from_secs(not_before).saturating_sub(now)
+    }
+
+    /// Verifies the given transaction locally, loading the programs it imports or calls from the query endpoint.
+    fn verify(transaction: &Transaction<CurrentNetwork>, query: Option<&str>) -> Result<String> {
+        let process = Self::load_dependencies(transaction, query)?;
+        match Self::verify_proofs(&process, transaction) {
+            Ok(()) => Ok(format!("✅ Transaction {} is valid", transaction.id())),
+            Err(error) => bail!("❌ Transaction {} is invalid: {error}", transaction.id()),
+        }
+    }
+
+    /// Returns a process holding the programs the given transaction imports or calls, loaded from the query
+    /// endpoint. A query endpoint is only needed for programs other than `credits.aleo`.
+    pub(super) fn load_dependencies(
+        transaction: &Transaction<CurrentNetwork>,
+        query: Option<&str>,
+    ) -> Result<Process<CurrentNetwork>> {
+        let program_ids: Vec<ProgramID<CurrentNetwork>> = match transaction {
+            Transaction::Deploy(_, _, deployment, _) => deployment.program().imports().keys().copied().collect(),
+            Transaction::Execute(_, execution, _) => {
+                execution.transitions().map(|transition| *transition.program_id()).collect()
+            }
+            Transaction::Fee(..) => Vec::new(),
+        };
+        let mut process = Process::load()?;
+        for program_id in &program_ids {
+            match (process.contains_program(program_id), query) {
+                (true, _) => (),
+                (false, Some(query)) => load_program(query, &mut process, program_id)?,
+                (false, None) => bail!("❌ Please specify the endpoint to load '{program_id}' from with --query"),
+            }
+        }
+        Ok(process)
+    }
+
+    /// Verifies the proofs of the given transaction against the given process.
+    ///
+    /// Only the checks that do not depend on the state of the network are made: the deployment and its owner,
+    /// or the execution, and the fee. Whether the global state root the fee was proven against is in the chain,
+    /// and whether the records it spends are unspent, is left to the network.
+    pub(super) fn verify_proofs(
+        process: &Process<CurrentNetwork>,
+        transaction: &Transaction<CurrentNetwork>,
+    ) -> Result<()> {
+        match transaction {
+            Transaction::Deploy(_, owner, deployment, fee) => {
+                let deployment_id = deployment.to_deployment_id()?;
+                ensure!(owner.verify(deployment_id), "the program owner did not sign the deployment");
+                process.verify_deployment::<CurrentAleo, _>(deployment, &mut rand::thread_rng())?;
+                process.verify_fee(fee, deployment_id)
+            }
+            Transaction::Execute(_, execution, fee) => {
+                process.verify_execution(execution)?;
+                match fee {
+                    Some(fee) => process.verify_fee(fee, execution.to_execution_id()?),
+                    None => Ok(()),
+                }
+            }
+            Transaction::Fee(..) => {
+                bail!("a fee transaction is only created by the network, for a rejected transaction")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, CLI};
+
+    use crate::commands::developer::test_helpers::{mock_http_sequence, sample_deployment_transaction};
+
+    use snarkvm::prelude::{
+        store::{helpers::memory::ConsensusMemory, ConsensusStore},
+        Program,
+        TestRng,
+        VM,
+    };
+    use std::str::FromStr;
+
+    /// Starts a mock node that responds to each request with the next of the given status lines and bodies.
+    fn mock_node_sequence(responses: Vec<(&'static str, &'static str)>) -> String {
+        let responses = responses.into_iter().map(|(status, body)| (status, body.to_string())).collect();
+        mock_http_sequence(responses).0
+    }
+
+    #[test]
+    fn clap_snarkos_broadcast() {
+        let arg_vec = vec!["snarkos", "developer", "broadcast", "--endpoint", "ENDPOINT", "transaction.json"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Broadcast(broadcast)) = cli.command {
+            assert_eq!(broadcast.path, PathBuf::from("transaction.json"));
+            assert_eq!(broadcast.endpoint, Some("ENDPOINT".to_string()));
+            assert!(!broadcast.verify_only);
+            assert!(!broadcast.no_wait);
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure the endpoint is not required to verify the transaction.
+        let arg_vec = vec!["snarkos", "developer", "broadcast", "--verify-only", "transaction.json"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Broadcast(broadcast)) = cli.command {
+            assert!(broadcast.verify_only);
+            assert_eq!(broadcast.endpoint, None);
+            assert_eq!(broadcast.query, None);
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure the programs the verified transaction depends on can be loaded from a query endpoint.
+        let arg_vec =
+            vec!["snarkos", "developer", "broadcast", "--verify-only", "--query", "QUERY", "transaction.json"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Broadcast(broadcast)) = cli.command {
+            assert_eq!(broadcast.query, Some("QUERY".to_string()));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+        assert!(CLI::try_parse_from(vec![
+            "snarkos",
+            "developer",
+            "broadcast",
+            "--endpoint",
+            "ENDPOINT",
+            "--query",
+            "QUERY",
+            "transaction.json"
+        ])
+        .is_err());
+
+        // Ensure the endpoint is required to broadcast, and conflicts with `--verify-only`.
+        assert!(CLI::try_parse_from(vec!["snarkos", "developer", "broadcast", "transaction.json"]).is_err());
+        assert!(CLI::try_parse_from(vec![
+            "snarkos",
+            "developer",
+            "broadcast",
+            "--verify-only",
+            "--endpoint",
+            "ENDPOINT",
+            "transaction.json"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        let rng = &mut TestRng::default();
+        let transaction = sample_deployment_transaction();
+
+        // Ensure the transaction is valid, although a VM without the chain rejects its fee.
+        let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::open(None).unwrap();
+        assert!(VM::from(store).unwrap().check_transaction(&transaction, None).is_err());
+        let output = Broadcast::verify(&transaction, None).unwrap();
+        assert_eq!(output, format!("✅ Transaction {} is valid", transaction.id()));
+
+        // Ensure a transaction whose owner and fee are for another deployment is invalid.
+        let program = Program::<CurrentNetwork>::from_str(
+            r"
+program verify_other.aleo;
+
+function compute:
+    input r0 as u32.public;
+    output r0 as u32.public;",
+        )
+        .unwrap();
+        let deployment = Process::load().unwrap().deploy::<CurrentAleo, _>(&program, rng).unwrap();
+        let tampered = match &transaction {
+            Transaction::Deploy(_, owner, _, fee) => {
+                Transaction::from_deployment(*owner, deployment, fee.clone()).unwrap()
+            }
+            _ => panic!("Expected a deployment transaction"),
+        };
+        let error = Broadcast::verify(&tampered, None).unwrap_err().to_string();
+        assert!(error.starts_with(&format!("❌ Transaction {} is invalid", tampered.id())), "{error}");
+    }
+
+    #[test]
+    fn test_broadcast_batch_stops_at_first_failure() {
+        let directory = std::env::temp_dir().join(format!("snarkos-broadcast-batch-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        // Write a manifest of three deployments, the first of which has a corrupted transaction file.
+        let manifest = r#"{"deployments":[
+            {"program_id":"token.aleo","transaction_id":"at1token"},
+            {"program_id":"hello.aleo","transaction_id":"at1hello"},
+            {"program_id":"game.aleo","transaction_id":"at1game"}
+        ]}"#;
+        std::fs::write(directory.join(Deploy::OUTPUT_MANIFEST), manifest).unwrap();
+        std::fs::write(directory.join("token.aleo.tx"), "corrupted").unwrap();
+
+        // Ensure the batch stops at the first failure, without submitting the rest.
+        let broadcast = Broadcast {
+            path: directory.clone(),
+            endpoint: None,
+            verify_only: false,
+            query: None,
+            retries: 0,
+            no_wait: false,
+        };
+        let error = broadcast.parse().unwrap_err().to_string();
+        assert!(error.starts_with("❌ Failed to broadcast 'token.aleo'"), "{error}");
+        assert!(error.ends_with("Broadcast: none\n  Not submitted: hello.aleo, game.aleo"), "{error}");
+
+        // Ensure the report lists the partial success precisely.
+        let error = anyhow::anyhow!("rejected");
+        let report = Broadcast::batch_failure(&["token.aleo".to_string()], "hello.aleo", error, &[]);
+        assert_eq!(
+            report,
+            "❌ Failed to broadcast 'hello.aleo': rejected\n  Broadcast: token.aleo\n  Not submitted: none"
+        );
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_delay_until() {
+        let now = Duration::from_millis(1_700_000_000_250);
+        // Ensure a scheduled time in the past, or the present, allows an immediate broadcast.
+        assert_eq!(Broadcast::delay_until(1_699_999_000, now), Duration::ZERO);
+        assert_eq!(Broadcast::delay_until(1_700_000_000, now), Duration::ZERO);
+        // Ensure a scheduled time in the future defers the broadcast until then.
+        assert_eq!(Broadcast::delay_until(1_700_000_010, now), Duration::from_millis(9_750));
+    }
+
+    #[test]
+    fn test_broadcast_error_message() {
+        let cases = [
+            (r#"{"error":"fee too low"}"#, "fee too low"),
+            (r#"{"message":"program exists"}"#, "program exists"),
+            (r#""fee too low""#, "fee too low"),
+            ("Something went wrong: program exists", "program exists"),
+        ];
+        for (body, expected) in cases {
+            // Broadcast to a mock node that rejects the transaction.
+            let url = mock_node_sequence(vec![("500 Internal Server Error", body)]);
+            let endpoint = format!("{url}/testnet3/transaction/broadcast");
+            let error = ureq::post(&endpoint).send_string("{}").unwrap_err();
+
+            // Ensure the node's error message is surfaced verbatim.
+            let message = Developer::broadcast_error_message(error).unwrap();
+            assert_eq!(message, format!("(status code 500: {expected})"));
+        }
+    }
+
+    #[test]
+    fn test_post_transaction_retries() {
+        let transaction = serde_json::json!({});
+        let busy = ("503 Service Unavailable", "Something went wrong: the node is busy");
+        let exists = ("500 Internal Server Error", "Something went wrong: Transaction is already in the memory pool.");
+
+        // Ensure a transient failure is not retried by default.
+        let endpoint = mock_node_sequence(vec![busy]);
+        assert!(Developer::post_transaction(&endpoint, &transaction, "at1", 0).is_err());
+
+        // Ensure a retry that finds the transaction already exists succeeds.
+        let endpoint = mock_node_sequence(vec![busy, exists]);
+        Developer::post_transaction(&endpoint, &transaction, "at1", 2).unwrap();
+
+        // Ensure a first attempt that finds the transaction already exists still fails, as no earlier attempt was made.
+        let endpoint = mock_node_sequence(vec![exists]);
+        let error = Developer::post_transaction(&endpoint, &transaction, "at1", 2).unwrap_err();
+        assert!(error.to_string().contains("already in the memory pool"));
+
+        // Ensure a retry that succeeds is checked against the transaction ID.
+        let endpoint = mock_node_sequence(vec![busy, ("200 OK", "\"at1\"")]);
+        Developer::post_transaction(&endpoint, &transaction, "at1", 1).unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{CurrentAleo, CurrentNetwork};
+
+use snarkvm::{
+    package::Package,
+    prelude::{block::Deployment, FromBytes, Program, ProgramID, ToBytes},
+};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+/// A local cache of the deployments synthesized for packages, keyed by program ID.
+///
+/// Synthesizing the keys of each function dominates the time taken by `package.deploy`. A deployment bundles the
+/// verifying keys and certificates of those keys, so a cached deployment lets an unchanged program skip the synthesis.
+/// The proving keys themselves are not needed once the deployment is generated, and are not cached.
+pub struct ProvingCache;
+
+impl ProvingCache {
+    /// Returns the path of the cached deployment of the given program within the cache directory.
+    fn path(cache_dir: &Path, program_id: &ProgramID<CurrentNetwork>) -> PathBuf {
+        cache_dir.join(format!("{program_id}.deployment"))
+    }
+
+    /// Stores the given deployment in the cache directory, returning the path it was written to.
+    pub(super) fn store(cache_dir: &Path, deployment: &Deployment<CurrentNetwork>) -> Result<PathBuf> {
+        std::fs::create_dir_all(cache_dir)?;
+        let path = Self::path(cache_dir, deployment.program_id());
+        std::fs::write(&path, deployment.to_bytes_le()?)?;
+        Ok(path)
+    }
+
+    /// Loads the cached deployment of the given program, if one exists and was synthesized for the same program.
+    /// A deployment cached for an earlier revision of the program is stale, and is not returned.
+    pub(super) fn load(
+        cache_dir: &Path,
+        program: &Program<CurrentNetwork>,
+    ) -> Result<Option<Deployment<CurrentNetwork>>> {
+        let path = Self::path(cache_dir, program.id());
+        if !path.exists() {
+            return Ok(None);
+        }
+        let deployment = Deployment::<CurrentNetwork>::from_bytes_le(&std::fs::read(&path)?)?;
+        match deployment.program().to_bytes_le()? == program.to_bytes_le()? {
+            true => Ok(Some(deployment)),
+            false => {
+                println!("⚠️  The cached deployment of '{}' is stale, and will be regenerated", program.id());
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Synthesizes the keys of a package once, and caches its deployment for `developer deploy --proving-cache`.
+#[derive(Debug, Parser)]
+pub struct BuildCache {
+    /// A path to a directory containing a manifest file. Defaults to the current working directory.
+    #[clap(long)]
+    path: Option<PathBuf>,
+    /// The directory to cache the deployment in.
+    #[clap(long)]
+    proving_cache: PathBuf,
+}
+
+impl BuildCache {
+    /// Generates the deployment of the package, and stores it in the cache directory.
+    pub fn parse(self) -> Result<String> {
+        // Load the package.
+        let directory = match self.path {
+            Some(path) => path,
+            None => std::env::current_dir()?,
+        };
+        let package = Package::<CurrentNetwork>::open(&directory)?;
+
+        println!("📦 Synthesizing the keys of '{}'...", package.program_id().to_string().bold());
+
+        // Generate the deployment, and cache it.
+        let deployment = package.deploy::<CurrentAleo>(None)?;
+        let path = ProvingCache::store(&self.proving_cache, &deployment)?;
+        Ok(format!("✅ Cached the deployment of '{}' to {}", package.program_id(), path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, Developer, CLI};
+    use std::str::FromStr;
+
+    #[test]
+    fn clap_snarkos_build_cache() {
+        let arg_vec = vec!["snarkos", "developer", "build-cache", "--path", "hello", "--proving-cache", "cache"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::BuildCache(build_cache)) = cli.command {
+            assert_eq!(build_cache.path, Some(PathBuf::from("hello")));
+            assert_eq!(build_cache.proving_cache, PathBuf::from("cache"));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure the cache directory is required.
+        assert!(CLI::try_parse_from(vec!["snarkos", "developer", "build-cache"]).is_err());
+    }
+
+    #[test]
+    fn test_proving_cache_miss() {
+        let cache_dir = std::env::temp_dir().join(format!("snarkos_proving_cache_{}", std::process::id()));
+        let program = Program::<CurrentNetwork>::from_str(
+            "program hello.aleo;\n\nfunction main:\n    input r0 as u32.public;\n    output r0 as u32.public;\n",
+        )
+        .unwrap();
+
+        // Ensure a program without a cached deployment is a miss, even if the cache directory does not exist.
+        assert!(ProvingCache::load(&cache_dir, &program).unwrap().is_none());
+    }
+}
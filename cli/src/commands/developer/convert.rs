@@ -0,0 +1,117 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CurrentNetwork;
+
+use snarkvm::prelude::{block::Transaction, FromBytes, ToBytes};
+
+use anyhow::{bail, ensure, Result};
+use clap::{Parser, ValueEnum};
+use std::{path::PathBuf, str::FromStr};
+
+/// The serialization format of a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TransactionFormat {
+    /// The JSON representation of the transaction.
+    Json,
+    /// The little-endian byte representation of the transaction.
+    Bytes,
+}
+
+/// Converts a transaction between its serialization formats.
+#[derive(Debug, Parser)]
+pub struct Convert {
+    /// The path to the transaction file to convert.
+    #[clap(short, long)]
+    input: PathBuf,
+    /// The format to convert the transaction into.
+    #[clap(long, value_enum)]
+    to: TransactionFormat,
+    /// The path to write the converted transaction to.
+    #[clap(short, long)]
+    output: PathBuf,
+}
+
+impl Convert {
+    /// Converts the transaction in the input file into the requested format.
+    pub fn parse(self) -> Result<String> {
+        // Read the input file.
+        let contents = std::fs::read(&self.input)?;
+
+        // Deserialize the transaction.
+        let transaction = Self::read_transaction(&contents)?;
+
+        // Serialize the transaction in the requested format.
+        let converted = Self::write_transaction(&transaction, self.to)?;
+
+        // Ensure the converted transaction round-trips to the same transaction.
+        ensure!(
+            Self::read_transaction(&converted)? == transaction,
+            "The converted transaction does not match the original transaction"
+        );
+
+        // Write the converted transaction to the output file.
+        std::fs::write(&self.output, converted)?;
+
+        Ok(format!("✅ Converted transaction {} to {}", transaction.id(), self.output.display()))
+    }
+
+    /// Deserializes a transaction from either its byte or JSON representation.
+    pub(super) fn read_transaction(contents: &[u8]) -> Result<Transaction<CurrentNetwork>> {
+        // Attempt to deserialize the transaction from bytes.
+        if let Ok(transaction) = Transaction::<CurrentNetwork>::from_bytes_le(contents) {
+            return Ok(transaction);
+        }
+        // Otherwise, attempt to deserialize the transaction from JSON.
+        match std::str::from_utf8(contents).map(|string| Transaction::<CurrentNetwork>::from_str(string.trim())) {
+            Ok(Ok(transaction)) => Ok(transaction),
+            _ => bail!("❌ The input file does not contain a valid transaction"),
+        }
+    }
+
+    /// Serializes the transaction into the given format.
+    fn write_transaction(transaction: &Transaction<CurrentNetwork>, format: TransactionFormat) -> Result<Vec<u8>> {
+        match format {
+            TransactionFormat::Json => Ok(transaction.to_string().into_bytes()),
+            TransactionFormat::Bytes => transaction.to_bytes_le(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, Developer, CLI};
+
+    #[test]
+    fn clap_snarkos_convert() {
+        let arg_vec =
+            vec!["snarkos", "developer", "convert", "--input", "tx.json", "--to", "bytes", "--output", "tx.bytes"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Convert(convert)) = cli.command {
+            assert_eq!(convert.input, PathBuf::from("tx.json"));
+            assert_eq!(convert.to, TransactionFormat::Bytes);
+            assert_eq!(convert.output, PathBuf::from("tx.bytes"));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+    }
+
+    #[test]
+    fn test_read_invalid_transaction() {
+        assert!(Convert::read_transaction(b"not a transaction").is_err());
+        assert!(Convert::read_transaction(&[0u8; 32]).is_err());
+    }
+}
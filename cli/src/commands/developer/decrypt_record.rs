@@ -0,0 +1,113 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{CurrentNetwork, Developer};
+
+use snarkvm::prelude::{Ciphertext, Entry, Identifier, Literal, Plaintext, PrivateKey, Record, ViewKey};
+
+use anyhow::{ensure, Result};
+use clap::Parser;
+use std::{fmt::Write, str::FromStr};
+
+/// Decrypts a record with a private key, and prints its plaintext fields.
+#[derive(Debug, Parser)]
+pub struct DecryptRecord {
+    /// The private key used to decrypt the record.
+    #[clap(short, long)]
+    private_key: String,
+    /// The record ciphertext to decrypt.
+    #[clap(short, long)]
+    record: String,
+}
+
+impl DecryptRecord {
+    /// Decrypts the record, and returns its owner, balance, nonce, and plaintext.
+    pub fn parse(self) -> Result<String> {
+        // Retrieve the private key.
+        let private_key = PrivateKey::<CurrentNetwork>::from_str(&self.private_key)?;
+
+        // Ensure the private key owns the record, as decrypting with any other key does not reliably fail.
+        if self.record.starts_with("record1") {
+            let ciphertext = Record::<CurrentNetwork, Ciphertext<CurrentNetwork>>::from_str(&self.record)?;
+            ensure!(
+                ciphertext.is_owner(&ViewKey::try_from(&private_key)?),
+                "The given private key cannot decrypt the record, as it does not own it"
+            );
+        }
+
+        // Decrypt the record.
+        let record = Developer::parse_record(&private_key, &self.record)?;
+
+        // Retrieve the balance, if the record holds credits.
+        let balance = match record.data().get(&Identifier::from_str("microcredits")?) {
+            Some(Entry::Private(Plaintext::Literal(Literal::U64(amount), _)))
+            | Some(Entry::Public(Plaintext::Literal(Literal::U64(amount), _))) => format!("{} microcredits", **amount),
+            _ => "none".to_string(),
+        };
+
+        let mut output = String::new();
+        writeln!(output, "owner: {}", record.owner())?;
+        writeln!(output, "balance: {balance}")?;
+        writeln!(output, "nonce: {}", record.nonce())?;
+        write!(output, "\n{record}")?;
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{developer::test_helpers::sample_ciphertext, Command, CLI};
+
+    use snarkvm::prelude::TestRng;
+
+    #[test]
+    fn test_decrypt_record() {
+        let mut rng = TestRng::default();
+
+        let private_key = PrivateKey::<CurrentNetwork>::new(&mut rng).unwrap();
+        let ciphertext = sample_ciphertext(&private_key, 1_500_000, &mut rng);
+        let record = ciphertext.decrypt(&ViewKey::try_from(&private_key).unwrap()).unwrap();
+
+        let decrypt = DecryptRecord { private_key: private_key.to_string(), record: ciphertext.to_string() };
+        let output = decrypt.parse().unwrap();
+
+        assert!(output.contains(&format!("owner: {}", record.owner())));
+        assert!(output.contains("balance: 1500000 microcredits"));
+        assert!(output.contains(&format!("nonce: {}", record.nonce())));
+        assert!(output.ends_with(&record.to_string()));
+
+        // Ensure a private key that does not own the record is rejected.
+        let incorrect_private_key = PrivateKey::<CurrentNetwork>::new(&mut rng).unwrap();
+        let decrypt = DecryptRecord { private_key: incorrect_private_key.to_string(), record: ciphertext.to_string() };
+        assert!(decrypt.parse().is_err());
+    }
+
+    #[test]
+    fn clap_snarkos_decrypt_record() {
+        let arg_vec = vec!["snarkos", "developer", "decrypt-record", "--private-key", "PRIVATE_KEY", "-r", "RECORD"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::DecryptRecord(decrypt)) = cli.command {
+            assert_eq!(decrypt.private_key, "PRIVATE_KEY");
+            assert_eq!(decrypt.record, "RECORD");
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure the record is required.
+        let arg_vec = vec!["snarkos", "developer", "decrypt-record", "--private-key", "PRIVATE_KEY"];
+        assert!(CLI::try_parse_from(arg_vec).is_err());
+    }
+}
@@ -12,151 +12,2516 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{CurrentAleo, CurrentNetwork, Developer};
+use super::{
+    Broadcast,
+    ConfirmationBackoff,
+    CurrentAleo,
+    CurrentNetwork,
+    Developer,
+    GenesisConfig,
+    LocalChain,
+    OfflineState,
+    ProvingCache,
+    QrCode,
+    Registry,
+    Stats,
+    StorePath,
+    TlsOptions,
+};
 
 use snarkvm::{
     console::program::ProgramOwner,
     prelude::{
-        block::Transaction,
+        block::{Block, Deployment, Transaction},
         deployment_cost,
         query::Query,
         store::{helpers::memory::ConsensusMemory, ConsensusStore},
+        Address,
+        Field,
+        Identifier,
+        Network,
         PrivateKey,
+        Program,
         ProgramID,
+        ToBytes,
+        VerifyingKey,
         VM,
     },
 };
 
-use anyhow::{anyhow, bail, Result};
-use clap::Parser;
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::{Parser, ValueEnum};
 use colored::Colorize;
-use std::str::FromStr;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
 
-/// Deploys an Aleo program.
-#[derive(Debug, Parser)]
-pub struct Deploy {
-    /// The name of the program to deploy.
-    program_id: ProgramID<CurrentNetwork>,
-    /// A path to a directory containing a manifest file. Defaults to the current working directory.
-    #[clap(long)]
-    path: Option<String>,
+/// A stored deployment transaction, bundled with the verifying keys of the deployed functions.
+#[derive(Serialize, Deserialize)]
+pub struct DeploymentBundle {
+    /// The deployment transaction.
+    pub transaction: Transaction<CurrentNetwork>,
+    /// The verifying keys produced during the deployment, along with their function names.
+    pub verifying_keys: Vec<(Identifier<CurrentNetwork>, VerifyingKey<CurrentNetwork>)>,
+}
+
+/// A stored transaction that may not be broadcast before the given time, for time-coordinated launches.
+#[derive(Serialize, Deserialize)]
+pub struct ScheduledTransaction {
+    /// The earliest time, as a UNIX timestamp in seconds, at which the transaction may be broadcast.
+    pub not_before: u64,
+    /// The transaction.
+    pub transaction: Transaction<CurrentNetwork>,
+}
+
+/// The manifest of the deployment transactions written to an output directory, in the order they were deployed.
+#[derive(Default, Serialize, Deserialize)]
+pub struct DeployManifest {
+    /// The deployed programs, along with the IDs of their transactions.
+    pub deployments: Vec<DeployManifestEntry>,
+}
+
+/// An entry in the manifest of an output directory.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeployManifestEntry {
+    /// The ID of the deployed program.
+    pub program_id: String,
+    /// The ID of the deployment transaction.
+    pub transaction_id: String,
+}
+
+/// A deployment without its owner signature or fee, to be completed by a separate fee service.
+#[derive(Serialize, Deserialize)]
+pub struct UnsignedDeployment {
+    /// The ID of the program being deployed.
+    pub program_id: ProgramID<CurrentNetwork>,
+    /// The address of the program owner.
+    pub owner: Address<CurrentNetwork>,
+    /// The ID of the deployment, which the owner signature and fee commit to.
+    pub deployment_id: Field<CurrentNetwork>,
+    /// The deployment.
+    pub deployment: Deployment<CurrentNetwork>,
+}
+
+/// The reference of an unsigned deployment, with the fields of [`UnsignedDeployment`] that a signer needs to
+/// produce the owner signature, compact enough for a QR code.
+#[derive(Serialize, Deserialize)]
+pub struct UnsignedDeploymentReference {
+    /// The ID of the program being deployed.
+    pub program_id: ProgramID<CurrentNetwork>,
+    /// The address of the program owner.
+    pub owner: Address<CurrentNetwork>,
+    /// The ID of the deployment, which the owner signature commits to.
+    pub deployment_id: Field<CurrentNetwork>,
+}
+
+/// The timing and resource metrics of the phases of a deployment, for diagnosing slow deployments.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DeploymentProfile {
+    /// The ID of the program being deployed.
+    pub program_id: String,
+    /// The profiled phases, in the order they ran.
+    pub phases: Vec<PhaseProfile>,
+}
+
+/// The timing and resource metrics of a single deployment phase.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PhaseProfile {
+    /// The name of the phase.
+    pub name: String,
+    /// The wall-clock duration of the phase, in milliseconds.
+    pub duration_ms: u128,
+    /// The peak resident memory of the process at the end of the phase, in kilobytes, if obtainable.
+    pub peak_memory_kb: Option<u64>,
+}
+
+impl DeploymentProfile {
+    /// Runs the given phase, recording its duration and the memory high-water mark once it completes.
+    fn phase<T>(&mut self, name: &str, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        let timer = Instant::now();
+        let output = f()?;
+        let duration_ms = timer.elapsed().as_millis();
+        self.phases.push(PhaseProfile { name: name.to_string(), duration_ms, peak_memory_kb: Self::peak_memory_kb() });
+        Ok(output)
+    }
+
+    /// Returns the time spent proving the deployment and its fee, in milliseconds.
+    fn proving_ms(&self) -> u128 {
+        let is_proving = |phase: &&PhaseProfile| phase.name == "deploy" || phase.name == "execute_fee_raw";
+        self.phases.iter().filter(is_proving).map(|phase| phase.duration_ms).sum()
+    }
+
+    /// Returns the peak resident memory of the process in kilobytes, where the platform exposes it.
+    fn peak_memory_kb() -> Option<u64> {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+        line.trim_start_matches("VmHWM:").trim().trim_end_matches("kB").trim().parse().ok()
+    }
+}
+
+/// The unit in which the fees of a deployment are displayed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum FeeDisplay {
+    /// Displays the fees in microcredits.
+    Micro,
+    /// Displays the fees in credits.
+    Credits,
+    /// Displays the fees in credits, followed by the exact amount in microcredits.
+    Both,
+}
+
+impl FeeDisplay {
+    /// The number of microcredits in a credit.
+    const MICROCREDITS_PER_CREDIT: u64 = 1_000_000;
+
+    /// Formats the given amount of microcredits. Credits are formatted exactly, without a lossy conversion.
+    pub(super) fn format(&self, microcredits: u64) -> String {
+        let credits = format!(
+            "{}.{:06} credits",
+            microcredits / Self::MICROCREDITS_PER_CREDIT,
+            microcredits % Self::MICROCREDITS_PER_CREDIT
+        );
+        match self {
+            Self::Micro => format!("{microcredits} microcredits"),
+            Self::Credits => credits,
+            Self::Both => format!("{credits} ({microcredits} microcredits)"),
+        }
+    }
+}
+
+/// A milestone of a deployment, posted as JSON to the URL given with `--webhook`.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct DeploymentEvent {
+    /// The milestone, one of `proving_started`, `proving_complete`, `broadcast_submitted`, or `confirmed`.
+    pub event: &'static str,
+    /// The ID of the deployed program.
+    pub program_id: String,
+    /// The ID of the deployment, once it is generated.
+    pub deployment_id: Option<String>,
+    /// The ID of the deployment transaction, once it is generated.
+    pub transaction_id: Option<String>,
+    /// The total fee of the deployment in microcredits, once it is known.
+    pub fee: Option<u64>,
+}
+
+impl DeploymentEvent {
+    /// The time a webhook has to respond to an event, before the deployment continues without it.
+    const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Posts the event to the given webhook, if any. A failure is only logged, as it must not abort the deployment.
+    fn notify(&self, webhook: &Option<String>) {
+        if let Some(url) = webhook {
+            self.post(url, Self::WEBHOOK_TIMEOUT);
+        }
+    }
+
+    /// Posts the event to the given URL, giving up once the timeout elapses.
+    fn post(&self, url: &str, timeout: Duration) {
+        let timer = Instant::now();
+        if let Err(error) = TlsOptions::http_agent().post(url).timeout(timeout).send_json(self) {
+            match timer.elapsed() >= timeout {
+                true => println!(
+                    "⚠️  The webhook did not respond to the '{}' event within {}ms, continuing without it",
+                    self.event,
+                    timeout.as_millis()
+                ),
+                false => println!("⚠️  Unable to post the '{}' event to the webhook: {error}", self.event),
+            }
+        }
+    }
+}
+
+/// The query endpoints of a deployment. Each request tries them in order, starting from the endpoint that served
+/// the last request, and fails over to the next endpoint when one is unreachable or errors.
+#[derive(Debug)]
+struct QueryEndpoints {
+    /// The endpoints, in order of preference.
+    endpoints: Vec<String>,
+    /// The index of the endpoint that served the last request.
+    current: AtomicUsize,
+}
+
+impl QueryEndpoints {
+    /// Initializes the query endpoints, in order of preference.
+    fn new(endpoints: Vec<String>) -> Self {
+        Self { endpoints, current: AtomicUsize::new(0) }
+    }
+
+    /// Returns the endpoint that served the last request, or the first endpoint if none was served yet.
+    fn current(&self) -> &str {
+        &self.endpoints[self.current.load(Ordering::SeqCst)]
+    }
+
+    /// Sends the given request to the endpoints in order, starting from the current one, until one serves it,
+    /// logging each failover. Returns the error of the last endpoint if none serves it.
+    fn request<T>(&self, mut request: impl FnMut(&str) -> Result<T>) -> Result<T> {
+        let start = self.current.load(Ordering::SeqCst);
+        let mut last_error = None;
+        for offset in 0..self.endpoints.len() {
+            let index = (start + offset) % self.endpoints.len();
+            let endpoint = &self.endpoints[index];
+            match request(endpoint) {
+                Ok(output) => {
+                    self.current.store(index, Ordering::SeqCst);
+                    return Ok(output);
+                }
+                Err(error) => {
+                    if offset + 1 < self.endpoints.len() {
+                        let next = &self.endpoints[(index + 1) % self.endpoints.len()];
+                        println!("⚠️  The query endpoint {endpoint} failed ({error}), failing over to {next}");
+                    }
+                    last_error = Some(error);
+                }
+            }
+        }
+        match (last_error, self.endpoints.len()) {
+            (Some(error), 1) => Err(error),
+            (Some(error), _) => bail!("{error} (all of the query endpoints failed: {})", self.endpoints.join(", ")),
+            (None, _) => bail!("❌ Please specify the endpoint to query node state from with --query"),
+        }
+    }
+}
+
+/// The congestion of the network, as observed from the memory pool and the latest block of a node.
+#[derive(Debug, PartialEq, Eq)]
+pub struct NetworkConditions {
+    /// The number of unconfirmed transactions in the memory pool.
+    pub pending_transactions: usize,
+    /// The number of transactions in the latest block.
+    pub latest_block_transactions: usize,
+}
+
+impl NetworkConditions {
+    /// Fetches the current conditions of the network from the given endpoint.
+    fn fetch(endpoint: &str) -> Result<Self> {
+        let pending: Vec<Transaction<CurrentNetwork>> =
+            Self::get(&format!("{endpoint}/testnet3/memoryPool/transactions"))?;
+        let block: Block<CurrentNetwork> = Self::get(&format!("{endpoint}/testnet3/latest/block"))?;
+        Ok(Self { pending_transactions: pending.len(), latest_block_transactions: block.transactions().len() })
+    }
+
+    /// Fetches the JSON response of the given URL.
+    fn get<T: DeserializeOwned>(url: &str) -> Result<T> {
+        match TlsOptions::http_agent().get(url).call() {
+            Ok(response) => response.into_json().map_err(|err| err.into()),
+            Err(ureq::Error::Status(_status, response)) => {
+                bail!(response.into_string().unwrap_or("Response too large!".to_owned()))
+            }
+            Err(err) => bail!(TlsOptions::describe_error(err)),
+        }
+    }
+
+    /// Reports whether a deployment with the given priority fee would currently be accepted, with a warning if
+    /// the memory pool holds more transactions than a block includes, and no priority fee is paid to be preferred.
+    /// The priority fees paid by the pending transactions are not served by a node, so only the backlog is weighed.
+    fn fee_report(&self, priority_fee: u64) -> String {
+        let Self { pending_transactions, latest_block_transactions } = self;
+        let mut report = format!(
+            "✅ The fee covers the minimum deployment cost, so it would currently be accepted ({pending_transactions} pending transactions, {latest_block_transactions} in the latest block)"
+        );
+        if priority_fee == 0 && pending_transactions > latest_block_transactions {
+            report.push_str(
+                "\n⚠️  The memory pool holds more transactions than the latest block included, so a deployment without a priority fee may wait several blocks. Consider raising --fee.",
+            );
+        }
+        report
+    }
+}
+
+/// The thresholds of the local mempool admission check, mirroring the checks a node makes before
+/// admitting a transaction into its memory pool.
+#[derive(Clone, Debug, Parser)]
+pub struct MempoolThresholds {
+    /// The maximum size in bytes of a transaction admitted into the mempool.
+    #[clap(long = "mempool-max-size", default_value_t = 128 * 1024 * 1024)]
+    max_size: usize,
+    /// The minimum priority fee in microcredits of a transaction admitted into the mempool.
+    #[clap(long = "mempool-min-priority-fee", default_value_t = 0)]
+    min_priority_fee: u64,
+}
+
+impl MempoolThresholds {
+    /// Returns the reasons a transaction of the given size and priority fee would be rejected, if any.
+    fn rejections(&self, size: usize, priority_fee: u64) -> Vec<String> {
+        let mut rejections = Vec::new();
+        if size > self.max_size {
+            rejections.push(format!("the size of {size} bytes exceeds the limit of {} bytes", self.max_size));
+        }
+        if priority_fee < self.min_priority_fee {
+            rejections.push(format!(
+                "the priority fee of {priority_fee} microcredits is below the minimum of {} microcredits",
+                self.min_priority_fee
+            ));
+        }
+        rejections
+    }
+}
+
+/// The account that signs the deployment and pays its fee, given as a private key or derived from a mnemonic.
+#[derive(Clone, Debug, Parser)]
+pub struct AccountOptions {
     /// The private key used to generate the deployment.
-    #[clap(short, long)]
-    private_key: String,
-    /// The endpoint to query node state from.
-    #[clap(short, long)]
-    query: String,
-    /// The priority fee in microcredits.
-    #[clap(short, long)]
-    fee: u64,
-    /// The record to spend the fee from.
-    #[clap(short, long)]
-    record: String,
-    /// The endpoint used to broadcast the generated transaction.
+    #[clap(short, long, required_unless_present_any = ["mnemonic", "mnemonic_file"])]
+    private_key: Option<String>,
+    /// The BIP39 mnemonic to derive the private key from, instead of `--private-key`, along the SLIP-10 path
+    /// `m/44'/683'/<index>'/0'` of the Aleo wallets.
+    #[clap(long, conflicts_with_all = ["private_key", "mnemonic_file"])]
+    mnemonic: Option<String>,
+    /// A path to a file containing the BIP39 mnemonic to derive the private key from.
+    #[clap(long, conflicts_with = "private_key")]
+    mnemonic_file: Option<PathBuf>,
+    /// The index of the account derived from the mnemonic, hardened in the derivation path.
+    #[clap(long, default_value_t = 0)]
+    derivation_index: u32,
+}
+
+/// The endpoints a deployment reads the node state from and broadcasts to, or the offline and local sources
+/// that replace them.
+#[derive(Clone, Debug, Parser)]
+pub struct EndpointOptions {
+    /// The endpoint to query node state from, e.g. a read replica of the broadcast endpoint. May be repeated, or
+    /// given as a comma-separated list, for each request to fail over to the next endpoint if one is unreachable
+    /// or errors. Falls back to `SNARKOS_QUERY`. Required, unless a state file is given.
+    #[clap(short, long, value_delimiter = ',')]
+    query: Vec<String>,
+    /// Reads the node state from a file written by `developer export-state`, to deploy without network access.
+    /// The checks that need the network, e.g. of the imports and of an existing deployment, are skipped.
+    #[clap(long, conflicts_with_all = ["query", "broadcast"])]
+    state_file: Option<PathBuf>,
+    /// Deploys to a chain run in-process from the given genesis configuration (JSON), instead of to a network.
+    /// The chain is discarded after the deployment is accepted, unless a ledger path is given, and the network is
+    /// never contacted.
+    #[clap(long, conflicts_with_all = ["query", "broadcast", "state_file"])]
+    local_genesis: Option<PathBuf>,
+    /// A directory to persist the local chain to, which is created if it is missing. A chain already persisted there
+    /// is resumed, so repeated deploys build on the same chain, and the balances of the genesis are not minted again.
+    #[clap(long, requires = "local_genesis")]
+    ledger_path: Option<PathBuf>,
+    /// The maximum age in seconds of the state file, beyond which it is considered stale.
+    #[clap(long, default_value_t = 3600, requires = "state_file")]
+    max_state_age: u64,
+    /// The number of blocks the query endpoint may lag behind the broadcast endpoint before a warning is shown.
+    #[clap(long, default_value_t = 10)]
+    max_replica_lag: u32,
+    /// Skips checking that the query endpoint is on the same network as the deployment, by its genesis block.
+    #[clap(long)]
+    skip_network_check: bool,
+    /// The endpoint used to broadcast the generated transaction, and only to broadcast it.
+    /// Falls back to `SNARKOS_BROADCAST`, unless `--dry-run` is given.
     #[clap(short, long, conflicts_with = "dry_run")]
     broadcast: Option<String>,
+}
+
+/// The fee of a deployment, and the record it is spent from.
+#[derive(Clone, Debug, Parser)]
+pub struct FeeOptions {
+    /// The priority fee in microcredits. Falls back to `SNARKOS_FEE`.
+    #[clap(short, long)]
+    fee: Option<u64>,
+    /// The unit in which the fees are displayed. The fees are always computed in microcredits.
+    #[clap(long, alias = "fee-currency", value_enum, default_value_t = FeeDisplay::Micro)]
+    fee_display: FeeDisplay,
+    /// The record to spend the fee from. It is needed even for a free deployment, as the fee transition of every
+    /// deployment spends a record, but none of its balance is paid if the total fee is zero.
+    #[clap(short, long)]
+    record: Option<String>,
+    /// Skips checking that the fee record is unspent before generating the deployment.
+    #[clap(long)]
+    skip_spent_check: bool,
+}
+
+/// The deployment of the missing imports of a program, before the program.
+#[derive(Clone, Debug, Parser)]
+pub struct ImportOptions {
+    /// Deploys the imports of the program that are not deployed yet, including those imported indirectly, before
+    /// the program. The imports are read from the `imports` directory of the package, and deployed in the order of
+    /// their dependencies. Each broadcast import is confirmed before the deployments that import it.
+    #[clap(long, conflicts_with_all = ["store", "unsigned_output", "state_file", "local_genesis"])]
+    deploy_imports: bool,
+    /// The record to spend the fee of an import deployed by `--deploy-imports` from, given as `PROGRAM_ID=RECORD`.
+    /// May be repeated, once per import that is not deployed yet.
+    #[clap(long = "import-record", value_name = "PROGRAM_ID=RECORD", requires = "deploy_imports")]
+    import_records: Vec<String>,
+    /// The maximum number of imports deployed by `--deploy-imports` that are proven and submitted concurrently.
+    /// Only imports that do not import one another are deployed together.
+    #[clap(long, default_value_t = 1, requires = "deploy_imports")]
+    parallel_deploys: usize,
+}
+
+/// What a deployment produces, other than a broadcast: a dry run, a stored or unsigned transaction, or a
+/// format check.
+#[derive(Clone, Debug, Parser)]
+pub struct OutputOptions {
     /// Performs a dry-run of transaction generation.
     #[clap(short, long, conflicts_with = "broadcast")]
     dry_run: bool,
-    /// Store generated deployment transaction to a local file.
+    /// Writes the dry-run transaction to a local file for inspection, without staging it for broadcast.
+    #[clap(long, requires = "dry_run")]
+    dry_run_output: Option<String>,
+    /// Checks whether the dry-run transaction would be admitted into the mempool of a node.
+    #[clap(long, requires = "dry_run")]
+    simulate_mempool: bool,
+    /// Validates the transaction as `--dry-run --simulate-mempool` does, and only broadcasts it if it passes.
+    /// Requires a broadcast endpoint.
+    #[clap(long)]
+    validate_first: bool,
+    /// Store generated deployment transaction to a local file, or to S3-compatible storage given an `s3://bucket/key`
+    /// URI, using the `AWS_*` credentials and endpoint from the environment.
     #[clap(long)]
     store: Option<String>,
+    /// Embeds the verifying keys of the deployment in the stored file, for auditing.
+    #[clap(long, requires = "store")]
+    include_vks: bool,
+    /// Stores the transaction with the UNIX timestamp (in seconds) before which `developer broadcast` refuses to
+    /// broadcast it, deferring the broadcast of a scheduled deployment.
+    #[clap(long, requires = "store", conflicts_with = "include_vks")]
+    not_before: Option<u64>,
+    /// Writes the deployment transaction to `<program_id>.tx` in the given directory, and records it in the
+    /// directory's `manifest.json`, which lists the deployments in the order they were made.
+    #[clap(long)]
+    output_dir: Option<PathBuf>,
+    /// Overwrites an existing transaction file of the program in the output directory.
+    #[clap(long, requires = "output_dir")]
+    overwrite: bool,
+    /// Checks that the program is well-formed, without generating the deployment.
+    #[clap(long)]
+    format_check: bool,
+    /// Writes the unsigned deployment to a local file, so that a separate service can attach the fee.
+    #[clap(long)]
+    unsigned_output: Option<String>,
+    /// Renders the reference of the unsigned deployment as QR codes for a mobile signer, to the terminal if the
+    /// path is `-`, or otherwise to an SVG image.
+    #[clap(long, requires = "unsigned_output")]
+    qr: Option<String>,
+}
+
+/// The reports of a deployment, written locally or posted to a webhook.
+#[derive(Clone, Debug, Parser)]
+pub struct ReportOptions {
+    /// Prints the verifying-key ID of each function in the deployment.
+    #[clap(long)]
+    print_vk_ids: bool,
+    /// Writes the per-phase durations and memory high-water marks of the deployment to a local JSON file.
+    #[clap(long)]
+    profile: Option<String>,
+    /// Appends the proving duration of the deployment to a local stats file, summarized by `developer stats`.
+    #[clap(long)]
+    stats_file: Option<PathBuf>,
+    /// A URL to post a JSON event to when proving starts and completes, and when the transaction is broadcast and
+    /// confirmed. A failed post is logged, without aborting the deployment.
+    #[clap(long)]
+    webhook: Option<String>,
+}
+
+impl AccountOptions {
+    /// Returns the private key, parsed from `--private-key` or derived from the mnemonic.
+    fn private_key(&self) -> Result<PrivateKey<CurrentNetwork>> {
+        match (&self.private_key, &self.mnemonic, &self.mnemonic_file) {
+            (Some(private_key), _, _) => PrivateKey::from_str(private_key),
+            (None, Some(mnemonic), _) => Developer::derive_private_key(mnemonic, self.derivation_index),
+            (None, None, Some(path)) => {
+                Developer::derive_private_key(&std::fs::read_to_string(path)?, self.derivation_index)
+            }
+            (None, None, None) => {
+                bail!("❌ Please specify a private key with --private-key, --mnemonic, or --mnemonic-file")
+            }
+        }
+    }
+}
+
+impl OutputOptions {
+    /// Returns `true` if an action other than a broadcast is given.
+    fn has_local_action(&self) -> bool {
+        self.dry_run
+            || self.store.is_some()
+            || self.output_dir.is_some()
+            || self.unsigned_output.is_some()
+            || self.format_check
+    }
+}
+
+/// Deploys an Aleo program.
+///
+/// The `--query`, `--broadcast`, and `--fee` flags fall back to the `SNARKOS_QUERY`, `SNARKOS_BROADCAST`,
+/// and `SNARKOS_FEE` environment variables when omitted. An explicit flag always takes precedence.
+/// `SNARKOS_BROADCAST` is ignored with `--dry-run`, and both endpoints are ignored with `--state-file` and
+/// `--local-genesis`.
+/// If no fee is given, the `fee` field of the package manifest (`program.json`) is used, if present.
+/// Likewise, if no program ID is given, the `program` field of the package manifest is used.
+///
+/// All node state is read from the `--query` endpoint, which may be a read replica, and only the final
+/// submission of the transaction is sent to the `--broadcast` endpoint. With `--state-file`, the state is
+/// instead read from a file written by `developer export-state`, and the network is never contacted.
+#[derive(Clone, Debug, Parser)]
+pub struct Deploy {
+    /// The name of the program to deploy. Defaults to the program declared in the package manifest.
+    program_id: Option<ProgramID<CurrentNetwork>>,
+    /// A path to a directory containing a manifest file. Defaults to the current working directory.
+    #[clap(long)]
+    path: Option<String>,
+    /// A program registry to fetch the program and its imports from, instead of a local package. The sources are
+    /// verified against their digests, and staged as a package in the temporary directory, before proving.
+    #[clap(long, conflicts_with = "path", requires_all = ["program_id", "registry_version"])]
+    registry: Option<String>,
+    /// The version of the program to fetch from the registry.
+    #[clap(long, requires = "registry")]
+    registry_version: Option<String>,
+    /// The account of the deployment.
+    #[clap(flatten)]
+    account: AccountOptions,
+    /// The endpoints to query the node state from and to broadcast to.
+    #[clap(flatten)]
+    endpoints: EndpointOptions,
+    /// The fee of the deployment.
+    #[clap(flatten)]
+    fees: FeeOptions,
+    /// The block height by which the transaction must be included, after which the network drops it.
+    /// Note: Only supported on networks whose transactions can expire, which excludes the current network.
+    #[clap(long)]
+    valid_until: Option<u32>,
+    /// Overrides a program constant at compile time, given as `KEY=VALUE`. May be repeated.
+    /// Note: Aleo instructions do not declare constants, so every override is currently rejected as unknown.
+    #[clap(long = "define", value_name = "KEY=VALUE")]
+    defines: Vec<String>,
+    /// The deployment of the missing imports.
+    #[clap(flatten)]
+    imports: ImportOptions,
+    /// Whether this is the deployment of an import by `--deploy-imports`, whose own imports are deployed before it.
+    #[clap(skip)]
+    is_import: bool,
+    /// The maximum size of the compiled program in bytes, checked before proving. Zero disables the check.
+    #[clap(long, default_value_t = 0)]
+    max_program_size: usize,
+    /// Waits for the broadcast transaction to be confirmed, polling the query endpoint with exponential backoff.
+    /// Requires a broadcast endpoint.
+    #[clap(long)]
+    wait_for_confirmation: bool,
+    /// The backoff used to poll for the confirmation.
+    #[clap(flatten)]
+    confirmation: ConfirmationBackoff,
+    /// The reports of the deployment.
+    #[clap(flatten)]
+    report: ReportOptions,
+    /// Redeploys a program that is already deployed, on test networks that permit redeployment.
+    #[clap(long)]
+    redeploy: bool,
+    /// The outputs of the deployment, other than a broadcast.
+    #[clap(flatten)]
+    output: OutputOptions,
+    /// The thresholds of the mempool admission check.
+    #[clap(flatten)]
+    mempool: MempoolThresholds,
+    /// The TLS verification of the HTTPS query and broadcast endpoints.
+    #[clap(flatten)]
+    tls: TlsOptions,
+    /// A directory of deployments cached by `developer build-cache`, reused if the program is unchanged.
+    /// On a miss, the generated deployment is cached in it for subsequent deploys.
+    #[clap(long)]
+    proving_cache: Option<PathBuf>,
 }
 
 impl Deploy {
+    /// The environment variable the `--broadcast` flag falls back to.
+    const BROADCAST_ENV: &str = "SNARKOS_BROADCAST";
+    /// The environment variable the `--fee` flag falls back to.
+    const FEE_ENV: &str = "SNARKOS_FEE";
+    /// The name of the manifest file in the output directory.
+    pub(super) const OUTPUT_MANIFEST: &str = "manifest.json";
+    /// The environment variable the `--query` flag falls back to.
+    const QUERY_ENV: &str = "SNARKOS_QUERY";
+
     /// Deploys an Aleo program.
-    pub fn parse(self) -> Result<String> {
+    pub fn parse(mut self) -> Result<String> {
+        // Configure the TLS verification of the HTTPS endpoints, before any of them is reached.
+        self.tls.install()?;
+
+        // Fill in the flags omitted from the command line from the environment variables.
+        self.apply_env_defaults(&std::env::vars().collect())?;
+
+        // Fetch the program from the registry, if one is given, and deploy it as a local package.
+        if let (Some(registry), Some(version), Some(program_id)) =
+            (&self.registry, &self.registry_version, self.program_id)
+        {
+            let directory = std::env::temp_dir().join("snarkos-registry").join(format!("{program_id}-{version}"));
+            Registry::new(registry).fetch_package(program_id, version, &directory)?;
+            println!("📥 Fetched version {version} of '{}' from {registry}\n", program_id.to_string().bold());
+            self.path = Some(directory.to_string_lossy().to_string());
+        }
+
+        // Determine the program ID, from the arguments or the package manifest.
+        let program_id = self.resolve_program_id()?;
+
+        // Determine if the program should only be checked for errors.
+        if self.output.format_check {
+            return self.format_check(program_id);
+        }
+
+        // Ensure the deadline can be set, before anything is generated.
+        if let Some(valid_until) = self.valid_until {
+            Self::check_valid_until(valid_until)?;
+        }
+
+        // Ensure the constant overrides can be applied, before anything is generated.
+        Self::check_defines(&self.defines)?;
+
+        // Determine if the program should be deployed to a local chain instead of a network.
+        if let Some(path) = self.endpoints.local_genesis.clone() {
+            return self.deploy_locally(&path, program_id);
+        }
+
+        // Determine if the unsigned deployment should be written out for a separate fee service.
+        if let Some(path) = self.output.unsigned_output.clone() {
+            return self.write_unsigned(&path, program_id);
+        }
+
         // Ensure that the user has specified an action.
-        if !self.dry_run && self.broadcast.is_none() && self.store.is_none() {
-            bail!("❌ Please specify one of the following actions: --broadcast, --dry-run, --store");
+        if !self.output.dry_run
+            && self.endpoints.broadcast.is_none()
+            && self.output.store.is_none()
+            && self.output.output_dir.is_none()
+        {
+            bail!(
+                "❌ Please specify one of the following actions: --broadcast, --dry-run, --store, --output-dir, --format-check"
+            );
+        }
+
+        // Ensure the transaction file in the output directory is not overwritten, before generating the deployment.
+        let output_path = match &self.output.output_dir {
+            Some(output_dir) => Some(Self::output_path(output_dir, program_id, self.output.overwrite)?),
+            None => None,
+        };
+
+        // Initialize the query endpoints, which each request fails over between.
+        let query_endpoints = match self.endpoints.query.is_empty() {
+            true => None,
+            false => Some(QueryEndpoints::new(self.endpoints.query.clone())),
+        };
+
+        // Ensure the query endpoint is on the network being deployed to.
+        if let (Some(query_endpoints), false) = (&query_endpoints, self.endpoints.skip_network_check) {
+            query_endpoints
+                .request(Developer::check_network)
+                .map_err(|error| anyhow!("{error} Use --skip-network-check to override."))?;
         }
 
-        // Specify the query
-        let query = Query::from(&self.query);
+        // Warn if the query endpoint lags behind the broadcast endpoint, as stale reads can produce an invalid fee.
+        let endpoints = query_endpoints.as_ref().zip(self.endpoints.broadcast.as_deref());
+        if let Some((query_endpoints, broadcast)) = endpoints.filter(|(query, broadcast)| query.current() != *broadcast)
+        {
+            let heights = query_endpoints
+                .request(Developer::fetch_latest_height)
+                .and_then(|query_height| Ok((query_height, Developer::fetch_latest_height(broadcast)?)));
+            match heights {
+                Ok((query_height, broadcast_height)) => {
+                    if let Some(warning) =
+                        Self::replica_lag_warning(query_height, broadcast_height, self.endpoints.max_replica_lag)
+                    {
+                        println!("{warning}");
+                    }
+                }
+                Err(error) => {
+                    println!("⚠️  Unable to compare the heights of the query and broadcast endpoints: {error}")
+                }
+            }
+        }
 
         // Retrieve the private key.
-        let private_key = PrivateKey::from_str(&self.private_key)?;
+        let private_key = self.account.private_key()?;
+
+        // Determine the priority fee, falling back to the fee declared in the manifest.
+        let priority_fee = match self.fees.fee {
+            Some(fee) => fee,
+            None => match Self::manifest_fee(&self.path)? {
+                Some(fee) => fee,
+                None => bail!("❌ Please specify a fee with --fee, or declare a 'fee' in the package manifest"),
+            },
+        };
+
+        // Retrieve the fee record, before anything is generated, as every deployment spends one.
+        let fee_record = Self::require_fee_record(self.fees.record.as_deref(), priority_fee)?;
+        let fee_record = Developer::parse_record(&private_key, fee_record)?;
+
+        // Determine the endpoints to query the state from, serving the state file locally if one is given.
+        let query_endpoints = match (query_endpoints, &self.endpoints.state_file) {
+            (Some(query_endpoints), _) => query_endpoints,
+            (None, Some(state_file)) => {
+                println!("⚠️  Deploying offline, without checking the imports or prior deployment of the program");
+                QueryEndpoints::new(vec![
+                    OfflineState::read(state_file, &fee_record, self.endpoints.max_state_age)?.serve()?
+                ])
+            }
+            _ => bail!("❌ Please specify the endpoint to query node state from with --query"),
+        };
+        let is_offline = self.endpoints.state_file.is_some();
+
+        // Ensure the fee record is unspent before generating the deployment, as `developer export-state` does offline.
+        if !self.fees.skip_spent_check && !is_offline {
+            query_endpoints
+                .request(|endpoint| Developer::ensure_record_unspent(&private_key, &fee_record, endpoint))?;
+        }
 
         // Fetch the package from the directory.
-        let package = Developer::parse_package(self.program_id, self.path)?;
+        let package = Developer::parse_package(program_id, self.path.clone())?;
 
-        println!("📦 Creating deployment transaction for '{}'...\n", &self.program_id.to_string().bold());
+        // Ensure the compiled program is within the size budget, before proving the deployment.
+        Self::check_program_size(package.program().to_bytes_le()?.len(), self.max_program_size)?;
 
-        // Generate the deployment
-        let deployment = package.deploy::<CurrentAleo>(None)?;
+        // Ensure the imports of the program are deployed, as the deployment is rejected otherwise, deploying the
+        // missing imports first if requested.
+        if !self.is_import && !is_offline {
+            let missing = query_endpoints.request(|endpoint| Self::check_imports(package.program(), endpoint))?;
+            if !missing.is_empty() {
+                let missing = missing.iter().map(|import_id| import_id.to_string()).collect::<Vec<_>>();
+                ensure!(
+                    self.imports.deploy_imports,
+                    "❌ The imports {} are not deployed. Deploy them first, or use --deploy-imports to deploy them from the package before the program.",
+                    missing.join(", ")
+                );
+                self.deploy_missing_imports(&missing, &package.imports_directory(), &query_endpoints, priority_fee)?;
+            }
+        }
+
+        // Ensure the program is not already deployed, unless a confirmed redeployment was requested.
+        let is_redeployment =
+            !is_offline && query_endpoints.request(|endpoint| Developer::is_program_deployed(&program_id, endpoint))?;
+        if is_redeployment {
+            ensure!(
+                self.redeploy,
+                "❌ '{program_id}' is already deployed. Use --redeploy on test networks that permit redeployment."
+            );
+            Self::confirm_redeploy(program_id, &mut std::io::stdin().lock())?;
+        }
+
+        println!("📦 Creating deployment transaction for '{}'...\n", &program_id.to_string().bold());
+
+        // Initialize the profile of the deployment phases.
+        let mut profile = DeploymentProfile { program_id: program_id.to_string(), ..Default::default() };
+
+        // Initialize the event posted to the webhook at each milestone.
+        let mut event = DeploymentEvent {
+            event: "proving_started",
+            program_id: program_id.to_string(),
+            deployment_id: None,
+            transaction_id: None,
+            fee: None,
+        };
+        event.notify(&self.report.webhook);
+
+        // Generate the deployment, unless an unchanged deployment of the program is cached.
+        let cached = self.proving_cache.as_deref().map(|dir| ProvingCache::load(dir, package.program())).transpose()?;
+        let deployment = match cached.flatten() {
+            Some(deployment) => {
+                println!("♻️  Reusing the cached deployment of '{program_id}'");
+                deployment
+            }
+            None => {
+                let deployment = profile.phase("deploy", || package.deploy::<CurrentAleo>(None))?;
+                // Cache the deployment, if a cache directory is given.
+                if let Some(cache_dir) = &self.proving_cache {
+                    ProvingCache::store(cache_dir, &deployment)?;
+                }
+                deployment
+            }
+        };
         let deployment_id = deployment.to_deployment_id()?;
 
+        // Determine if the verifying-key IDs should be printed.
+        if self.report.print_vk_ids {
+            for (function_name, id) in Developer::verifying_key_ids(&deployment)? {
+                println!("🔑 {function_name}: {id}");
+            }
+        }
+
         // Generate the deployment transaction.
-        let transaction = {
+        let (transaction, fee_in_microcredits) = {
             // Initialize an RNG.
             let rng = &mut rand::thread_rng();
 
-            // Initialize the VM.
-            let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::open(None)?;
-            let vm = VM::from(store)?;
-
             // Compute the minimum deployment cost.
             let (minimum_deployment_cost, (_, _)) = deployment_cost(&deployment)?;
             // Determine the fee.
             let fee_in_microcredits = minimum_deployment_cost
-                .checked_add(self.fee)
+                .checked_add(priority_fee)
                 .ok_or_else(|| anyhow!("Fee overflowed for a deployment transaction"))?;
+            println!(
+                "💰 The deployment costs {}, plus a priority fee of {}, for a total fee of {}",
+                self.fees.fee_display.format(minimum_deployment_cost),
+                self.fees.fee_display.format(priority_fee),
+                self.fees.fee_display.format(fee_in_microcredits)
+            );
+            // Determine if the fee should be checked against the current conditions of the network, which is
+            // non-fatal, as the network may only be used to read its state.
+            if (self.output.dry_run || self.output.validate_first) && !is_offline {
+                match query_endpoints.request(NetworkConditions::fetch) {
+                    Ok(conditions) => println!("{}", conditions.fee_report(priority_fee)),
+                    Err(error) => println!("⚠️  Unable to check the fee against the network conditions: {error}"),
+                }
+            }
+            // Initialize the VM.
+            let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::open(None)?;
+            let vm = VM::from(store)?;
 
-            // Prepare the fees.
-            let fee_record = Developer::parse_record(&private_key, &self.record)?;
-            let (_, fee) =
-                vm.execute_fee_raw(&private_key, fee_record, fee_in_microcredits, deployment_id, Some(query), rng)?;
+            // Prepare the fees, reading the state of the fee record from the query endpoints.
+            let (_, fee) = profile.phase("execute_fee_raw", || {
+                query_endpoints.request(|endpoint| {
+                    let query = Query::from(endpoint);
+                    let fee_record = fee_record.clone();
+                    vm.execute_fee_raw(&private_key, fee_record, fee_in_microcredits, deployment_id, Some(query), rng)
+                })
+            })?;
 
             // Construct the owner.
             let owner = ProgramOwner::new(&private_key, deployment_id, rng)?;
 
             // Create a new transaction.
-            Transaction::from_deployment(owner, deployment, fee)?
+            let transaction =
+                profile.phase("from_deployment", || Transaction::from_deployment(owner, deployment, fee))?;
+            (transaction, fee_in_microcredits)
+        };
+        println!("✅ Created deployment transaction for '{}'", program_id.to_string().bold());
+
+        event.event = "proving_complete";
+        event.deployment_id = Some(deployment_id.to_string());
+        event.transaction_id = Some(transaction.id().to_string());
+        event.fee = Some(fee_in_microcredits);
+        event.notify(&self.report.webhook);
+
+        // Determine if the profile of the deployment phases should be written out.
+        if let Some(path) = &self.report.profile {
+            std::fs::write(path, serde_json::to_string_pretty(&profile)?)?;
+            println!("Deployment profile was written to {path}");
+        }
+
+        // Determine if the proving duration of the deployment and its fee should be recorded.
+        if let Some(path) = &self.report.stats_file {
+            Stats::append(path, program_id.to_string(), profile.proving_ms())?;
+        }
+
+        // Determine if the dry-run transaction should be written out for inspection.
+        if let Some(path) = &self.output.dry_run_output {
+            std::fs::write(path, transaction.to_string())?;
+            println!("Dry-run transaction {} was written to {path}", transaction.id());
+        }
+
+        // Determine if the admission of the dry-run transaction into the mempool should be simulated.
+        if self.output.simulate_mempool {
+            let report =
+                query_endpoints.request(|endpoint| self.simulate_mempool(&transaction, priority_fee, endpoint))?;
+            println!("{report}");
+        }
+
+        // Ensure the transaction is valid and would be admitted into the mempool, before anything is broadcast.
+        if self.output.validate_first {
+            let report =
+                query_endpoints.request(|endpoint| self.simulate_mempool(&transaction, priority_fee, endpoint))?;
+            println!("{report}");
+            println!("✅ Transaction {} passed validation, proceeding to broadcast", transaction.id());
+        }
+
+        // Determine if the transaction should be written to the output directory.
+        if let (Some(output_dir), Some(output_path)) = (&self.output.output_dir, &output_path) {
+            std::fs::write(output_path, transaction.to_bytes_le()?)?;
+            Self::record_output(output_dir, program_id, transaction.id().to_string())?;
+            println!("Transaction {} was written to {}", transaction.id(), output_path.display());
+        }
+
+        // Determine if the verifying keys should be stored alongside the transaction.
+        let store = match (self.output.store, self.output.include_vks, self.output.not_before) {
+            (Some(path), true, _) => {
+                Self::store_bundle(&path, &transaction)?;
+                None
+            }
+            // Determine if the transaction should be stored for a deferred broadcast.
+            (Some(path), false, Some(not_before)) => {
+                Self::store_scheduled(&path, &transaction, not_before)?;
+                None
+            }
+            (store, _, _) => store,
         };
-        println!("✅ Created deployment transaction for '{}'", self.program_id.to_string().bold());
 
         // Determine if the transaction should be broadcast, stored, or displayed to user.
-        Developer::handle_transaction(
-            self.broadcast,
-            self.dry_run,
-            self.store,
+        let transaction_id = transaction.id();
+        let is_broadcast = self.endpoints.broadcast.is_some();
+        let result = Developer::handle_transaction(
+            self.endpoints.broadcast,
+            self.output.dry_run,
+            store,
             transaction,
-            self.program_id.to_string(),
+            program_id.to_string(),
+            0,
+        );
+        // Surface a rejected redeployment, as the network may not permit it.
+        let output = result.map_err(|error| match is_redeployment {
+            true => anyhow!("{error}\n   Note: the network may not permit redeploying '{program_id}'"),
+            false => error,
+        })?;
+        if is_broadcast {
+            event.event = "broadcast_submitted";
+            event.notify(&self.report.webhook);
+        }
+
+        // Determine if the confirmation of the broadcast transaction should be awaited.
+        if self.wait_for_confirmation {
+            let confirmation = query_endpoints
+                .request(|endpoint| Developer::wait_for_confirmation(endpoint, transaction_id, &self.confirmation))?;
+            println!("{confirmation}");
+            event.event = "confirmed";
+            event.notify(&self.report.webhook);
+        }
+        Ok(output)
+    }
+
+    /// Returns the fee record, or an error explaining why it is needed for the given priority fee.
+    fn require_fee_record<T>(fee_record: Option<T>, priority_fee: u64) -> Result<T> {
+        match fee_record {
+            Some(fee_record) => Ok(fee_record),
+            // A deployment transaction always carries a fee transition, which spends a record even if nothing is paid,
+            // so even a network without a minimum deployment cost requires a fee record.
+            None if priority_fee == 0 => bail!(
+                "❌ The network requires a fee: every deployment spends a fee record, even with --fee 0. Please specify one with --record (none of its balance is paid if the total fee is zero)"
+            ),
+            None => bail!(
+                "❌ Please specify the record to spend the fee from with --record (the priority fee is {priority_fee} microcredits)"
+            ),
+        }
+    }
+
+    /// Returns a warning if the query endpoint lags behind the broadcast endpoint by more than the given blocks.
+    fn replica_lag_warning(query_height: u32, broadcast_height: u32, max_lag: u32) -> Option<String> {
+        let lag = broadcast_height.saturating_sub(query_height);
+        match lag > max_lag {
+            true => Some(format!(
+                "⚠️  The query endpoint is {lag} blocks behind the broadcast endpoint (height {query_height} vs. {broadcast_height}), so the fee may be computed from stale state"
+            )),
+            false => None,
+        }
+    }
+
+    /// Ensures the network supports the expiry of transactions at the given deadline.
+    /// Transactions on `CurrentNetwork` carry no deadline, so they can not expire; the deadline is refused
+    /// instead of being silently ignored.
+    fn check_valid_until(valid_until: u32) -> Result<()> {
+        bail!(
+            "❌ Unable to deploy with --valid-until {valid_until}, as {} does not support transaction expiry",
+            CurrentNetwork::NAME
         )
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::commands::{Command, CLI};
+    /// Ensures each `KEY=VALUE` override names a constant of the program. Aleo instructions declare no constants,
+    /// so any override names an unknown constant, and is refused instead of being silently ignored.
+    fn check_defines(defines: &[String]) -> Result<()> {
+        let mut keys = Vec::with_capacity(defines.len());
+        for define in defines {
+            match define.split_once('=') {
+                Some((key, _)) if !key.trim().is_empty() => keys.push(key.trim()),
+                _ => bail!("❌ The override '{define}' must be of the form KEY=VALUE"),
+            }
+        }
+        match keys.is_empty() {
+            true => Ok(()),
+            false => bail!(
+                "❌ Unknown program constants: {}. Aleo instructions do not declare constants that can be overridden",
+                keys.join(", ")
+            ),
+        }
+    }
 
-    #[test]
-    fn clap_snarkos_deploy() {
-        let arg_vec = vec![
-            "snarkos",
-            "developer",
-            "deploy",
-            "--private-key",
-            "PRIVATE_KEY",
-            "--query",
-            "QUERY",
-            "--fee",
-            "77",
-            "--record",
-            "RECORD",
-            "hello.aleo",
-        ];
-        let cli = CLI::parse_from(arg_vec);
+    /// Ensures the size of the compiled program does not exceed the given maximum, unless the maximum is zero.
+    fn check_program_size(size: usize, max_size: usize) -> Result<()> {
+        ensure!(
+            max_size == 0 || size <= max_size,
+            "❌ The compiled program is {size} bytes, which exceeds the --max-program-size of {max_size} bytes"
+        );
+        Ok(())
+    }
 
-        if let Command::Developer(Developer::Deploy(deploy)) = cli.command {
-            assert_eq!(deploy.program_id, "hello.aleo".try_into().unwrap());
-            assert_eq!(deploy.private_key, "PRIVATE_KEY");
-            assert_eq!(deploy.query, "QUERY");
-            assert_eq!(deploy.fee, 77);
-            assert_eq!(deploy.record, "RECORD");
-        } else {
-            panic!("Unexpected result of clap parsing!");
+    /// Runs the transaction through the admission checks of a node's mempool, bailing if it would be rejected.
+    ///
+    /// The imports of the program are loaded from the query endpoint, to verify the proofs of the transaction.
+    /// The checks against the state of the network, such as whether the fee record is unspent, are left to the node.
+    fn simulate_mempool(
+        &self,
+        transaction: &Transaction<CurrentNetwork>,
+        priority_fee: u64,
+        query_endpoint: &str,
+    ) -> Result<String> {
+        // Ensure the transaction is within the size and fee thresholds.
+        let mut rejections = self.mempool.rejections(transaction.to_bytes_le()?.len(), priority_fee);
+        // Ensure the proofs of the transaction verify, as a node checks them before admitting it.
+        let process = Broadcast::load_dependencies(transaction, Some(query_endpoint))?;
+        if let Err(error) = Broadcast::verify_proofs(&process, transaction) {
+            rejections.push(format!("the transaction is invalid ({error})"));
+        }
+        ensure!(
+            rejections.is_empty(),
+            "❌ Transaction {} would be rejected by the mempool: {}",
+            transaction.id(),
+            rejections.join("; ")
+        );
+        Ok(format!("✅ Transaction {} would be admitted into the mempool", transaction.id()))
+    }
+
+    /// Warns that the program will be redeployed, and waits for the user to confirm it.
+    fn confirm_redeploy(program_id: ProgramID<CurrentNetwork>, input: &mut impl BufRead) -> Result<()> {
+        println!("⚠️  '{program_id}' is already deployed. Redeploying it is only permitted on some test networks.");
+        print!("Proceed with the redeployment? [y/N] ");
+        std::io::stdout().flush()?;
+
+        let mut answer = String::new();
+        input.read_line(&mut answer)?;
+        match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => Ok(()),
+            _ => bail!("❌ The redeployment of '{program_id}' was not confirmed"),
+        }
+    }
+
+    /// Deploys the program to a local chain initialized from the genesis configuration at the given path,
+    /// and returns the ID of the accepted deployment.
+    fn deploy_locally(&self, path: &Path, program_id: ProgramID<CurrentNetwork>) -> Result<String> {
+        let rng = &mut rand::thread_rng();
+        let config = GenesisConfig::read(path)?;
+        let chain = match &self.endpoints.ledger_path {
+            Some(ledger_path) => LocalChain::open(&config, ledger_path, rng)?,
+            None => LocalChain::new(&config, rng)?,
+        };
+        let private_key = self.account.private_key()?;
+        let package = Developer::parse_package(program_id, self.path.clone())?;
+
+        let transaction = chain.deploy(&private_key, package.program(), self.fees.fee.unwrap_or(0), rng)?;
+        println!(
+            "✅ The deployment of '{}' was accepted by the local chain at block {}",
+            program_id.to_string().bold(),
+            chain.latest_height()
+        );
+        Ok(transaction.id().to_string())
+    }
+
+    /// Renders the reference of the unsigned deployment as QR codes, to the terminal if the path is `-`,
+    /// or otherwise to an SVG image at the path.
+    fn write_qr(path: &str, reference: &UnsignedDeploymentReference) -> Result<()> {
+        let codes = QrCode::encode_chunks(serde_json::to_string(reference)?.as_bytes())?;
+        match path {
+            "-" => {
+                for (index, code) in codes.iter().enumerate() {
+                    println!("📱 QR code {} of {} for the signer:\n\n{}", index + 1, codes.len(), code.to_terminal());
+                }
+            }
+            path => {
+                std::fs::write(path, QrCode::to_svg(&codes))?;
+                println!("📱 Wrote {} QR code(s) for the signer to {path}\n", codes.len());
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the unsigned deployment, along with the context needed to attach the fee, to the output file.
+    fn write_unsigned(self, path: &str, program_id: ProgramID<CurrentNetwork>) -> Result<String> {
+        // Retrieve the owner address.
+        let private_key = self.account.private_key()?;
+        let owner = Address::try_from(&private_key)?;
+
+        // Fetch the package from the directory.
+        let package = Developer::parse_package(program_id, self.path)?;
+
+        println!("📦 Creating unsigned deployment for '{}'...\n", &program_id.to_string().bold());
+
+        // Generate the deployment.
+        let deployment = package.deploy::<CurrentAleo>(None)?;
+        let deployment_id = deployment.to_deployment_id()?;
+
+        // Write the unsigned deployment to the output file.
+        let unsigned = UnsignedDeployment { program_id, owner, deployment_id, deployment };
+        std::fs::write(path, serde_json::to_string_pretty(&unsigned)?)?;
+
+        // Render the reference of the unsigned deployment for a mobile signer, if requested.
+        if let Some(qr) = &self.output.qr {
+            Self::write_qr(qr, &UnsignedDeploymentReference { program_id, owner, deployment_id })?;
+        }
+
+        Ok(format!("✅ Wrote unsigned deployment {deployment_id} for '{}' to {path}", program_id.to_string().bold()))
+    }
+
+    /// Checks that each import of the program is deployed, listing the imports that are present and missing.
+    /// Returns the imports that are missing.
+    fn check_imports(program: &Program<CurrentNetwork>, endpoint: &str) -> Result<Vec<ProgramID<CurrentNetwork>>> {
+        let mut missing = Vec::new();
+        for import_id in program.imports().keys() {
+            match Developer::is_program_deployed(import_id, endpoint)? {
+                true => println!("✅ Import '{import_id}' is deployed"),
+                false => {
+                    println!("❌ Import '{import_id}' is not deployed");
+                    missing.push(*import_id);
+                }
+            }
         }
+        Ok(missing)
+    }
+
+    /// Deploys the given missing imports, and the missing imports they import in turn, in waves of up to
+    /// `--parallel-deploys` imports that do not import one another. Each wave is proven and submitted concurrently,
+    /// after the wave before it. Each import is deployed as a package staged from the given imports directory.
+    fn deploy_missing_imports(
+        &self,
+        missing: &[String],
+        imports_directory: &Path,
+        query_endpoints: &QueryEndpoints,
+        priority_fee: u64,
+    ) -> Result<()> {
+        let programs =
+            query_endpoints.request(|endpoint| Self::missing_imports(missing, imports_directory, endpoint))?;
+        let program_ids = programs.iter().map(|program| program.id().to_string()).collect::<Vec<_>>();
+        let imports = programs
+            .iter()
+            .map(|program| program.imports().keys().map(|import_id| import_id.to_string()).collect())
+            .collect::<Vec<_>>();
+        let waves = Self::deployment_waves(&program_ids, &imports, self.imports.parallel_deploys)?;
+
+        // Ensure a fee record is given for each import, before any of them is deployed.
+        let records = program_ids
+            .iter()
+            .map(|import_id| Self::import_record(&self.imports.import_records, import_id))
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::deploy_waves(&program_ids, &waves, |index| {
+            println!("📦 Deploying the missing import '{}' first...\n", program_ids[index].bold());
+            let directory = Self::stage_import(&programs[index], imports_directory)?;
+            let import = Deploy {
+                program_id: Some(*programs[index].id()),
+                path: Some(directory.to_string_lossy().to_string()),
+                registry: None,
+                registry_version: None,
+                fees: FeeOptions { fee: Some(priority_fee), record: Some(records[index].clone()), ..self.fees.clone() },
+                imports: ImportOptions { deploy_imports: false, import_records: vec![], ..self.imports.clone() },
+                is_import: true,
+                // A broadcast import must be accepted before the deployments that import it are.
+                wait_for_confirmation: self.wait_for_confirmation || self.endpoints.broadcast.is_some(),
+                redeploy: false,
+                ..self.clone()
+            };
+            println!("{}", import.parse()?);
+            Ok(())
+        })
+    }
+
+    /// Runs the deployment of each program of a batch in the given waves, running the deployments of a wave
+    /// concurrently, and stopping after the first wave with a failure.
+    fn deploy_waves<F>(program_ids: &[String], waves: &[Vec<usize>], deploy: F) -> Result<()>
+    where
+        F: Fn(usize) -> Result<()> + Sync,
+    {
+        for (wave_index, wave) in waves.iter().enumerate() {
+            let deploy = &deploy;
+            let results = std::thread::scope(|scope| {
+                let handles = wave.iter().map(|&index| scope.spawn(move || deploy(index))).collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().unwrap_or_else(|_| Err(anyhow!("the deployment panicked"))))
+                    .collect::<Vec<_>>()
+            });
+            let failures = wave
+                .iter()
+                .zip(results)
+                .filter_map(|(&index, result)| result.err().map(|error| format!("'{}': {error}", program_ids[index])))
+                .collect::<Vec<_>>();
+            if !failures.is_empty() {
+                let not_deployed = waves[wave_index + 1..].iter().flatten().map(|&index| program_ids[index].as_str());
+                bail!(
+                    "❌ Failed to deploy {}\n  Not deployed: {}",
+                    failures.join("\n  Also failed: "),
+                    not_deployed.collect::<Vec<_>>().join(", ")
+                )
+            }
+        }
+        Ok(())
+    }
+
+    /// Groups the deployments of a batch into waves, such that each deployment comes after the deployments of the
+    /// batch it imports, and each wave holds at most `limit` deployments. Imports outside of the batch are assumed
+    /// to be deployed already. The deployments of a wave are listed in the order of the batch.
+    fn deployment_waves(program_ids: &[String], imports: &[Vec<String>], limit: usize) -> Result<Vec<Vec<usize>>> {
+        let mut deployed = vec![false; program_ids.len()];
+        let mut waves = Vec::new();
+        while deployed.iter().any(|deployed| !deployed) {
+            // Select the pending deployments whose imports in the batch were all deployed in an earlier wave.
+            let ready = (0..program_ids.len())
+                .filter(|&index| !deployed[index])
+                .filter(|&index| {
+                    imports[index].iter().all(|import| {
+                        program_ids
+                            .iter()
+                            .zip(&deployed)
+                            .all(|(program_id, deployed)| program_id != import || *deployed)
+                    })
+                })
+                .collect::<Vec<_>>();
+            ensure!(!ready.is_empty(), "❌ The deployments of the batch import one another in a cycle");
+            for chunk in ready.chunks(limit.max(1)) {
+                waves.push(chunk.to_vec());
+            }
+            ready.iter().for_each(|&index| deployed[index] = true);
+        }
+        Ok(waves)
+    }
+
+    /// Returns the programs of the given missing imports, read from the imports directory, along with the imports
+    /// they import in turn that are not deployed either, in the order they were found.
+    fn missing_imports(
+        missing: &[String],
+        imports_directory: &Path,
+        endpoint: &str,
+    ) -> Result<Vec<Program<CurrentNetwork>>> {
+        let mut programs: Vec<Program<CurrentNetwork>> = Vec::new();
+        let mut pending =
+            missing.iter().rev().map(|import_id| ProgramID::from_str(import_id)).collect::<Result<Vec<_>>>()?;
+        while let Some(import_id) = pending.pop() {
+            if programs.iter().any(|program| program.id() == &import_id) {
+                continue;
+            }
+            let path = imports_directory.join(import_id.to_string());
+            let source = std::fs::read_to_string(&path).map_err(|error| {
+                anyhow!("❌ Unable to read the import '{import_id}' from {}: {error}", path.display())
+            })?;
+            let program = Program::<CurrentNetwork>::from_str(&source)?;
+            // Queue the imports of the import that are not deployed either.
+            for nested_id in program.imports().keys() {
+                if !programs.iter().any(|program| program.id() == nested_id)
+                    && !Developer::is_program_deployed(nested_id, endpoint)?
+                {
+                    pending.push(*nested_id);
+                }
+            }
+            programs.push(program);
+        }
+        Ok(programs)
+    }
+
+    /// Returns the fee record of the given import, from the `PROGRAM_ID=RECORD` pairs of `--import-record`.
+    fn import_record(import_records: &[String], import_id: &str) -> Result<String> {
+        for import_record in import_records {
+            match import_record.split_once('=') {
+                Some((program_id, record)) if program_id.trim() == import_id => return Ok(record.trim().to_string()),
+                Some(_) => continue,
+                None => bail!("❌ The import record '{import_record}' is not of the form PROGRAM_ID=RECORD"),
+            }
+        }
+        bail!("❌ Please specify the record to spend the fee of the import '{import_id}' from with --import-record {import_id}=RECORD")
+    }
+
+    /// Stages the given import as a package in the temporary directory, with the sources of the imports directory,
+    /// and returns the directory of the package.
+    fn stage_import(program: &Program<CurrentNetwork>, imports_directory: &Path) -> Result<PathBuf> {
+        let directory = std::env::temp_dir().join("snarkos-imports").join(program.id().to_string());
+        if directory.exists() {
+            std::fs::remove_dir_all(&directory)?;
+        }
+        std::fs::create_dir_all(directory.join("imports"))?;
+        let manifest = serde_json::json!({
+            "program": program.id().to_string(),
+            "version": "0.0.0",
+            "description": "",
+            "license": "",
+        });
+        std::fs::write(directory.join("program.json"), serde_json::to_string_pretty(&manifest)?)?;
+        std::fs::write(directory.join("main.aleo"), program.to_string())?;
+        for entry in std::fs::read_dir(imports_directory)? {
+            let entry = entry?;
+            std::fs::copy(entry.path(), directory.join("imports").join(entry.file_name()))?;
+        }
+        Ok(directory)
+    }
+
+    /// Fills in the `--query`, `--broadcast`, and `--fee` flags omitted from the command line from the given
+    /// environment variables, then ensures the flags that need an endpoint have one. The broadcast endpoint is only
+    /// read when no other action is given, so that the environment does not turn a `--store` run into a broadcast.
+    /// Neither endpoint is read with `--state-file` or `--local-genesis`, as they would conflict.
+    fn apply_env_defaults(&mut self, env: &HashMap<String, String>) -> Result<()> {
+        if self.endpoints.state_file.is_none() && self.endpoints.local_genesis.is_none() {
+            if self.endpoints.query.is_empty() {
+                if let Some(query) = env.get(Self::QUERY_ENV) {
+                    self.endpoints.query = query.split(',').map(str::to_string).collect();
+                }
+            }
+            if self.endpoints.broadcast.is_none() && !self.output.has_local_action() {
+                self.endpoints.broadcast = env.get(Self::BROADCAST_ENV).cloned();
+            }
+        }
+        if self.fees.fee.is_none() {
+            if let Some(fee) = env.get(Self::FEE_ENV) {
+                match fee.parse() {
+                    Ok(fee) => self.fees.fee = Some(fee),
+                    Err(_) => bail!("❌ The {} of '{fee}' is not a fee in microcredits", Self::FEE_ENV),
+                }
+            }
+        }
+
+        // Ensure the endpoints are given where they are needed, as a format check or an unsigned deployment
+        // contacts no node.
+        let needs_query = !self.output.format_check && self.output.unsigned_output.is_none();
+        if needs_query
+            && self.endpoints.state_file.is_none()
+            && self.endpoints.local_genesis.is_none()
+            && self.endpoints.query.is_empty()
+        {
+            bail!("❌ Please specify a query endpoint with --query or {}, or a --state-file", Self::QUERY_ENV)
+        }
+        if self.endpoints.broadcast.is_none() && (self.wait_for_confirmation || self.output.validate_first) {
+            bail!(
+                "❌ --wait-for-confirmation and --validate-first require a broadcast endpoint, with --broadcast or {}",
+                Self::BROADCAST_ENV
+            )
+        }
+        Ok(())
+    }
+
+    /// Returns the program ID from the arguments, falling back to the program declared in the package manifest.
+    fn resolve_program_id(&self) -> Result<ProgramID<CurrentNetwork>> {
+        match (self.program_id, Self::manifest_program_id(&self.path)) {
+            (Some(program_id), Ok(Some(manifest_id))) if program_id != manifest_id => {
+                bail!("❌ The program ID '{program_id}' does not match '{manifest_id}' in the package manifest")
+            }
+            (Some(program_id), _) => Ok(program_id),
+            (None, Ok(Some(manifest_id))) => Ok(manifest_id),
+            (None, Ok(None)) => {
+                bail!("❌ Please specify the program ID, or declare a 'program' in the package manifest")
+            }
+            (None, Err(error)) => {
+                bail!("❌ Please specify the program ID, as the package manifest is unreadable: {error}")
+            }
+        }
+    }
+
+    /// Returns the program ID declared in the package manifest, if one is present.
+    fn manifest_program_id(path: &Option<String>) -> Result<Option<ProgramID<CurrentNetwork>>> {
+        // Instantiate a path to the directory containing the manifest file.
+        let directory = match path {
+            Some(path) => PathBuf::from_str(path)?,
+            None => std::env::current_dir()?,
+        };
+        // Read the manifest file.
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(directory.join("program.json"))?)?;
+        // Retrieve the program ID from the manifest.
+        match manifest.get("program") {
+            Some(program) => match program.as_str() {
+                Some(program) => Ok(Some(ProgramID::from_str(program)?)),
+                None => bail!("❌ The 'program' in the package manifest must be a program ID"),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the priority fee declared in the package manifest, if one is present.
+    fn manifest_fee(path: &Option<String>) -> Result<Option<u64>> {
+        // Instantiate a path to the directory containing the manifest file.
+        let directory = match path {
+            Some(path) => PathBuf::from_str(path)?,
+            None => std::env::current_dir()?,
+        };
+        // Read the manifest file.
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(directory.join("program.json"))?)?;
+        // Retrieve the fee from the manifest.
+        match manifest.get("fee") {
+            Some(fee) => match fee.as_u64() {
+                Some(fee) => Ok(Some(fee)),
+                None => bail!("❌ The 'fee' in the package manifest must be a number of microcredits"),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Parses and validates the program and its imports, reporting any syntax or semantic errors.
+    fn format_check(self, program_id: ProgramID<CurrentNetwork>) -> Result<String> {
+        // Parse the program, along with its imports.
+        let process = Developer::parse_package(program_id, self.path)
+            .and_then(|package| package.get_process())
+            .map_err(|error| anyhow!("❌ '{program_id}' is not well-formed: {error}"))?;
+        // Ensure the program was added to the process.
+        ensure!(process.contains_program(&program_id), "❌ '{program_id}' is not well-formed");
+        Ok(format!("✅ '{}' is well-formed", program_id.to_string().bold()))
+    }
+
+    /// Returns the path of the transaction file of the program in the output directory, creating the directory
+    /// if it is missing. Bails if the file already exists, unless it should be overwritten.
+    fn output_path(output_dir: &Path, program_id: ProgramID<CurrentNetwork>, overwrite: bool) -> Result<PathBuf> {
+        std::fs::create_dir_all(output_dir)?;
+        let path = output_dir.join(format!("{program_id}.tx"));
+        ensure!(!path.exists() || overwrite, "❌ '{}' already exists. Use --overwrite to replace it.", path.display());
+        Ok(path)
+    }
+
+    /// Records the deployment transaction of the program in the manifest of the output directory.
+    /// A program that is deployed again is moved to the end of the deployment order.
+    fn record_output(output_dir: &Path, program_id: ProgramID<CurrentNetwork>, transaction_id: String) -> Result<()> {
+        // Serialize the updates of the manifest, as the imports of a batch may be deployed concurrently.
+        static MANIFEST_LOCK: parking_lot::Mutex<()> = parking_lot::Mutex::new(());
+        let _guard = MANIFEST_LOCK.lock();
+
+        let manifest_path = output_dir.join(Self::OUTPUT_MANIFEST);
+        let mut manifest: DeployManifest = match manifest_path.exists() {
+            true => serde_json::from_str(&std::fs::read_to_string(&manifest_path)?)?,
+            false => Default::default(),
+        };
+        let program_id = program_id.to_string();
+        manifest.deployments.retain(|entry| entry.program_id != program_id);
+        manifest.deployments.push(DeployManifestEntry { program_id, transaction_id });
+        std::fs::write(manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+        Ok(())
+    }
+
+    /// Stores the transaction to the given path, along with the time before which it may not be broadcast.
+    fn store_scheduled(path: &str, transaction: &Transaction<CurrentNetwork>, not_before: u64) -> Result<()> {
+        let scheduled = ScheduledTransaction { not_before, transaction: transaction.clone() };
+        StorePath::parse(path)?.write(serde_json::to_string_pretty(&scheduled)?.as_bytes())?;
+        println!("Transaction {} was stored to {path}, to be broadcast no earlier than {not_before}", transaction.id());
+        Ok(())
+    }
+
+    /// Stores the deployment transaction to the given path, bundled with its verifying keys.
+    fn store_bundle(path: &str, transaction: &Transaction<CurrentNetwork>) -> Result<()> {
+        // Retrieve the deployment.
+        let deployment = match transaction.deployment() {
+            Some(deployment) => deployment,
+            None => bail!("❌ The transaction is not a deployment transaction"),
+        };
+        // Collect the verifying keys, along with their function names.
+        let verifying_keys =
+            deployment.verifying_keys().iter().map(|(function_name, (vk, _))| (*function_name, vk.clone())).collect();
+        // Write the bundle to the given path.
+        let bundle = DeploymentBundle { transaction: transaction.clone(), verifying_keys };
+        StorePath::parse(path)?.write(serde_json::to_string_pretty(&bundle)?.as_bytes())?;
+        println!("Transaction {} was stored with its verifying keys to {path}", transaction.id());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{
+        developer::{
+            hex,
+            slip10_derive,
+            test_helpers::{mock_http_sequence, mock_http_server, sample_deployment_transaction},
+        },
+        Command,
+        CLI,
+    };
+    use snarkvm::{
+        console::prelude::{Environment, PrimeField},
+        prelude::{FromBytes, TestRng},
+    };
+    use std::net::TcpListener;
+
+    #[test]
+    fn clap_snarkos_deploy() {
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "deploy",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--fee",
+            "77",
+            "--record",
+            "RECORD",
+            "hello.aleo",
+        ];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Deploy(deploy)) = cli.command {
+            assert_eq!(deploy.program_id, Some("hello.aleo".try_into().unwrap()));
+            assert_eq!(deploy.account.private_key, Some("PRIVATE_KEY".to_string()));
+            assert_eq!(deploy.endpoints.query, vec!["QUERY".to_string()]);
+            assert_eq!(deploy.fees.fee, Some(77));
+            assert_eq!(deploy.fees.record, Some("RECORD".to_string()));
+            assert_eq!(deploy.output.dry_run_output, None);
+            assert!(!deploy.endpoints.skip_network_check);
+            assert!(!deploy.output.include_vks);
+            assert_eq!(deploy.output.not_before, None);
+            assert!(!deploy.output.format_check);
+            assert!(!deploy.fees.skip_spent_check);
+            assert_eq!(deploy.fees.fee_display, FeeDisplay::Micro);
+            assert_eq!(deploy.proving_cache, None);
+            assert!(deploy.defines.is_empty());
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+    }
+
+    #[test]
+    fn clap_snarkos_deploy_dry_run_output() {
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "deploy",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--fee",
+            "77",
+            "--record",
+            "RECORD",
+            "--dry-run",
+            "--dry-run-output",
+            "deployment.json",
+            "hello.aleo",
+        ];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Deploy(deploy)) = cli.command {
+            assert!(deploy.output.dry_run);
+            assert_eq!(deploy.output.dry_run_output, Some("deployment.json".to_string()));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure the mempool simulation defaults to the thresholds of a node.
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "deploy",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--record",
+            "RECORD",
+            "--dry-run",
+            "--simulate-mempool",
+            "--mempool-min-priority-fee",
+            "10",
+            "hello.aleo",
+        ];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Deploy(deploy)) = cli.command {
+            assert!(deploy.output.simulate_mempool);
+            assert_eq!(deploy.mempool.max_size, 128 * 1024 * 1024);
+            assert_eq!(deploy.mempool.min_priority_fee, 10);
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure `--dry-run-output` requires `--dry-run`.
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "deploy",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--fee",
+            "77",
+            "--record",
+            "RECORD",
+            "--dry-run-output",
+            "deployment.json",
+            "hello.aleo",
+        ];
+        assert!(CLI::try_parse_from(arg_vec).is_err());
+    }
+
+    #[test]
+    fn clap_snarkos_deploy_env_defaults() {
+        let env = HashMap::from([
+            ("SNARKOS_QUERY".to_string(), "ENV_QUERY".to_string()),
+            ("SNARKOS_BROADCAST".to_string(), "ENV_BROADCAST".to_string()),
+            ("SNARKOS_FEE".to_string(), "88".to_string()),
+        ]);
+        let args = ["snarkos", "developer", "deploy", "--private-key", "PRIVATE_KEY", "--record", "RECORD"];
+        let deploy = |extra: &[&str], env: &HashMap<String, String>| {
+            let mut deploy = match CLI::parse_from([&args[..], extra, &["hello.aleo"]].concat()).command {
+                Command::Developer(Developer::Deploy(deploy)) => deploy,
+                _ => panic!("Unexpected result of clap parsing!"),
+            };
+            deploy.apply_env_defaults(env).map(|()| deploy)
+        };
+
+        // Ensure the environment variables are used when the flags are omitted.
+        let defaults = deploy(&[], &env).unwrap();
+        assert_eq!(defaults.endpoints.query, vec!["ENV_QUERY".to_string()]);
+        assert_eq!(defaults.endpoints.broadcast, Some("ENV_BROADCAST".to_string()));
+        assert_eq!(defaults.fees.fee, Some(88));
+
+        // Ensure the explicit flags take precedence over the environment variables.
+        let explicit = deploy(&["--query", "QUERY", "--broadcast", "BROADCAST", "--fee", "77"], &env).unwrap();
+        assert_eq!(explicit.endpoints.query, vec!["QUERY".to_string()]);
+        assert_eq!(explicit.endpoints.broadcast, Some("BROADCAST".to_string()));
+        assert_eq!(explicit.fees.fee, Some(77));
+
+        // Ensure a dry run ignores the broadcast endpoint of the environment, instead of conflicting with it.
+        let dry_run = deploy(&["--dry-run"], &env).unwrap();
+        assert!(dry_run.output.dry_run);
+        assert_eq!(dry_run.endpoints.broadcast, None);
+        // Ensure a state file ignores the endpoints of the environment.
+        let offline = deploy(&["--state-file", "state.json", "--dry-run"], &env).unwrap();
+        assert!(offline.endpoints.query.is_empty());
+        // Ensure the other actions are not turned into a broadcast by the environment.
+        for action in [&["--store", "STORE"][..], &["--output-dir", "out"], &["--unsigned-output", "unsigned.json"]] {
+            let local = deploy(action, &env).unwrap();
+            assert_eq!(local.endpoints.broadcast, None);
+            assert_eq!(local.endpoints.query, vec!["ENV_QUERY".to_string()]);
+        }
+        assert_eq!(deploy(&["--format-check"], &env).unwrap().endpoints.broadcast, None);
+        // Ensure a format check and an unsigned deployment need no query endpoint.
+        assert!(deploy(&["--format-check"], &HashMap::new()).is_ok());
+        assert!(deploy(&["--unsigned-output", "unsigned.json"], &HashMap::new()).is_ok());
+        assert!(deploy(&["--store", "STORE"], &HashMap::new()).is_err());
+
+        // Ensure the flags that need an endpoint are satisfied by the environment, and rejected without one.
+        assert!(deploy(&["--validate-first"], &env).unwrap().output.validate_first);
+        assert!(deploy(&["--validate-first"], &HashMap::new()).is_err());
+        assert!(deploy(&["--dry-run", "--wait-for-confirmation"], &env).is_err());
+        assert!(deploy(&[], &HashMap::new()).unwrap_err().to_string().contains("SNARKOS_QUERY"));
+
+        // Ensure a malformed fee is reported.
+        let env = HashMap::from([("SNARKOS_FEE".to_string(), "many".to_string())]);
+        assert!(deploy(&["--query", "QUERY"], &env).unwrap_err().to_string().contains("SNARKOS_FEE"));
+    }
+
+    #[test]
+    fn clap_snarkos_deploy_unsigned_output() {
+        // Ensure the fee record is not required for an unsigned deployment.
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "deploy",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--unsigned-output",
+            "unsigned.json",
+            "hello.aleo",
+        ];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Deploy(deploy)) = cli.command {
+            assert_eq!(deploy.output.unsigned_output, Some("unsigned.json".to_string()));
+            assert_eq!(deploy.fees.record, None);
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+    }
+
+    #[test]
+    fn clap_snarkos_deploy_qr() {
+        let args = |extra: &[&'static str]| {
+            let mut arg_vec =
+                vec!["snarkos", "developer", "deploy", "--private-key", "PRIVATE_KEY", "--query", "QUERY"];
+            arg_vec.extend_from_slice(extra);
+            arg_vec.push("hello.aleo");
+            arg_vec
+        };
+        let cli = CLI::parse_from(args(&["--unsigned-output", "unsigned.json", "--qr", "-"]));
+
+        if let Command::Developer(Developer::Deploy(deploy)) = cli.command {
+            assert_eq!(deploy.output.qr, Some("-".to_string()));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure the QR codes are only rendered for an unsigned deployment.
+        assert!(CLI::try_parse_from(args(&["--qr", "deployment.svg"])).is_err());
+    }
+
+    #[test]
+    fn clap_snarkos_deploy_validate_first() {
+        let args = |extra: &[&'static str]| {
+            let mut arg_vec =
+                vec!["snarkos", "developer", "deploy", "--private-key", "PRIVATE_KEY", "--query", "QUERY"];
+            arg_vec.extend_from_slice(extra);
+            arg_vec.push("hello.aleo");
+            arg_vec
+        };
+        let cli = CLI::parse_from(args(&["--broadcast", "BROADCAST", "--validate-first"]));
+
+        if let Command::Developer(Developer::Deploy(deploy)) = cli.command {
+            assert!(deploy.output.validate_first);
+            assert!(!deploy.output.dry_run);
+            assert_eq!(deploy.endpoints.broadcast, Some("BROADCAST".to_string()));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure the validation only precedes a broadcast.
+        for arg_vec in [args(&["--validate-first"]), args(&["--dry-run", "--validate-first"])] {
+            match CLI::parse_from(arg_vec).command {
+                Command::Developer(Developer::Deploy(mut deploy)) => {
+                    assert!(deploy.apply_env_defaults(&HashMap::new()).is_err())
+                }
+                _ => panic!("Unexpected result of clap parsing!"),
+            }
+        }
+    }
+
+    #[test]
+    fn clap_snarkos_deploy_registry() {
+        let args = |extra: &[&'static str]| {
+            let mut arg_vec =
+                vec!["snarkos", "developer", "deploy", "--private-key", "PRIVATE_KEY", "--query", "QUERY", "--dry-run"];
+            arg_vec.extend_from_slice(extra);
+            arg_vec
+        };
+        let cli = CLI::parse_from(args(&["--registry", "REGISTRY", "--registry-version", "1.0.0", "hello.aleo"]));
+
+        if let Command::Developer(Developer::Deploy(deploy)) = cli.command {
+            assert_eq!(deploy.registry, Some("REGISTRY".to_string()));
+            assert_eq!(deploy.registry_version, Some("1.0.0".to_string()));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure a program is fetched by its ID and version, instead of from a local package.
+        assert!(CLI::try_parse_from(args(&["--registry", "REGISTRY", "hello.aleo"])).is_err());
+        assert!(CLI::try_parse_from(args(&["--registry", "REGISTRY", "--registry-version", "1.0.0"])).is_err());
+        assert!(CLI::try_parse_from(args(&[
+            "--registry",
+            "REGISTRY",
+            "--registry-version",
+            "1.0.0",
+            "--path",
+            "PATH",
+            "hello.aleo"
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_deployment_profile() {
+        let mut profile = DeploymentProfile { program_id: "hello.aleo".to_string(), ..Default::default() };
+
+        // Ensure each phase is recorded in order, and its output is returned.
+        assert_eq!(profile.phase("deploy", || Ok(1)).unwrap(), 1);
+        assert_eq!(profile.phase("execute_fee_raw", || Ok(2)).unwrap(), 2);
+        let names = profile.phases.iter().map(|phase| phase.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["deploy", "execute_fee_raw"]);
+
+        // Ensure a failed phase is not recorded.
+        assert!(profile.phase::<()>("from_deployment", || bail!("failed")).is_err());
+        assert_eq!(profile.phases.len(), 2);
+
+        // Ensure the proving duration covers the proving phases only.
+        profile.phases.push(PhaseProfile {
+            name: "from_deployment".to_string(),
+            duration_ms: 1000,
+            peak_memory_kb: None,
+        });
+        let proving_ms = profile.phases[0].duration_ms + profile.phases[1].duration_ms;
+        assert_eq!(profile.proving_ms(), proving_ms);
+
+        // Ensure the profile serializes to JSON.
+        let json = serde_json::to_value(&profile).unwrap();
+        assert_eq!(json["phases"][0]["name"], "deploy");
+    }
+
+    #[test]
+    fn test_resolve_program_id() {
+        let directory = std::env::temp_dir().join(format!("snarkos_resolve_program_id_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.to_string_lossy().to_string();
+        std::fs::write(directory.join("program.json"), r#"{"program": "hello.aleo"}"#).unwrap();
+
+        let parse = |program_id: Option<&str>| {
+            let mut arg_vec =
+                vec!["snarkos", "developer", "deploy", "--private-key", "PRIVATE_KEY", "--query", "QUERY"];
+            arg_vec.extend(["--record", "RECORD", "--path", path.as_str()]);
+            arg_vec.extend(program_id);
+            match CLI::parse_from(arg_vec).command {
+                Command::Developer(Developer::Deploy(deploy)) => deploy.resolve_program_id(),
+                _ => panic!("Unexpected result of clap parsing!"),
+            }
+        };
+
+        // Ensure the program ID defaults to the manifest.
+        assert_eq!(parse(None).unwrap(), ProgramID::from_str("hello.aleo").unwrap());
+        // Ensure a matching program ID is accepted.
+        assert_eq!(parse(Some("hello.aleo")).unwrap(), ProgramID::from_str("hello.aleo").unwrap());
+        // Ensure a conflicting program ID is rejected, naming both.
+        let error = parse(Some("token.aleo")).unwrap_err().to_string();
+        assert!(error.contains("token.aleo") && error.contains("hello.aleo"));
+
+        // Ensure the program ID is required if the manifest does not declare one.
+        std::fs::write(directory.join("program.json"), "{}").unwrap();
+        assert!(parse(None).is_err());
+        assert_eq!(parse(Some("token.aleo")).unwrap(), ProgramID::from_str("token.aleo").unwrap());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn clap_snarkos_deploy_wait_for_confirmation() {
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "deploy",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--record",
+            "RECORD",
+            "--broadcast",
+            "BROADCAST",
+            "--wait-for-confirmation",
+            "--confirmation-initial-delay-ms",
+            "500",
+            "--confirmation-multiplier",
+            "3",
+            "--confirmation-max-wait-secs",
+            "10",
+            "hello.aleo",
+        ];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Deploy(deploy)) = cli.command {
+            assert!(deploy.wait_for_confirmation);
+            // Ensure the delays grow exponentially, and are capped by the maximum total wait.
+            let delays = deploy.confirmation.delays();
+            let expected = [500, 1500, 4500, 3500].map(Duration::from_millis);
+            assert_eq!(delays, expected);
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure waiting for the confirmation requires a broadcast.
+        let cli = CLI::parse_from(vec![
+            "snarkos",
+            "developer",
+            "deploy",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--record",
+            "RECORD",
+            "--dry-run",
+            "--wait-for-confirmation",
+            "hello.aleo",
+        ]);
+        match cli.command {
+            Command::Developer(Developer::Deploy(mut deploy)) => {
+                assert!(deploy.apply_env_defaults(&HashMap::new()).is_err())
+            }
+            _ => panic!("Unexpected result of clap parsing!"),
+        }
+    }
+
+    #[test]
+    fn clap_snarkos_deploy_not_before() {
+        let args = ["snarkos", "developer", "deploy", "--private-key", "PRIVATE_KEY", "--query", "QUERY"];
+        let arg_vec = [&args[..], &["--record", "RECORD", "--store", "tx.json", "--not-before", "1700000000"]].concat();
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Deploy(deploy)) = cli.command {
+            assert_eq!(deploy.output.store, Some("tx.json".to_string()));
+            assert_eq!(deploy.output.not_before, Some(1700000000));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure the timestamp requires a stored transaction, and conflicts with embedding the verifying keys.
+        let arg_vec = [&args[..], &["--record", "RECORD", "--dry-run", "--not-before", "1700000000"]].concat();
+        assert!(CLI::try_parse_from(arg_vec).is_err());
+        let arg_vec =
+            [&args[..], &["--record", "RECORD", "--store", "tx.json", "--include-vks", "--not-before", "1"]].concat();
+        assert!(CLI::try_parse_from(arg_vec).is_err());
+    }
+
+    #[test]
+    fn clap_snarkos_deploy_state_file() {
+        let args = ["snarkos", "developer", "deploy", "--private-key", "PRIVATE_KEY", "--record", "RECORD"];
+        let arg_vec = [&args[..], &["--state-file", "state.json", "--max-state-age", "60", "--dry-run"]].concat();
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Deploy(deploy)) = cli.command {
+            assert!(deploy.endpoints.query.is_empty());
+            assert_eq!(deploy.endpoints.state_file, Some(PathBuf::from("state.json")));
+            assert_eq!(deploy.endpoints.max_state_age, 60);
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure the state file conflicts with contacting the network.
+        let arg_vec = [&args[..], &["--state-file", "state.json", "--query", "QUERY", "--dry-run"]].concat();
+        assert!(CLI::try_parse_from(arg_vec).is_err());
+        let arg_vec = [&args[..], &["--state-file", "state.json", "--broadcast", "BROADCAST"]].concat();
+        assert!(CLI::try_parse_from(arg_vec).is_err());
+        // Ensure either the state file or the query endpoint is required.
+        match CLI::parse_from([&args[..], &["--dry-run"]].concat()).command {
+            Command::Developer(Developer::Deploy(mut deploy)) => {
+                assert!(deploy.apply_env_defaults(&HashMap::new()).is_err())
+            }
+            _ => panic!("Unexpected result of clap parsing!"),
+        }
+    }
+
+    #[test]
+    fn clap_snarkos_deploy_local_genesis() {
+        let args =
+            ["snarkos", "developer", "deploy", "--private-key", "PRIVATE_KEY", "--local-genesis", "genesis.json"];
+        match CLI::parse_from(args).command {
+            Command::Developer(Developer::Deploy(mut deploy)) => {
+                assert_eq!(deploy.endpoints.local_genesis, Some(PathBuf::from("genesis.json")));
+                assert_eq!(deploy.endpoints.ledger_path, None);
+                // Ensure the local chain needs no query endpoint, and ignores the one in the environment.
+                let env = HashMap::from([(Deploy::QUERY_ENV.to_string(), "QUERY".to_string())]);
+                deploy.apply_env_defaults(&env).unwrap();
+                assert!(deploy.endpoints.query.is_empty());
+            }
+            _ => panic!("Unexpected result of clap parsing!"),
+        }
+
+        // Ensure the local chain conflicts with contacting the network.
+        assert!(CLI::try_parse_from([&args[..], &["--query", "QUERY"]].concat()).is_err());
+        assert!(CLI::try_parse_from([&args[..], &["--broadcast", "BROADCAST"]].concat()).is_err());
+        assert!(CLI::try_parse_from([&args[..], &["--state-file", "state.json"]].concat()).is_err());
+
+        // Ensure the ledger path is only given for a local chain.
+        let arg_vec = [&args[..], &["--ledger-path", "ledger"]].concat();
+        match CLI::parse_from(arg_vec).command {
+            Command::Developer(Developer::Deploy(deploy)) => {
+                assert_eq!(deploy.endpoints.ledger_path, Some(PathBuf::from("ledger")))
+            }
+            _ => panic!("Unexpected result of clap parsing!"),
+        }
+        let arg_vec = ["snarkos", "developer", "deploy", "--private-key", "PRIVATE_KEY", "--ledger-path", "ledger"];
+        assert!(CLI::try_parse_from(arg_vec).is_err());
+    }
+
+    #[test]
+    fn test_output_dir() {
+        let directory = std::env::temp_dir().join(format!("snarkos_output_dir_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        let (hello, token) = (ProgramID::from_str("hello.aleo").unwrap(), ProgramID::from_str("token.aleo").unwrap());
+
+        // Ensure the transaction files are named per program.
+        let path = Deploy::output_path(&directory, hello, false).unwrap();
+        assert_eq!(path, directory.join("hello.aleo.tx"));
+        std::fs::write(&path, "").unwrap();
+        Deploy::record_output(&directory, hello, "at1hello".to_string()).unwrap();
+        Deploy::record_output(&directory, token, "at1token".to_string()).unwrap();
+
+        // Ensure an existing transaction file is only replaced with --overwrite.
+        assert!(Deploy::output_path(&directory, hello, false).is_err());
+        assert_eq!(Deploy::output_path(&directory, hello, true).unwrap(), path);
+        Deploy::record_output(&directory, hello, "at1hello2".to_string()).unwrap();
+
+        // Ensure the manifest lists the deployments in order.
+        let manifest: DeployManifest =
+            serde_json::from_str(&std::fs::read_to_string(directory.join(Deploy::OUTPUT_MANIFEST)).unwrap()).unwrap();
+        let entry = |program_id: &str, transaction_id: &str| DeployManifestEntry {
+            program_id: program_id.to_string(),
+            transaction_id: transaction_id.to_string(),
+        };
+        assert_eq!(manifest.deployments, vec![entry("token.aleo", "at1token"), entry("hello.aleo", "at1hello2")]);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_slip10_derive() {
+        // Ensure the derivation matches the first test vector of SLIP-10.
+        let seed = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+        let master = slip10_derive(b"ed25519 seed", &seed, &[]);
+        assert_eq!(hex(&master), "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7");
+        let child = slip10_derive(b"ed25519 seed", &seed, &[0]);
+        assert_eq!(hex(&child), "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3");
+    }
+
+    #[test]
+    fn test_mnemonic() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let args = ["snarkos", "developer", "deploy", "--query", "QUERY", "--record", "RECORD", "--dry-run"];
+
+        // Ensure the private key is derived deterministically, per index.
+        let parse = |extra: &[&str]| match CLI::parse_from([&args[..], extra].concat()).command {
+            Command::Developer(Developer::Deploy(deploy)) => deploy,
+            _ => panic!("Unexpected result of clap parsing!"),
+        };
+        let deploy = parse(&["--mnemonic", mnemonic]);
+        assert_eq!(deploy.account.derivation_index, 0);
+        let private_key = deploy.account.private_key().unwrap();
+        assert_eq!(parse(&["--mnemonic", mnemonic]).account.private_key().unwrap(), private_key);
+        let private_key_1 = parse(&["--mnemonic", mnemonic, "--derivation-index", "1"]).account.private_key().unwrap();
+        assert_ne!(private_key_1, private_key);
+
+        // Ensure the private key seed is derived along the path of the index.
+        let seed = bip39::Mnemonic::parse(mnemonic).unwrap().to_seed("");
+        let key = slip10_derive(Developer::DERIVATION_CURVE, &seed, &[44, 683, 0, 0]);
+        assert_eq!(hex(&key), "c253767bf33bfafba853567bf7d613abfb48bdab5b2a2b5b734b1530ff2402cd");
+        let key_1 = slip10_derive(Developer::DERIVATION_CURVE, &seed, &[44, 683, 1, 0]);
+        assert_eq!(hex(&key_1), "6be39dabd985e8534a288512fcb90130befa995ad05f0955a97dc1bd7560491b");
+        let field = <CurrentNetwork as Environment>::Field::from_bytes_le_mod_order(&key);
+        assert_eq!(private_key, PrivateKey::try_from(Field::new(field)).unwrap());
+        // Ensure an index outside of the hardened range is rejected.
+        assert!(parse(&["--mnemonic", mnemonic, "--derivation-index", "2147483648"]).account.private_key().is_err());
+
+        // Ensure the mnemonic can be read from a file, ignoring surrounding whitespace.
+        let path = std::env::temp_dir().join(format!("snarkos-mnemonic-{}.txt", std::process::id()));
+        std::fs::write(&path, format!("  {mnemonic}\n")).unwrap();
+        let deploy = parse(&["--mnemonic-file", path.to_str().unwrap()]);
+        assert_eq!(deploy.account.private_key().unwrap(), private_key);
+        std::fs::remove_file(&path).unwrap();
+
+        // Ensure an invalid mnemonic is rejected.
+        let invalid = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+        let error = parse(&["--mnemonic", invalid]).account.private_key().unwrap_err();
+        assert!(error.to_string().contains("The mnemonic is invalid"));
+
+        // Ensure the mnemonic conflicts with the private key, and that one of them is required.
+        assert!(CLI::try_parse_from([&args[..], &["--mnemonic", mnemonic, "--private-key", "KEY"]].concat()).is_err());
+        assert!(CLI::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn clap_snarkos_deploy_imports() {
+        let args = ["snarkos", "developer", "deploy", "--private-key", "PRIVATE_KEY", "--query", "QUERY", "--dry-run"];
+        let arg_vec = [&args[..], &["--deploy-imports", "--import-record", "token.aleo=RECORD"]].concat();
+        match CLI::parse_from(arg_vec).command {
+            Command::Developer(Developer::Deploy(deploy)) => {
+                assert!(deploy.imports.deploy_imports);
+                assert_eq!(deploy.imports.import_records, vec!["token.aleo=RECORD".to_string()]);
+                assert!(!deploy.is_import);
+                assert_eq!(deploy.imports.parallel_deploys, 1);
+            }
+            _ => panic!("Unexpected result of clap parsing!"),
+        }
+
+        // Ensure the import records are only given to deploy the imports.
+        assert!(CLI::try_parse_from([&args[..], &["--import-record", "token.aleo=RECORD"]].concat()).is_err());
+        assert!(CLI::try_parse_from([&args[..], &["--parallel-deploys", "4"]].concat()).is_err());
+        // Ensure the imports are not deployed where their transactions would be lost or could not be checked.
+        assert!(CLI::try_parse_from([&args[..], &["--deploy-imports", "--store", "tx.json"]].concat()).is_err());
+        assert!(
+            CLI::try_parse_from([&args[..], &["--deploy-imports", "--unsigned-output", "u.json"]].concat()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_deployment_waves() {
+        let program_ids = ["token.aleo", "hello.aleo", "swap.aleo", "game.aleo", "bank.aleo"].map(String::from);
+        // The swap imports the token, the game imports the swap, and the hello imports a program outside the batch.
+        let imports = [vec![], vec!["credits.aleo"], vec!["token.aleo"], vec!["swap.aleo", "hello.aleo"], vec![]]
+            .map(|imports| imports.into_iter().map(String::from).collect::<Vec<_>>());
+
+        // Ensure the independent deployments are deployed together, and each dependent one after its imports.
+        assert_eq!(Deploy::deployment_waves(&program_ids, &imports, 4).unwrap(), vec![vec![0, 1, 4], vec![2], vec![3]]);
+        // Ensure a wave holds no more than the limit, preserving the order of the batch.
+        assert_eq!(Deploy::deployment_waves(&program_ids, &imports, 2).unwrap(), vec![
+            vec![0, 1],
+            vec![4],
+            vec![2],
+            vec![3]
+        ]);
+        // Ensure a limit of 1 deploys the programs one at a time, in an order respecting the imports.
+        assert_eq!(Deploy::deployment_waves(&program_ids, &imports, 1).unwrap(), vec![
+            vec![0],
+            vec![1],
+            vec![4],
+            vec![2],
+            vec![3]
+        ]);
+
+        // Ensure deployments that import one another are rejected.
+        let program_ids = ["a.aleo", "b.aleo"].map(String::from);
+        let imports = [vec!["b.aleo".to_string()], vec!["a.aleo".to_string()]];
+        assert!(Deploy::deployment_waves(&program_ids, &imports, 2).is_err());
+    }
+
+    #[test]
+    fn test_deploy_waves() {
+        // The swap imports the token and the util, which are independent of one another.
+        let program_ids = ["token.aleo", "util.aleo", "swap.aleo"].map(String::from);
+        let imports = [vec![], vec![], vec!["token.aleo", "util.aleo"]]
+            .map(|imports| imports.into_iter().map(String::from).collect::<Vec<_>>());
+        let waves = Deploy::deployment_waves(&program_ids, &imports, 2).unwrap();
+
+        // Ensure the independent programs are deployed concurrently, and the dependent one after its imports.
+        let events = parking_lot::Mutex::new(Vec::new());
+        let (running, max_running) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        Deploy::deploy_waves(&program_ids, &waves, |index| {
+            events.lock().push(format!("start {}", program_ids[index]));
+            max_running.fetch_max(running.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(100));
+            running.fetch_sub(1, Ordering::SeqCst);
+            events.lock().push(format!("end {}", program_ids[index]));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+        let events = events.into_inner();
+        let position = |event: &str| events.iter().position(|e| e == event).unwrap();
+        assert!(position("end token.aleo") < position("start swap.aleo"), "{events:?}");
+        assert!(position("end util.aleo") < position("start swap.aleo"), "{events:?}");
+
+        // Ensure a failed deployment stops the batch before the programs that depend on it.
+        let deployed = parking_lot::Mutex::new(Vec::new());
+        let error = Deploy::deploy_waves(&program_ids, &waves, |index| {
+            deployed.lock().push(index);
+            match index {
+                0 => bail!("rejected"),
+                _ => Ok(()),
+            }
+        })
+        .unwrap_err()
+        .to_string();
+        assert_eq!(error, "❌ Failed to deploy 'token.aleo': rejected\n  Not deployed: swap.aleo");
+        assert!(!deployed.into_inner().contains(&2));
+    }
+
+    #[test]
+    fn test_import_record() {
+        let import_records = ["token.aleo=RECORD1".to_string(), " swap.aleo = RECORD2 ".to_string()];
+        assert_eq!(Deploy::import_record(&import_records, "token.aleo").unwrap(), "RECORD1");
+        assert_eq!(Deploy::import_record(&import_records, "swap.aleo").unwrap(), "RECORD2");
+        // Ensure an import without a record is refused, naming the flag to give it with.
+        let error = Deploy::import_record(&import_records, "game.aleo").unwrap_err().to_string();
+        assert!(error.contains("--import-record game.aleo=RECORD"), "{error}");
+        // Ensure a malformed import record is refused.
+        let error = Deploy::import_record(&["RECORD".to_string()], "token.aleo").unwrap_err().to_string();
+        assert!(error.contains("PROGRAM_ID=RECORD"), "{error}");
+    }
+
+    #[test]
+    fn test_missing_imports() {
+        let directory = std::env::temp_dir().join(format!("snarkos_missing_imports_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+
+        // The swap imports the token and the deployed base, and the token imports the missing util.
+        let program = |id: &str, imports: &[&str]| {
+            let imports = imports.iter().map(|import| format!("import {import};\n")).collect::<String>();
+            format!(
+                "{imports}program {id};\n\nfunction main:\n    input r0 as u32.public;\n    output r0 as u32.public;\n"
+            )
+        };
+        std::fs::write(directory.join("util.aleo"), program("util.aleo", &[])).unwrap();
+        std::fs::write(directory.join("token.aleo"), program("token.aleo", &["util.aleo"])).unwrap();
+        std::fs::write(directory.join("swap.aleo"), program("swap.aleo", &["token.aleo", "base.aleo"])).unwrap();
+        let (url, _) =
+            mock_http_server(usize::MAX, |request| match request.starts_with("GET /testnet3/program/base.aleo ") {
+                true => ("200 OK", "{}".to_string()),
+                false => ("404 Not Found", String::new()),
+            });
+
+        // Ensure the missing imports are found, including those imported indirectly, but not the deployed ones.
+        let programs = Deploy::missing_imports(&["swap.aleo".to_string()], &directory, &url).unwrap();
+        let program_ids = programs.iter().map(|program| program.id().to_string()).collect::<Vec<_>>();
+        assert_eq!(program_ids, ["swap.aleo", "token.aleo", "util.aleo"]);
+        // Ensure the batch ordering deploys each import after the imports it imports.
+        let imports = programs
+            .iter()
+            .map(|program| program.imports().keys().map(|import_id| import_id.to_string()).collect())
+            .collect::<Vec<_>>();
+        assert_eq!(Deploy::deployment_waves(&program_ids, &imports, 1).unwrap().concat(), [2, 1, 0]);
+
+        // Ensure an import missing from the imports directory is reported.
+        let error = Deploy::missing_imports(&["game.aleo".to_string()], &directory, &url).unwrap_err().to_string();
+        assert!(error.contains("Unable to read the import 'game.aleo'"), "{error}");
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_require_fee_record() {
+        assert_eq!(Deploy::require_fee_record(Some("RECORD"), 0).unwrap(), "RECORD");
+        assert_eq!(Deploy::require_fee_record(Some("RECORD"), 100).unwrap(), "RECORD");
+        // Ensure a missing fee record is reported, whether or not a priority fee is paid.
+        let error = Deploy::require_fee_record::<&str>(None, 0).unwrap_err();
+        assert!(error.to_string().contains("The network requires a fee"), "{error}");
+        let error = Deploy::require_fee_record::<&str>(None, 100).unwrap_err();
+        assert!(error.to_string().contains("the priority fee is 100 microcredits"), "{error}");
+    }
+
+    #[test]
+    fn test_free_deployment_requires_fee_record() {
+        // A deployment with --fee 0, on a network without a minimum deployment cost, pays no fee at all.
+        let private_key = PrivateKey::<CurrentNetwork>::new(&mut TestRng::default()).unwrap().to_string();
+        let arg_vec = ["snarkos", "developer", "deploy", "hello.aleo", "--private-key", &private_key, "--fee", "0"];
+        let arg_vec = [&arg_vec[..], &["--query", "http://127.0.0.1:1", "--skip-network-check", "--dry-run"]].concat();
+        let deploy = match CLI::parse_from(arg_vec).command {
+            Command::Developer(Developer::Deploy(deploy)) => deploy,
+            _ => panic!("Unexpected result of clap parsing!"),
+        };
+
+        // Ensure it is refused before anything is generated or the query endpoint is contacted, as its fee
+        // transition still spends a record.
+        let error = deploy.parse().unwrap_err();
+        assert!(error.to_string().contains("The network requires a fee"), "{error}");
+    }
+
+    #[test]
+    fn test_replica_lag_warning() {
+        // Ensure a replica within the threshold, or ahead of the primary, is not warned about.
+        assert_eq!(Deploy::replica_lag_warning(100, 110, 10), None);
+        assert_eq!(Deploy::replica_lag_warning(120, 110, 10), None);
+        // Ensure a replica beyond the threshold is warned about.
+        let warning = Deploy::replica_lag_warning(100, 111, 10).unwrap();
+        assert!(warning.contains("11 blocks behind"));
+    }
+
+    #[test]
+    fn test_check_program_size() {
+        // Ensure a program within the budget, or an unset budget, is accepted.
+        Deploy::check_program_size(1000, 1000).unwrap();
+        Deploy::check_program_size(usize::MAX, 0).unwrap();
+        // Ensure a program beyond the budget is rejected, with its actual size.
+        let error = Deploy::check_program_size(1001, 1000).unwrap_err();
+        assert!(error.to_string().contains("1001 bytes"));
+    }
+
+    #[test]
+    fn clap_snarkos_deploy_multiple_queries() {
+        // Ensure the query endpoints may be repeated, or given as a list, in order of preference.
+        let args = ["snarkos", "developer", "deploy", "--private-key", "PRIVATE_KEY", "--record", "RECORD"];
+        for queries in [&["--query", "PRIMARY", "--query", "SECONDARY"][..], &["--query", "PRIMARY,SECONDARY"][..]] {
+            let cli = CLI::parse_from([&args[..], queries].concat());
+            if let Command::Developer(Developer::Deploy(deploy)) = cli.command {
+                assert_eq!(deploy.endpoints.query, vec!["PRIMARY".to_string(), "SECONDARY".to_string()]);
+            } else {
+                panic!("Unexpected result of clap parsing!");
+            }
+        }
+    }
+
+    #[test]
+    fn test_query_endpoints() {
+        // Start a live node that serves its latest height, and find an address without one.
+        let (live, receiver) = mock_http_sequence(vec![("200 OK", "5".to_string()), ("200 OK", "6".to_string())]);
+        let dead = format!("http://{}", TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap());
+
+        // Ensure a request fails over from the dead endpoint to the live one.
+        let query_endpoints = QueryEndpoints::new(vec![dead.clone(), live.clone()]);
+        assert_eq!(query_endpoints.current(), dead);
+        assert_eq!(query_endpoints.request(Developer::fetch_latest_height).unwrap(), 5);
+        assert_eq!(query_endpoints.current(), live);
+        // Ensure the next request starts from the live endpoint.
+        let mut requested = Vec::new();
+        let height = query_endpoints.request(|endpoint| {
+            requested.push(endpoint.to_string());
+            Developer::fetch_latest_height(endpoint)
+        });
+        assert_eq!(height.unwrap(), 6);
+        assert_eq!(requested, [live.clone()]);
+        assert_eq!(receiver.iter().count(), 2);
+
+        // Ensure a request that no endpoint serves is refused, naming the endpoints.
+        let query_endpoints = QueryEndpoints::new(vec![dead.clone(), dead.clone()]);
+        let error = query_endpoints.request(Developer::fetch_latest_height).unwrap_err().to_string();
+        assert!(error.contains("all of the query endpoints failed"), "{error}");
+        // Ensure the error of a single endpoint is returned as is.
+        let query_endpoints = QueryEndpoints::new(vec![dead.clone()]);
+        let error = query_endpoints.request(|_| -> Result<()> { bail!("unreachable") }).unwrap_err();
+        assert_eq!(error.to_string(), "unreachable");
+    }
+
+    #[test]
+    fn test_check_defines() {
+        assert!(Deploy::check_defines(&[]).is_ok());
+        // Ensure malformed overrides are refused.
+        assert!(Deploy::check_defines(&["ADMIN".to_string()]).unwrap_err().to_string().contains("KEY=VALUE"));
+        assert!(Deploy::check_defines(&["=aleo1".to_string()]).unwrap_err().to_string().contains("KEY=VALUE"));
+        // Ensure each override is reported as an unknown constant.
+        let defines = ["ADMIN=aleo1".to_string(), "SUPPLY=100u64".to_string()];
+        let error = Deploy::check_defines(&defines).unwrap_err().to_string();
+        assert!(error.contains("Unknown program constants: ADMIN, SUPPLY"), "{error}");
+    }
+
+    #[test]
+    fn test_check_valid_until() {
+        // Ensure the deadline is refused, with the reason, as the current network does not support expiry.
+        let error = Deploy::check_valid_until(100).unwrap_err();
+        assert!(error.to_string().contains("--valid-until 100"));
+        assert!(error.to_string().contains("does not support transaction expiry"));
+    }
+
+    #[test]
+    fn test_check_network() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+
+        // Ensure an endpoint serving the genesis block of the current network is accepted.
+        let (url, receiver) = mock_http_sequence(vec![("200 OK", serde_json::to_string(&genesis).unwrap())]);
+        Developer::check_network(&url).unwrap();
+        assert!(receiver.recv().unwrap().starts_with("GET /testnet3/block/0 "));
+
+        // Ensure an endpoint on another network is refused, naming the expected network and both genesis blocks.
+        let other = serde_json::json!({ "block_hash": "ab1other" }).to_string();
+        let (url, _) = mock_http_sequence(vec![("200 OK", other)]);
+        let error = Developer::check_network(&url).unwrap_err().to_string();
+        assert!(error.contains(CurrentNetwork::NAME), "{error}");
+        assert!(error.contains("ab1other") && error.contains(&genesis.hash().to_string()), "{error}");
+
+        // Ensure an endpoint that does not serve the genesis block is not refused.
+        let (url, _) = mock_http_sequence(vec![("404 Not Found", String::new())]);
+        Developer::check_network(&url).unwrap();
+    }
+
+    #[test]
+    fn test_network_fee_report() {
+        let congested = NetworkConditions { pending_transactions: 50, latest_block_transactions: 10 };
+        let idle = NetworkConditions { pending_transactions: 5, latest_block_transactions: 10 };
+
+        // Ensure a deployment without a priority fee is warned about on a congested network only.
+        assert!(congested.fee_report(0).contains("⚠️"));
+        assert!(!idle.fee_report(0).contains("⚠️"));
+        // Ensure a priority fee silences the warning.
+        assert!(!congested.fee_report(1).contains("⚠️"));
+        assert!(congested.fee_report(1).contains("50 pending transactions, 10 in the latest block"));
+    }
+
+    #[test]
+    fn test_deployment_event_webhook() {
+        // Start a mock webhook that accepts a single event, and forwards it to the test.
+        let (url, receiver) = mock_http_sequence(vec![("200 OK", String::new())]);
+
+        let event = DeploymentEvent {
+            event: "proving_complete",
+            program_id: "hello.aleo".to_string(),
+            deployment_id: Some("1field".to_string()),
+            transaction_id: Some("at1".to_string()),
+            fee: Some(1_500_000),
+        };
+        event.notify(&Some(format!("{url}/events")));
+
+        // Ensure the event was posted as JSON.
+        let request = receiver.recv().unwrap();
+        assert!(request.starts_with("POST /events HTTP/1.1\r\n"));
+        let body: serde_json::Value = serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "event": "proving_complete",
+                "program_id": "hello.aleo",
+                "deployment_id": "1field",
+                "transaction_id": "at1",
+                "fee": 1_500_000
+            })
+        );
+
+        // Ensure an unreachable webhook does not abort the deployment, and no webhook posts nothing.
+        let dead = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        event.notify(&Some(format!("http://{dead}/events")));
+        event.notify(&None);
+
+        // Ensure a webhook that never responds is given up on once the timeout elapses.
+        let silent = TcpListener::bind("127.0.0.1:0").unwrap();
+        let timer = Instant::now();
+        event.post(&format!("http://{}/events", silent.local_addr().unwrap()), Duration::from_millis(200));
+        assert!(timer.elapsed() < Duration::from_secs(2), "{:?}", timer.elapsed());
+    }
+
+    #[test]
+    fn test_fee_display() {
+        // Ensure the fees are displayed exactly in each unit.
+        assert_eq!(FeeDisplay::Micro.format(1_500_000), "1500000 microcredits");
+        assert_eq!(FeeDisplay::Credits.format(1_500_000), "1.500000 credits");
+        assert_eq!(FeeDisplay::Credits.format(7), "0.000007 credits");
+        assert_eq!(
+            FeeDisplay::Both.format(u64::MAX),
+            "18446744073709.551615 credits (18446744073709551615 microcredits)"
+        );
+
+        // Ensure the unit is parsed from the command line, including under its alias.
+        for flag in ["--fee-display", "--fee-currency"] {
+            let arg_vec =
+                vec!["snarkos", "developer", "deploy", "--private-key", "PK", "--query", "QUERY", flag, "credits"];
+            if let Command::Developer(Developer::Deploy(deploy)) = CLI::parse_from(arg_vec).command {
+                assert_eq!(deploy.fees.fee_display, FeeDisplay::Credits);
+            } else {
+                panic!("Unexpected result of clap parsing!");
+            }
+        }
+    }
+
+    #[test]
+    fn test_mempool_rejections() {
+        let thresholds = MempoolThresholds { max_size: 1000, min_priority_fee: 10 };
+
+        // Ensure a transaction within the thresholds is admitted.
+        assert!(thresholds.rejections(1000, 10).is_empty());
+        // Ensure each exceeded threshold is reported.
+        assert_eq!(thresholds.rejections(1001, 10).len(), 1);
+        assert_eq!(thresholds.rejections(1000, 9).len(), 1);
+        let rejections = thresholds.rejections(1001, 9);
+        assert!(rejections[0].contains("1001 bytes") && rejections[1].contains("9 microcredits"));
+    }
+
+    #[test]
+    fn test_simulate_mempool() {
+        let deploy = |extra: &[&'static str]| {
+            let mut arg_vec = vec![
+                "snarkos",
+                "developer",
+                "deploy",
+                "--private-key",
+                "PRIVATE_KEY",
+                "--query",
+                "QUERY",
+                "--broadcast",
+                "BROADCAST",
+                "--validate-first",
+            ];
+            arg_vec.extend_from_slice(extra);
+            arg_vec.push("verify_transaction.aleo");
+            match CLI::parse_from(arg_vec).command {
+                Command::Developer(Developer::Deploy(deploy)) => deploy,
+                _ => panic!("Unexpected result of clap parsing!"),
+            }
+        };
+        let transaction = sample_deployment_transaction();
+
+        // Ensure a valid deployment is admitted. The query endpoint is not reached, as the program has no imports.
+        let output = deploy(&[]).simulate_mempool(&transaction, 0, "http://127.0.0.1:1").unwrap();
+        assert_eq!(output, format!("✅ Transaction {} would be admitted into the mempool", transaction.id()));
+
+        // Ensure the deployment is rejected if it exceeds the thresholds.
+        let error =
+            deploy(&["--mempool-max-size", "1"]).simulate_mempool(&transaction, 0, "http://127.0.0.1:1").unwrap_err();
+        assert!(error.to_string().contains("exceeds the limit of 1 bytes"), "{error}");
+    }
+
+    #[test]
+    fn test_confirm_redeploy() {
+        let program_id = ProgramID::from_str("hello.aleo").unwrap();
+
+        // Ensure the redeployment proceeds only when confirmed.
+        assert!(Deploy::confirm_redeploy(program_id, &mut "y\n".as_bytes()).is_ok());
+        assert!(Deploy::confirm_redeploy(program_id, &mut "YES\n".as_bytes()).is_ok());
+        assert!(Deploy::confirm_redeploy(program_id, &mut "n\n".as_bytes()).is_err());
+        assert!(Deploy::confirm_redeploy(program_id, &mut "\n".as_bytes()).is_err());
+        assert!(Deploy::confirm_redeploy(program_id, &mut "".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_manifest_fee() {
+        let directory = std::env::temp_dir().join(format!("snarkos_manifest_fee_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = Some(directory.to_string_lossy().to_string());
+
+        // Ensure the fee is read from the manifest.
+        std::fs::write(directory.join("program.json"), r#"{"program": "hello.aleo", "fee": 1000}"#).unwrap();
+        assert_eq!(Deploy::manifest_fee(&path).unwrap(), Some(1000));
+
+        // Ensure a missing fee is not an error.
+        std::fs::write(directory.join("program.json"), r#"{"program": "hello.aleo"}"#).unwrap();
+        assert_eq!(Deploy::manifest_fee(&path).unwrap(), None);
+
+        // Ensure an invalid fee is an error.
+        std::fs::write(directory.join("program.json"), r#"{"program": "hello.aleo", "fee": "1000"}"#).unwrap();
+        assert!(Deploy::manifest_fee(&path).is_err());
+
+        std::fs::remove_dir_all(&directory).unwrap();
     }
 }
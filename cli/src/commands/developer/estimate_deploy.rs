@@ -0,0 +1,114 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{CurrentAleo, CurrentNetwork, Developer};
+
+use snarkvm::{package::Package, prelude::deployment_cost};
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::{fmt::Write, path::PathBuf};
+
+/// Estimates the cost of deploying one or more Aleo programs, without creating any transactions.
+#[derive(Debug, Parser)]
+pub struct EstimateDeploy {
+    /// The paths to the directories containing the manifest files of the programs.
+    #[clap(required = true)]
+    paths: Vec<PathBuf>,
+    /// Prints the verifying-key ID of each function in the deployments.
+    #[clap(long)]
+    print_vk_ids: bool,
+}
+
+impl EstimateDeploy {
+    /// Estimates the per-program and aggregate deployment costs.
+    pub fn parse(self) -> Result<String> {
+        let mut output = String::new();
+        let (mut total_storage_cost, mut total_namespace_cost, mut total_cost) = (0u64, 0u64, 0u64);
+
+        for path in &self.paths {
+            // Load the package.
+            let package = Package::<CurrentNetwork>::open(path)?;
+            let program_id = *package.program_id();
+
+            println!("📦 Estimating the deployment cost for '{}'...", program_id.to_string().bold());
+
+            // Generate the deployment, without signing a fee.
+            let deployment = package.deploy::<CurrentAleo>(None)?;
+
+            // Compute the deployment cost.
+            let (cost, (storage_cost, namespace_cost)) = deployment_cost(&deployment)?;
+            writeln!(
+                output,
+                "{program_id}: {cost} microcredits (storage: {storage_cost}, namespace: {namespace_cost})"
+            )?;
+
+            // Determine if the verifying-key IDs should be included.
+            if self.print_vk_ids {
+                for (function_name, id) in Developer::verifying_key_ids(&deployment)? {
+                    writeln!(output, "  {function_name}: {id}")?;
+                }
+            }
+
+            // Accumulate the aggregate costs. Each program pays for its own namespace,
+            // as program namespaces are not shared between deployments.
+            total_storage_cost = total_storage_cost
+                .checked_add(storage_cost)
+                .ok_or_else(|| anyhow!("The aggregate storage cost overflowed"))?;
+            total_namespace_cost = total_namespace_cost
+                .checked_add(namespace_cost)
+                .ok_or_else(|| anyhow!("The aggregate namespace cost overflowed"))?;
+            total_cost = total_cost.checked_add(cost).ok_or_else(|| anyhow!("The aggregate cost overflowed"))?;
+        }
+
+        write!(
+            output,
+            "Total ({} programs): {total_cost} microcredits (storage: {total_storage_cost}, namespace: {total_namespace_cost})",
+            self.paths.len()
+        )?;
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, Developer, CLI};
+
+    #[test]
+    fn clap_snarkos_estimate_deploy() {
+        let arg_vec = vec!["snarkos", "developer", "estimate-deploy", "hello", "token"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::EstimateDeploy(estimate)) = cli.command {
+            assert_eq!(estimate.paths, vec![PathBuf::from("hello"), PathBuf::from("token")]);
+            assert!(!estimate.print_vk_ids);
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        let arg_vec = vec!["snarkos", "developer", "estimate-deploy", "--print-vk-ids", "hello"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::EstimateDeploy(estimate)) = cli.command {
+            assert!(estimate.print_vk_ids);
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure at least one path is required.
+        assert!(CLI::try_parse_from(vec!["snarkos", "developer", "estimate-deploy"]).is_err());
+    }
+}
@@ -32,6 +32,9 @@ use colored::Colorize;
 use std::str::FromStr;
 
 /// Executes an Aleo program function.
+///
+/// An execution transaction proves a single top-level function call, along with the transitions of any
+/// functions it calls. To compose several calls atomically, execute a program function that calls them.
 #[derive(Debug, Parser)]
 pub struct Execute {
     /// The program identifier.
@@ -116,12 +119,12 @@ impl Execute {
         println!("✅ Created execution transaction for '{}'", locator.to_string().bold());
 
         // Determine if the transaction should be broadcast, stored, or displayed to user.
-        Developer::handle_transaction(self.broadcast, self.dry_run, self.store, transaction, locator.to_string())
+        Developer::handle_transaction(self.broadcast, self.dry_run, self.store, transaction, locator.to_string(), 0)
     }
 }
 
 /// A helper function to recursively load the program and all of its imports into the process.
-fn load_program(
+pub(super) fn load_program(
     endpoint: &str,
     process: &mut Process<CurrentNetwork>,
     program_id: &ProgramID<CurrentNetwork>,
@@ -0,0 +1,256 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{CurrentNetwork, Developer};
+
+use snarkvm::prelude::{Field, Identifier, Network, Plaintext, PrivateKey, ProgramID, Record};
+
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The node state needed to generate the fee of a deployment offline, exported by `developer export-state`.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfflineState {
+    /// The version of the state file format.
+    pub version: u8,
+    /// The ID of the network the state was exported from.
+    pub network_id: u16,
+    /// The block height the state was exported at.
+    pub height: u32,
+    /// The time of the export, as a UNIX timestamp in seconds.
+    pub exported_at: u64,
+    /// The commitment of the fee record the state path is for.
+    pub commitment: String,
+    /// The latest state root, as served by the node.
+    pub state_root: Value,
+    /// The state path of the fee record, as served by the node.
+    pub state_path: Value,
+}
+
+impl OfflineState {
+    /// The current version of the state file format.
+    pub const VERSION: u8 = 1;
+
+    /// Reads the state file, ensuring it is well-formed, was exported for the given fee record on the current
+    /// network, and is no older than the given number of seconds.
+    pub(super) fn read(
+        path: &Path,
+        fee_record: &Record<CurrentNetwork, Plaintext<CurrentNetwork>>,
+        max_age_secs: u64,
+    ) -> Result<Self> {
+        let state: Self = match serde_json::from_str(&std::fs::read_to_string(path)?) {
+            Ok(state) => state,
+            Err(error) => bail!("❌ The state file '{}' is malformed: {error}", path.display()),
+        };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        state.check(&Self::commitment(fee_record)?.to_string(), now, max_age_secs)?;
+        Ok(state)
+    }
+
+    /// Ensures the state matches the current format, network, and fee record commitment, and is not stale.
+    fn check(&self, commitment: &str, now: u64, max_age_secs: u64) -> Result<()> {
+        ensure!(
+            self.version == Self::VERSION,
+            "❌ The state file is version {}, but version {} is expected. Export the state again.",
+            self.version,
+            Self::VERSION
+        );
+        ensure!(
+            self.network_id == CurrentNetwork::ID,
+            "❌ The state file was exported from network {}, but the deployment targets network {}",
+            self.network_id,
+            CurrentNetwork::ID
+        );
+        ensure!(
+            self.commitment == commitment,
+            "❌ The state file was exported for the record with commitment {}, not the given fee record ({commitment})",
+            self.commitment
+        );
+        let age = now.saturating_sub(self.exported_at);
+        ensure!(
+            age <= max_age_secs,
+            "❌ The state file is stale, as it was exported {age}s ago (at height {}). Export the state again, or raise --max-state-age.",
+            self.height
+        );
+        Ok(())
+    }
+
+    /// Serves the state on a local endpoint, in place of the query endpoint of a node, and returns its URL.
+    /// Only the latest state root and the state path of the fee record are served.
+    pub(super) fn serve(self) -> Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let endpoint = format!("http://{}", listener.local_addr()?);
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                // Read the request line, e.g. `GET /testnet3/latest/stateRoot HTTP/1.1`, followed by the headers.
+                let mut reader = BufReader::new(&stream);
+                let mut request_line = String::new();
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+                let mut header = String::new();
+                while reader.read_line(&mut header).map_or(false, |num_bytes| num_bytes > 0) && header != "\r\n" {
+                    header.clear();
+                }
+                let route = request_line.split_whitespace().nth(1).unwrap_or_default();
+                let (status, body) = match route {
+                    route if route.ends_with("/latest/stateRoot") => ("200 OK", self.state_root.to_string()),
+                    route if route.ends_with(&format!("/statePath/{}", self.commitment)) => {
+                        ("200 OK", self.state_path.to_string())
+                    }
+                    route => ("404 Not Found", format!("\"'{route}' is not in the offline state\"")),
+                };
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        Ok(endpoint)
+    }
+
+    /// Returns the commitment of the given `credits.aleo` fee record.
+    fn commitment(record: &Record<CurrentNetwork, Plaintext<CurrentNetwork>>) -> Result<Field<CurrentNetwork>> {
+        record.to_commitment(&ProgramID::from_str("credits.aleo")?, &Identifier::from_str("credits")?)
+    }
+}
+
+/// Exports the node state needed by `developer deploy --state-file` to generate a deployment offline.
+#[derive(Debug, Parser)]
+pub struct ExportState {
+    /// The endpoint to query node state from.
+    #[clap(short, long)]
+    query: String,
+    /// The private key that owns the fee record.
+    #[clap(short, long)]
+    private_key: String,
+    /// The record to spend the fee from, offline.
+    #[clap(short, long)]
+    record: String,
+    /// The path to write the state file to.
+    output: PathBuf,
+}
+
+impl ExportState {
+    /// Exports the latest state root and the state path of the fee record to the state file.
+    pub fn parse(self) -> Result<String> {
+        // Retrieve the fee record, and ensure it is unspent, as this can not be checked offline.
+        let private_key = PrivateKey::<CurrentNetwork>::from_str(&self.private_key)?;
+        let fee_record = Developer::parse_record(&private_key, &self.record)?;
+        Developer::ensure_record_unspent(&private_key, &fee_record, &self.query)?;
+
+        // Ensure the query endpoint is on the current network.
+        Developer::check_network(&self.query)?;
+
+        // Fetch the state.
+        let commitment = OfflineState::commitment(&fee_record)?.to_string();
+        let state = OfflineState {
+            version: OfflineState::VERSION,
+            network_id: CurrentNetwork::ID,
+            height: Developer::fetch_latest_height(&self.query)?,
+            exported_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            state_root: Developer::fetch_json(&format!("{}/testnet3/latest/stateRoot", self.query))?,
+            state_path: Developer::fetch_json(&format!("{}/testnet3/statePath/{commitment}", self.query))?,
+            commitment,
+        };
+
+        // Write the state file.
+        std::fs::write(&self.output, serde_json::to_string_pretty(&state)?)?;
+        Ok(format!("✅ Exported the state at height {} to {}", state.height, self.output.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, CLI};
+
+    /// Returns a sample state, exported at the given time for the given commitment.
+    fn sample_state(exported_at: u64, commitment: &str) -> OfflineState {
+        OfflineState {
+            version: OfflineState::VERSION,
+            network_id: CurrentNetwork::ID,
+            height: 100,
+            exported_at,
+            commitment: commitment.to_string(),
+            state_root: Value::String("ar1root".to_string()),
+            state_path: Value::String("path1".to_string()),
+        }
+    }
+
+    #[test]
+    fn clap_snarkos_export_state() {
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "export-state",
+            "--query",
+            "QUERY",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--record",
+            "RECORD",
+            "state.json",
+        ];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::ExportState(export_state)) = cli.command {
+            assert_eq!(export_state.query, "QUERY");
+            assert_eq!(export_state.record, "RECORD");
+            assert_eq!(export_state.output, PathBuf::from("state.json"));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+    }
+
+    #[test]
+    fn test_offline_state_check() {
+        // Ensure a fresh state for the fee record is accepted.
+        sample_state(1000, "1field").check("1field", 1060, 60).unwrap();
+
+        // Ensure a stale state is rejected.
+        let error = sample_state(1000, "1field").check("1field", 1061, 60).unwrap_err();
+        assert!(error.to_string().contains("stale"));
+        // Ensure a state for another record is rejected.
+        assert!(sample_state(1000, "1field").check("2field", 1000, 60).is_err());
+        // Ensure a state of another version or network is rejected.
+        assert!(OfflineState { version: 0, ..sample_state(1000, "1field") }.check("1field", 1000, 60).is_err());
+        assert!(OfflineState { network_id: 1, ..sample_state(1000, "1field") }.check("1field", 1000, 60).is_err());
+    }
+
+    #[test]
+    fn test_offline_state_serve() {
+        let endpoint = sample_state(1000, "1field").serve().unwrap();
+
+        // Ensure the state is served in place of the query endpoint.
+        let state_root: Value =
+            ureq::get(&format!("{endpoint}/testnet3/latest/stateRoot")).call().unwrap().into_json().unwrap();
+        assert_eq!(state_root, Value::String("ar1root".to_string()));
+        let state_path: Value =
+            ureq::get(&format!("{endpoint}/testnet3/statePath/1field")).call().unwrap().into_json().unwrap();
+        assert_eq!(state_path, Value::String("path1".to_string()));
+        // Ensure any other request is not found.
+        assert!(ureq::get(&format!("{endpoint}/testnet3/statePath/2field")).call().is_err());
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{Convert, DeploymentBundle};
+
+use snarkvm::prelude::block::Transaction;
+
+use anyhow::Result;
+use clap::Parser;
+use std::{fmt::Write, path::PathBuf};
+
+/// Inspects a stored transaction.
+#[derive(Debug, Parser)]
+pub struct Inspect {
+    /// The path to the stored transaction file.
+    path: PathBuf,
+}
+
+impl Inspect {
+    /// Inspects the stored transaction, listing any verifying keys archived alongside it.
+    pub fn parse(self) -> Result<String> {
+        // Read the stored file.
+        let contents = std::fs::read(&self.path)?;
+
+        // Deserialize the file as a deployment bundle, or as a plain transaction.
+        let (transaction, verifying_keys) = match serde_json::from_slice::<DeploymentBundle>(&contents) {
+            Ok(bundle) => (bundle.transaction, Some(bundle.verifying_keys)),
+            Err(_) => (Convert::read_transaction(&contents)?, None),
+        };
+
+        // Summarize the transaction.
+        let mut output = String::new();
+        match &transaction {
+            Transaction::Deploy(_, _, deployment, _) => {
+                writeln!(output, "Deployment {} ('{}')", transaction.id(), deployment.program_id())?
+            }
+            Transaction::Execute(..) => writeln!(output, "Execution {}", transaction.id())?,
+            Transaction::Fee(..) => writeln!(output, "Fee {}", transaction.id())?,
+        }
+
+        // List the archived verifying keys.
+        match verifying_keys {
+            Some(verifying_keys) => {
+                writeln!(output, "Verifying keys:")?;
+                for (function_name, verifying_key) in verifying_keys {
+                    writeln!(output, "  - {function_name}: {verifying_key}")?;
+                }
+            }
+            None => writeln!(output, "No verifying keys were archived with this transaction")?,
+        }
+
+        Ok(output.trim_end().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, Developer, CLI};
+
+    #[test]
+    fn clap_snarkos_inspect() {
+        let arg_vec = vec!["snarkos", "developer", "inspect", "deployment.json"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Inspect(inspect)) = cli.command {
+            assert_eq!(inspect.path, PathBuf::from("deployment.json"));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+    }
+}
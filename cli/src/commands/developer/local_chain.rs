@@ -0,0 +1,353 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CurrentNetwork;
+
+use snarkvm::{
+    console::program::{Entry, Literal},
+    prelude::{
+        block::{Block, Transaction},
+        store::{helpers::memory::ConsensusMemory, ConsensusStore},
+        Address,
+        FromBytes,
+        Identifier,
+        Ledger,
+        Plaintext,
+        PrivateKey,
+        Program,
+        Record,
+        RecordsFilter,
+        ToBytes,
+        Value,
+        ViewKey,
+        VM,
+    },
+};
+
+use anyhow::{anyhow, bail, ensure, Result};
+use rand::{CryptoRng, Rng};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The genesis of a local chain, given as JSON, e.g.
+/// `{ "validator": "APrivateKey1...", "balances": { "aleo1...": 10000000000 } }`.
+#[derive(Debug, Deserialize)]
+pub struct GenesisConfig {
+    /// The private key of the validator, to which the genesis block mints the starting supply, and which signs
+    /// every block. The pinned snarkVM signs a chain with a single key, so the validator set has one member.
+    pub validator: String,
+    /// The initial balances in microcredits, by address, which are minted in the blocks after the genesis block.
+    #[serde(default)]
+    pub balances: BTreeMap<String, u64>,
+}
+
+impl GenesisConfig {
+    /// Reads the genesis configuration from the given JSON file.
+    pub fn read(path: &Path) -> Result<Self> {
+        let config = std::fs::read_to_string(path)
+            .map_err(|error| anyhow!("❌ Unable to read the genesis configuration {}: {error}", path.display()))?;
+        serde_json::from_str(&config)
+            .map_err(|error| anyhow!("❌ The genesis configuration {} is malformed: {error}", path.display()))
+    }
+}
+
+/// A chain that runs in-process, on an in-memory ledger, to deploy to without a network.
+/// The chain may be persisted as its blocks in a directory, which are replayed to resume it in a later run.
+pub struct LocalChain {
+    /// The ledger of the chain.
+    ledger: Ledger<CurrentNetwork, ConsensusMemory<CurrentNetwork>>,
+    /// The private key of the validator, which signs every block.
+    validator: PrivateKey<CurrentNetwork>,
+    /// The directory the blocks are persisted to, if any.
+    path: Option<PathBuf>,
+}
+
+impl LocalChain {
+    /// The extension of the files the blocks are persisted to, which are named by their height.
+    const BLOCK_EXTENSION: &str = "block";
+    /// The fee in microcredits of each transaction that mints an initial balance.
+    const MINT_FEE_IN_MICROCREDITS: u64 = 3000;
+
+    /// Initializes the chain from the given genesis configuration, and mints the initial balances, one per block.
+    pub fn new<R: Rng + CryptoRng>(config: &GenesisConfig, rng: &mut R) -> Result<Self> {
+        Self::initialize(config, None, rng)
+    }
+
+    /// Resumes the chain persisted to the given directory, or initializes it there from the given genesis
+    /// configuration if the directory holds no chain yet. The directory is created if it is missing.
+    pub fn open<R: Rng + CryptoRng>(config: &GenesisConfig, path: &Path, rng: &mut R) -> Result<Self> {
+        if path.exists() && !path.is_dir() {
+            bail!("❌ The ledger path {} is not a directory", path.display())
+        }
+        std::fs::create_dir_all(path)
+            .map_err(|error| anyhow!("❌ Unable to create the ledger path {}: {error}", path.display()))?;
+
+        let mut blocks = Self::read_blocks(path)?.into_iter();
+        let genesis = match blocks.next() {
+            Some(genesis) => genesis,
+            None => return Self::initialize(config, Some(path.to_path_buf()), rng),
+        };
+
+        // Ensure the persisted chain is of the configured validator, so the new blocks are signed by the same key.
+        let validator = Self::validator(config)?;
+        ensure!(
+            genesis.signature().to_address() == Address::try_from(&validator)?,
+            "❌ The chain at {} was not started by the validator of the genesis configuration",
+            path.display()
+        );
+
+        // Replay the persisted blocks onto the genesis block.
+        let chain = Self { ledger: Ledger::load(genesis, None)?, validator, path: Some(path.to_path_buf()) };
+        for block in blocks {
+            chain.ledger.advance_to_next_block(&block)?;
+        }
+        Ok(chain)
+    }
+
+    /// Initializes the chain from the given genesis configuration, persisting its blocks to the given directory.
+    fn initialize<R: Rng + CryptoRng>(config: &GenesisConfig, path: Option<PathBuf>, rng: &mut R) -> Result<Self> {
+        let validator = Self::validator(config)?;
+
+        // Initialize the ledger from a genesis block signed by the validator.
+        let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::open(None)?;
+        let genesis = VM::from(store)?.genesis(&validator, rng)?;
+        let chain = Self { ledger: Ledger::load(genesis.clone(), None)?, validator, path };
+        chain.write_block(&genesis)?;
+
+        // Mint each initial balance as a record of its address.
+        for (address, amount) in &config.balances {
+            let address = Address::<CurrentNetwork>::from_str(address)
+                .map_err(|error| anyhow!("❌ The genesis balance of '{address}' is not of an address: {error}"))?;
+            let inputs = [Value::from_str(&address.to_string())?, Value::from_str(&format!("{amount}u64"))?];
+            let fee = (chain.largest_record(&chain.validator)?, Self::MINT_FEE_IN_MICROCREDITS);
+            let function = ("credits.aleo", "mint");
+            let transaction =
+                chain.ledger.vm().execute(&chain.validator, function, inputs.iter(), Some(fee), None, rng)?;
+            chain.advance(vec![transaction], rng)?;
+        }
+        Ok(chain)
+    }
+
+    /// Returns the height of the latest block.
+    pub fn latest_height(&self) -> u32 {
+        self.ledger.latest_height()
+    }
+
+    /// Returns the unspent microcredits of the given account.
+    pub fn balance(&self, private_key: &PrivateKey<CurrentNetwork>) -> Result<u64> {
+        Ok(self.unspent_records(private_key)?.iter().filter_map(record_microcredits).sum())
+    }
+
+    /// Deploys the given program with the given priority fee, paid from the largest record of the deployer, and
+    /// ensures the deployment is accepted in the next block.
+    pub fn deploy<R: Rng + CryptoRng>(
+        &self,
+        private_key: &PrivateKey<CurrentNetwork>,
+        program: &Program<CurrentNetwork>,
+        priority_fee: u64,
+        rng: &mut R,
+    ) -> Result<Transaction<CurrentNetwork>> {
+        let fee = (self.largest_record(private_key)?, priority_fee);
+        let transaction = self.ledger.vm().deploy(private_key, program, fee, None, rng)?;
+        self.advance(vec![transaction.clone()], rng)?;
+        ensure!(
+            self.ledger.contains_transaction_id(&transaction.id())?,
+            "❌ The local chain did not accept the deployment of '{}'",
+            program.id()
+        );
+        Ok(transaction)
+    }
+
+    /// Proposes a block of the given transactions, signed by the validator, and advances the chain to it.
+    fn advance<R: Rng + CryptoRng>(
+        &self,
+        transactions: Vec<Transaction<CurrentNetwork>>,
+        rng: &mut R,
+    ) -> Result<Block<CurrentNetwork>> {
+        let block = self.ledger.prepare_advance_to_next_block(&self.validator, transactions, vec![], rng)?;
+        self.ledger.check_next_block(&block)?;
+        self.ledger.advance_to_next_block(&block)?;
+        self.write_block(&block)?;
+        Ok(block)
+    }
+
+    /// Returns the private key of the validator of the given genesis configuration.
+    fn validator(config: &GenesisConfig) -> Result<PrivateKey<CurrentNetwork>> {
+        PrivateKey::from_str(&config.validator)
+            .map_err(|error| anyhow!("❌ The validator of the genesis configuration is not a private key: {error}"))
+    }
+
+    /// Persists the given block to the directory of the chain, if there is one.
+    fn write_block(&self, block: &Block<CurrentNetwork>) -> Result<()> {
+        if let Some(path) = &self.path {
+            let file = path.join(format!("{}.{}", block.height(), Self::BLOCK_EXTENSION));
+            std::fs::write(&file, block.to_bytes_le()?).map_err(|error| {
+                anyhow!("❌ Unable to write block {} to {}: {error}", block.height(), file.display())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Reads the blocks persisted to the given directory, in order of height.
+    fn read_blocks(path: &Path) -> Result<Vec<Block<CurrentNetwork>>> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let file = entry?.path();
+            if file.extension().map_or(false, |extension| extension == Self::BLOCK_EXTENSION) {
+                match file.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.parse::<u32>().ok()) {
+                    Some(height) => files.push((height, file)),
+                    None => bail!("❌ The block file {} is not named by its height", file.display()),
+                }
+            }
+        }
+        files.sort();
+
+        // Ensure the chain has no gaps, as it could not be replayed otherwise.
+        let mut blocks = Vec::with_capacity(files.len());
+        for (expected_height, (height, file)) in files.into_iter().enumerate() {
+            ensure!(
+                height as usize == expected_height,
+                "❌ The chain at {} is missing block {expected_height}",
+                path.display()
+            );
+            blocks.push(Block::from_bytes_le(&std::fs::read(&file)?)?);
+        }
+        Ok(blocks)
+    }
+
+    /// Returns the unspent credits record of the given account with the most microcredits.
+    fn largest_record(
+        &self,
+        private_key: &PrivateKey<CurrentNetwork>,
+    ) -> Result<Record<CurrentNetwork, Plaintext<CurrentNetwork>>> {
+        let records = self.unspent_records(private_key)?;
+        let record = records.into_iter().max_by_key(|record| record_microcredits(record).unwrap_or(0));
+        record.ok_or_else(|| {
+            anyhow!("❌ The account has no credits on the local chain, give it a balance in the genesis configuration")
+        })
+    }
+
+    /// Returns the unspent records of the given account.
+    fn unspent_records(
+        &self,
+        private_key: &PrivateKey<CurrentNetwork>,
+    ) -> Result<Vec<Record<CurrentNetwork, Plaintext<CurrentNetwork>>>> {
+        let view_key = ViewKey::try_from(private_key)?;
+        let records = self.ledger.find_records(&view_key, RecordsFilter::SlowUnspent(*private_key))?;
+        Ok(records.map(|(_, record)| record).collect())
+    }
+}
+
+/// Returns the microcredits of the given record, if it is a credits record.
+pub(super) fn record_microcredits(record: &Record<CurrentNetwork, Plaintext<CurrentNetwork>>) -> Option<u64> {
+    let microcredits = Identifier::from_str("microcredits").ok()?;
+    match record.data().get(&microcredits) {
+        Some(Entry::Private(Plaintext::Literal(Literal::U64(amount), _))) => Some(**amount),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::TestRng;
+
+    #[test]
+    fn test_genesis_config() {
+        let config: GenesisConfig = serde_json::from_str(r#"{ "validator": "APrivateKey1" }"#).unwrap();
+        assert!(config.balances.is_empty());
+
+        // Ensure a configuration whose validator is not a private key is refused.
+        let error = LocalChain::new(&config, &mut TestRng::default()).err().unwrap();
+        assert!(error.to_string().contains("is not a private key"), "{error}");
+    }
+
+    #[test]
+    fn test_resume_local_chain() {
+        let rng = &mut TestRng::default();
+        let directory = std::env::temp_dir().join(format!("snarkos_local_chain_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+
+        let validator = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let deployer = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let address = Address::try_from(&deployer).unwrap();
+        let config = GenesisConfig {
+            validator: validator.to_string(),
+            balances: [(address.to_string(), 10_000_000_000)].into_iter().collect(),
+        };
+
+        // Ensure the chain is initialized in the missing directory, and resumed from it.
+        let chain = LocalChain::open(&config, &directory, rng).unwrap();
+        assert_eq!(chain.latest_height(), 1);
+        drop(chain);
+        let chain = LocalChain::open(&config, &directory, rng).unwrap();
+        assert_eq!(chain.latest_height(), 1);
+        assert_eq!(chain.balance(&deployer).unwrap(), 10_000_000_000);
+
+        // Ensure the chain is not resumed by another validator.
+        let other = GenesisConfig { validator: PrivateKey::<CurrentNetwork>::new(rng).unwrap().to_string(), ..config };
+        let error = LocalChain::open(&other, &directory, rng).err().unwrap();
+        assert!(error.to_string().contains("was not started by the validator"), "{error}");
+
+        // Ensure a ledger path that is not a directory is refused.
+        let file = directory.join("0.block");
+        let error = LocalChain::open(&other, &file, rng).err().unwrap();
+        assert!(error.to_string().contains("is not a directory"), "{error}");
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_deploy_to_local_chain() {
+        let rng = &mut TestRng::default();
+
+        // Configure a genesis that gives the deployer an initial balance.
+        let validator = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let deployer = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let address = Address::try_from(&deployer).unwrap();
+        let config = GenesisConfig {
+            validator: validator.to_string(),
+            balances: [(address.to_string(), 10_000_000_000)].into_iter().collect(),
+        };
+        let chain = LocalChain::new(&config, rng).unwrap();
+        assert_eq!(chain.latest_height(), 1);
+        assert_eq!(chain.balance(&deployer).unwrap(), 10_000_000_000);
+
+        // Ensure the program is deployed, and accepted in the next block.
+        let program = Program::<CurrentNetwork>::from_str(
+            r"
+program local_chain.aleo;
+
+function compute:
+    input r0 as u32.public;
+    input r1 as u32.private;
+    add r0 r1 into r2;
+    output r2 as u32.private;",
+        )
+        .unwrap();
+        let transaction = chain.deploy(&deployer, &program, 0, rng).unwrap();
+        assert_eq!(chain.latest_height(), 2);
+        assert!(chain.ledger.contains_transaction_id(&transaction.id()).unwrap());
+        // Ensure the deployment paid its fee from the initial balance.
+        assert!(chain.balance(&deployer).unwrap() < 10_000_000_000);
+
+        // Ensure an account without a balance can not deploy.
+        let error = chain.deploy(&PrivateKey::new(rng).unwrap(), &program, 0, rng).unwrap_err();
+        assert!(error.to_string().contains("has no credits on the local chain"), "{error}");
+    }
+}
@@ -12,56 +12,213 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod balance;
+pub use balance::*;
+
+mod broadcast;
+pub use broadcast::*;
+
+mod build_cache;
+pub use build_cache::*;
+
+mod convert;
+pub use convert::*;
+
 mod decrypt;
 pub use decrypt::*;
 
+mod decrypt_record;
+pub use decrypt_record::*;
+
 mod deploy;
 pub use deploy::*;
 
+mod estimate_deploy;
+pub use estimate_deploy::*;
+
 mod execute;
 pub use execute::*;
 
+mod export_state;
+pub use export_state::*;
+
+mod inspect;
+pub use inspect::*;
+
+mod join_records;
+pub use join_records::*;
+
+mod local_chain;
+pub use local_chain::*;
+
+mod object_store;
+pub use object_store::*;
+
+mod qr_code;
+pub use qr_code::*;
+
+mod registry;
+pub use registry::*;
+
 mod scan;
 pub use scan::*;
 
+mod spent_filter;
+pub use spent_filter::*;
+
+mod split_record;
+pub use split_record::*;
+
+mod stats;
+pub use stats::*;
+
+#[cfg(test)]
+mod test_helpers;
+
+mod tls;
+pub use tls::*;
+
+mod transfer;
+pub use transfer::*;
+
 mod transfer_private;
 pub use transfer_private::*;
 
 use snarkvm::{
+    console::prelude::{Environment, PrimeField},
     package::Package,
-    prelude::{block::Transaction, Ciphertext, Plaintext, PrivateKey, Program, ProgramID, Record, ToBytes, ViewKey},
+    prelude::{
+        block::{Block, Deployment, Transaction},
+        Ciphertext,
+        Field,
+        FromBytes,
+        Identifier,
+        Network,
+        Plaintext,
+        PrivateKey,
+        Program,
+        ProgramID,
+        Record,
+        ToBits,
+        ToBytes,
+        ViewKey,
+    },
 };
 
 use anyhow::{bail, ensure, Result};
 use clap::Parser;
 use colored::Colorize;
-use std::{path::PathBuf, str::FromStr};
+use hmac::{Hmac, Mac};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::Sha512;
+use std::{
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 type CurrentAleo = snarkvm::circuit::AleoV0;
 type CurrentNetwork = snarkvm::prelude::Testnet3;
 
+/// The offset of the hardened indices of a SLIP-10 derivation path.
+const HARDENED_OFFSET: u32 = 1 << 31;
+
+/// The exponential backoff used to poll for the confirmation of a broadcast transaction.
+#[derive(Clone, Debug, Parser)]
+pub struct ConfirmationBackoff {
+    /// The initial delay in milliseconds between polls for the confirmation.
+    #[clap(long = "confirmation-initial-delay-ms", default_value_t = 1000)]
+    initial_delay_ms: u64,
+    /// The factor by which the delay between polls grows.
+    #[clap(long = "confirmation-multiplier", default_value_t = 2.0)]
+    multiplier: f64,
+    /// The maximum total wait in seconds for the confirmation.
+    #[clap(long = "confirmation-max-wait-secs", default_value_t = 120)]
+    max_wait_secs: u64,
+}
+
+impl ConfirmationBackoff {
+    /// Returns the delays between polls, growing by the multiplier until the maximum total wait is reached.
+    fn delays(&self) -> Vec<Duration> {
+        let max_wait = Duration::from_secs(self.max_wait_secs);
+        let mut delay = Duration::from_millis(self.initial_delay_ms.max(1));
+        let (mut delays, mut total) = (Vec::new(), Duration::ZERO);
+        while total < max_wait {
+            let next = delay.min(max_wait - total);
+            delays.push(next);
+            total += next;
+            let grown = delay.as_secs_f64() * self.multiplier.max(1.0);
+            delay = Duration::from_secs_f64(grown.min(max_wait.as_secs_f64()));
+        }
+        delays
+    }
+}
+
 /// Commands to manage Aleo accounts.
 #[derive(Debug, Parser)]
 pub enum Developer {
+    /// Display the private and public balance of an account.
+    Balance(Balance),
+    /// Broadcast a stored transaction.
+    Broadcast(Broadcast),
+    /// Synthesize the keys of a package once, and cache its deployment for repeated deploys.
+    BuildCache(BuildCache),
+    /// Convert a transaction between serialization formats.
+    Convert(Convert),
     /// Decrypt a ciphertext.
     Decrypt(Decrypt),
+    /// Decrypt a record with a private key, and print its plaintext fields.
+    DecryptRecord(DecryptRecord),
     /// Deploy a program.
     Deploy(Deploy),
+    /// Estimate the cost of deploying one or more programs.
+    EstimateDeploy(EstimateDeploy),
     /// Execute a program function.
     Execute(Execute),
+    /// Export the node state needed to deploy a program offline.
+    ExportState(ExportState),
+    /// Inspect a stored transaction.
+    Inspect(Inspect),
+    /// Execute the `credits.aleo/join` function to combine two records.
+    JoinRecords(JoinRecords),
     /// Scan the node for records.
     Scan(Scan),
+    /// Execute the `credits.aleo/split` function to split a record.
+    SplitRecord(SplitRecord),
+    /// Summarize the proving durations of the deployments recorded in a stats file.
+    Stats(Stats),
+    /// Transfer credits with the `credits.aleo/transfer_private` or `credits.aleo/transfer_public` function.
+    Transfer(Transfer),
     /// Execute the `credits.aleo/transfer_private` function.
     TransferPrivate(TransferPrivate),
 }
 
 impl Developer {
+    /// The delay in milliseconds before the first broadcast retry, which doubles with each retry.
+    const BROADCAST_RETRY_DELAY_IN_MS: u64 = 500;
+    /// The SLIP-44 coin type of Aleo.
+    const DERIVATION_COIN_TYPE: u32 = 683;
+    /// The SLIP-10 curve key the Aleo wallets derive the private key seeds with.
+    const DERIVATION_CURVE: &[u8] = b"bls12_377 seed";
+
     pub fn parse(self) -> Result<String> {
         match self {
+            Self::Balance(balance) => balance.parse(),
+            Self::Broadcast(broadcast) => broadcast.parse(),
+            Self::BuildCache(build_cache) => build_cache.parse(),
+            Self::Convert(convert) => convert.parse(),
             Self::Decrypt(decrypt) => decrypt.parse(),
+            Self::DecryptRecord(decrypt_record) => decrypt_record.parse(),
             Self::Deploy(deploy) => deploy.parse(),
+            Self::EstimateDeploy(estimate_deploy) => estimate_deploy.parse(),
             Self::Execute(execute) => execute.parse(),
+            Self::ExportState(export_state) => export_state.parse(),
+            Self::Inspect(inspect) => inspect.parse(),
+            Self::JoinRecords(join_records) => join_records.parse(),
             Self::Scan(scan) => scan.parse(),
+            Self::SplitRecord(split_record) => split_record.parse(),
+            Self::Stats(stats) => stats.parse(),
+            Self::Transfer(transfer) => transfer.parse(),
             Self::TransferPrivate(transfer_private) => transfer_private.parse(),
         }
     }
@@ -77,9 +234,11 @@ impl Developer {
         // Load the package.
         let package = Package::open(&directory)?;
 
+        // Ensure the package is for the specified program, naming both, as a copy-paste error is the likely cause.
         ensure!(
             package.program_id() == &program_id,
-            "The program name in the package does not match the specified program name"
+            "❌ The program '{}' in the package does not match the specified program '{program_id}'",
+            package.program_id()
         );
 
         // Return the package.
@@ -104,30 +263,245 @@ impl Developer {
         }
     }
 
+    /// Derives the private key at the given index from a BIP39 mnemonic (with an empty passphrase).
+    ///
+    /// The private key seed is derived along the hardened path `m/44'/683'/<index>'/0'` with SLIP-10, as the
+    /// Aleo wallets do, and reduced into a field element.
+    fn derive_private_key(mnemonic: &str, index: u32) -> Result<PrivateKey<CurrentNetwork>> {
+        // Parse the mnemonic, normalizing its whitespace and case.
+        let words = mnemonic.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        let mnemonic = match bip39::Mnemonic::parse_normalized(&words) {
+            Ok(mnemonic) => mnemonic,
+            Err(error) => bail!("❌ The mnemonic is invalid: {error}"),
+        };
+        ensure!(index < HARDENED_OFFSET, "❌ The derivation index {index} must be less than {HARDENED_OFFSET}");
+        // Derive the private key seed from the BIP39 seed along the path of the index.
+        let path = [44, Self::DERIVATION_COIN_TYPE, index, 0];
+        let key = slip10_derive(Self::DERIVATION_CURVE, &mnemonic.to_seed_normalized(""), &path);
+        let seed = <CurrentNetwork as Environment>::Field::from_bytes_le_mod_order(&key);
+        // Recover the private key from the seed.
+        match PrivateKey::try_from(Field::new(seed)) {
+            Ok(private_key) => Ok(private_key),
+            Err(_) => bail!("❌ Failed to derive a private key at index {index} from the mnemonic"),
+        }
+    }
+
+    /// Ensures the given record is unspent, by checking its serial number against the given endpoint.
+    fn ensure_record_unspent(
+        private_key: &PrivateKey<CurrentNetwork>,
+        record: &Record<CurrentNetwork, Plaintext<CurrentNetwork>>,
+        endpoint: &str,
+    ) -> Result<()> {
+        // Compute the serial number of the record.
+        let commitment =
+            record.to_commitment(&ProgramID::from_str("credits.aleo")?, &Identifier::from_str("credits")?)?;
+        let serial_number =
+            Record::<CurrentNetwork, Plaintext<CurrentNetwork>>::serial_number(*private_key, commitment)?;
+
+        // Check if the serial number has been published.
+        match TlsOptions::http_agent().get(&format!("{endpoint}/testnet3/find/transitionID/{serial_number}")).call() {
+            Ok(_) => bail!("❌ The record has already been spent (serial number {serial_number})"),
+            // The node responds with an error status if the serial number is not found, i.e. the record is unspent.
+            Err(ureq::Error::Status(..)) => Ok(()),
+            Err(err) => bail!("❌ Unable to check if the record is spent: {}", TlsOptions::describe_error(err)),
+        }
+    }
+
+    /// Returns `true` if the given program is deployed, according to the given endpoint.
+    fn is_program_deployed(program_id: &ProgramID<CurrentNetwork>, endpoint: &str) -> Result<bool> {
+        match TlsOptions::http_agent().get(&format!("{endpoint}/testnet3/program/{program_id}")).call() {
+            Ok(_) => Ok(true),
+            // The node responds with an error status if the program is not found.
+            Err(ureq::Error::Status(..)) => Ok(false),
+            Err(err) => bail!("❌ Unable to check if '{program_id}' is deployed: {}", TlsOptions::describe_error(err)),
+        }
+    }
+
     /// Fetch the program from the given endpoint.
     fn fetch_program(program_id: &ProgramID<CurrentNetwork>, endpoint: &str) -> Result<Program<CurrentNetwork>> {
+        Self::fetch_json(&format!("{endpoint}/testnet3/program/{program_id}"))
+    }
+
+    /// Returns the function names of the deployment, along with the IDs of their verifying keys.
+    /// The ID of a verifying key is the BHP-1024 hash of its byte representation.
+    fn verifying_key_ids(
+        deployment: &Deployment<CurrentNetwork>,
+    ) -> Result<Vec<(Identifier<CurrentNetwork>, Field<CurrentNetwork>)>> {
+        deployment
+            .verifying_keys()
+            .iter()
+            .map(|(function_name, (verifying_key, _))| {
+                let id = CurrentNetwork::hash_bhp1024(&verifying_key.to_bytes_le()?.to_bits_le())?;
+                Ok((*function_name, id))
+            })
+            .collect()
+    }
+
+    /// Ensures the given endpoint is on the current network, by comparing the hash of its genesis block with the
+    /// genesis block of the current network. If the endpoint does not serve its genesis block, a warning is shown.
+    fn check_network(endpoint: &str) -> Result<()> {
+        let expected = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes())?.hash().to_string();
+        match Self::fetch_genesis_hash(endpoint)? {
+            Some(actual) if actual != expected => bail!(
+                "❌ {endpoint} is not on {} (network {}): its genesis block is {actual}, but expected {expected}.",
+                CurrentNetwork::NAME,
+                CurrentNetwork::ID
+            ),
+            Some(_) => Ok(()),
+            None => {
+                println!("⚠️  {endpoint} does not serve its genesis block, so its network could not be checked");
+                Ok(())
+            }
+        }
+    }
+
+    /// Fetch the hash of the genesis block from the given endpoint, or `None` if the endpoint does not serve it.
+    fn fetch_genesis_hash(endpoint: &str) -> Result<Option<String>> {
         // Send a request to the query node.
-        let response = ureq::get(&format!("{endpoint}/testnet3/program/{program_id}")).call();
+        let response = TlsOptions::http_agent().get(&format!("{endpoint}/testnet3/block/0")).call();
 
-        // Deserialize the program.
+        // Deserialize the hash of the block.
         match response {
+            Ok(response) => {
+                let block: serde_json::Value = response.into_json()?;
+                Ok(block.get("block_hash").and_then(|hash| hash.as_str()).map(str::to_string))
+            }
+            // The node responds with an error status if it does not serve the block.
+            Err(ureq::Error::Status(..)) => Ok(None),
+            Err(err) => bail!(TlsOptions::describe_error(err)),
+        }
+    }
+
+    /// Fetch the latest block height from the given endpoint.
+    fn fetch_latest_height(endpoint: &str) -> Result<u32> {
+        Self::fetch_json(&format!("{endpoint}/testnet3/latest/height"))
+    }
+
+    /// Fetches the JSON response of the given URL with the installed HTTP agent, reporting an error status of the
+    /// response by its body.
+    fn fetch_json<T: DeserializeOwned>(url: &str) -> Result<T> {
+        match TlsOptions::http_agent().get(url).call() {
             Ok(response) => response.into_json().map_err(|err| err.into()),
-            Err(err) => match err {
-                ureq::Error::Status(_status, response) => {
-                    bail!(response.into_string().unwrap_or("Response too large!".to_owned()))
+            Err(ureq::Error::Status(_status, response)) => {
+                bail!(response.into_string().unwrap_or("Response too large!".to_owned()))
+            }
+            Err(err) => bail!(TlsOptions::describe_error(err)),
+        }
+    }
+
+    /// Polls the endpoint until the given transaction is confirmed, backing off exponentially between polls.
+    fn wait_for_confirmation(
+        endpoint: &str,
+        transaction_id: <CurrentNetwork as Network>::TransactionID,
+        backoff: &ConfirmationBackoff,
+    ) -> Result<String> {
+        println!("⏳ Waiting for transaction {transaction_id} to be confirmed...");
+        let timer = Instant::now();
+        let mut attempts = 0;
+        for delay in backoff.delays() {
+            std::thread::sleep(delay);
+            attempts += 1;
+            // The node responds with the transaction once it is included in a block.
+            let url = format!("{endpoint}/testnet3/transaction/{transaction_id}");
+            if TlsOptions::http_agent().get(&url).call().is_ok() {
+                return Ok(format!(
+                    "✅ Transaction {transaction_id} was confirmed after {attempts} attempts ({:.1}s)",
+                    timer.elapsed().as_secs_f64()
+                ));
+            }
+        }
+        bail!(
+            "❌ Transaction {transaction_id} was not confirmed after {attempts} attempts ({:.1}s)",
+            timer.elapsed().as_secs_f64()
+        )
+    }
+
+    /// Posts the transaction with the given ID to the endpoint, retrying a transient failure up to the given
+    /// number of times. A retry that is rejected because the transaction already exists is treated as a success,
+    /// as an earlier attempt must have reached the node, which makes the retries idempotent.
+    fn post_transaction(
+        endpoint: &str,
+        transaction: &impl Serialize,
+        transaction_id: &str,
+        retries: u32,
+    ) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            let error = match TlsOptions::http_agent().post(endpoint).send_json(transaction) {
+                Ok(response) => {
+                    // Remove the quotes from the response.
+                    let response_string = response.into_string()?.trim_matches('\"').to_string();
+                    ensure!(
+                        response_string == transaction_id,
+                        "The response does not match the transaction id. ({response_string} != {transaction_id})"
+                    );
+                    return Ok(());
                 }
-                err => bail!(err),
-            },
+                Err(error) => error,
+            };
+            // Only timeouts, rate limits, and gateway failures are retried, as other rejections are deterministic.
+            let is_transient = match &error {
+                ureq::Error::Status(code, _) => *code == 429 || *code >= 502,
+                ureq::Error::Transport(_) => true,
+            };
+            let error_message = Self::broadcast_error_message(error)?;
+            if attempt > 0 && Self::is_duplicate_transaction(&error_message) {
+                println!("Transaction {transaction_id} already exists on {endpoint}, from an earlier attempt");
+                return Ok(());
+            }
+            if !is_transient || attempt >= retries {
+                bail!(error_message)
+            }
+            let delay = Duration::from_millis(Self::BROADCAST_RETRY_DELAY_IN_MS << attempt.min(10));
+            let delay_ms = delay.as_millis();
+            println!("⚠️  Broadcast attempt {} failed {error_message}, retrying in {delay_ms}ms", attempt + 1);
+            std::thread::sleep(delay);
+            attempt += 1;
         }
     }
 
+    /// Returns `true` if the given error message of a node reports that the transaction already exists.
+    fn is_duplicate_transaction(error_message: &str) -> bool {
+        let error_message = error_message.to_lowercase();
+        ["already exists", "already in the memory pool", "already in the ledger"]
+            .iter()
+            .any(|pattern| error_message.contains(pattern))
+    }
+
+    /// Returns the error message for a failed broadcast, including the node's own error message, if any.
+    fn broadcast_error_message(error: ureq::Error) -> Result<String> {
+        match error {
+            ureq::Error::Status(code, response) => {
+                Ok(format!("(status code {code}: {})", Self::parse_node_error(&response.into_string()?)))
+            }
+            ureq::Error::Transport(err) => Ok(format!("({})", TlsOptions::describe_error(err))),
+        }
+    }
+
+    /// Extracts the error message from the given response body of a node.
+    /// The body may be a JSON object with an `error` or `message` field, a JSON string, or plain text.
+    fn parse_node_error(body: &str) -> String {
+        let message = match serde_json::from_str::<serde_json::Value>(body) {
+            Ok(serde_json::Value::Object(object)) => {
+                object.get("error").or_else(|| object.get("message")).and_then(|value| value.as_str()).map(String::from)
+            }
+            Ok(serde_json::Value::String(message)) => Some(message),
+            _ => None,
+        };
+        let message = message.unwrap_or_else(|| body.trim().to_string());
+        // The REST server prefixes its error messages with a generic preamble.
+        message.strip_prefix("Something went wrong: ").map(String::from).unwrap_or(message)
+    }
+
     /// Determine if the transaction should be broadcast or displayed to user.
+    /// A broadcast retries transient failures up to the given number of times.
     fn handle_transaction(
         broadcast: Option<String>,
         dry_run: bool,
         store: Option<String>,
         transaction: Transaction<CurrentNetwork>,
         operation: String,
+        retries: u32,
     ) -> Result<String> {
         // Get the transaction id.
         let transaction_id = transaction.id();
@@ -137,11 +511,11 @@ impl Developer {
 
         // Determine if the transaction should be stored.
         if let Some(path) = store {
-            match PathBuf::from_str(&path) {
-                Ok(file_path) => {
+            match StorePath::parse(&path) {
+                Ok(store_path) => {
                     let transaction_bytes = transaction.to_bytes_le()?;
-                    std::fs::write(&file_path, transaction_bytes)?;
-                    println!("Transaction {transaction_id} was stored to {}", file_path.display());
+                    store_path.write(&transaction_bytes)?;
+                    println!("Transaction {transaction_id} was stored to {store_path}");
                 }
                 Err(err) => {
                     println!("The transaction was unable to be stored due to: {err}");
@@ -152,42 +526,28 @@ impl Developer {
         // Determine if the transaction should be broadcast to the network.
         if let Some(endpoint) = broadcast {
             // Send the deployment request to the local development node.
-            match ureq::post(&endpoint).send_json(&transaction) {
-                Ok(id) => {
-                    // Remove the quotes from the response.
-                    let response_string = id.into_string()?.trim_matches('\"').to_string();
-                    ensure!(
-                        response_string == transaction_id.to_string(),
-                        "The response does not match the transaction id. ({response_string} != {transaction_id})"
-                    );
-
-                    match transaction {
-                        Transaction::Deploy(..) => {
-                            println!(
-                                "✅ Successfully broadcast deployment {transaction_id} ('{}') to {}.",
-                                operation.bold(),
-                                endpoint
-                            )
-                        }
-                        Transaction::Execute(..) => {
-                            println!(
-                                "✅ Successfully broadcast execution {transaction_id} ('{}') to {}.",
-                                operation.bold(),
-                                endpoint
-                            )
-                        }
-                        Transaction::Fee(..) => {
-                            println!("❌ Failed to broadcast fee '{}' to the {}.", operation.bold(), endpoint)
-                        }
+            match Self::post_transaction(&endpoint, &transaction, &transaction_id.to_string(), retries) {
+                Ok(()) => match transaction {
+                    Transaction::Deploy(..) => {
+                        println!(
+                            "✅ Successfully broadcast deployment {transaction_id} ('{}') to {}.",
+                            operation.bold(),
+                            endpoint
+                        )
                     }
-                }
+                    Transaction::Execute(..) => {
+                        println!(
+                            "✅ Successfully broadcast execution {transaction_id} ('{}') to {}.",
+                            operation.bold(),
+                            endpoint
+                        )
+                    }
+                    Transaction::Fee(..) => {
+                        println!("❌ Failed to broadcast fee '{}' to the {}.", operation.bold(), endpoint)
+                    }
+                },
                 Err(error) => {
-                    let error_message = match error {
-                        ureq::Error::Status(code, response) => {
-                            format!("(status code {code}: {:?})", response.into_string()?)
-                        }
-                        ureq::Error::Transport(err) => format!("({err})"),
-                    };
+                    let error_message = error.to_string();
 
                     match transaction {
                         Transaction::Deploy(..) => {
@@ -223,3 +583,24 @@ impl Developer {
         }
     }
 }
+
+/// Derives the 32-byte key at the given path of hardened indices from the seed, with SLIP-10.
+fn slip10_derive(curve: &[u8], seed: &[u8], path: &[u32]) -> [u8; 32] {
+    // Returns the key and chain code of the HMAC-SHA512 of the data.
+    fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+        let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        data.iter().for_each(|data| mac.update(data));
+        let output = mac.finalize().into_bytes();
+        let (mut key, mut chain_code) = ([0u8; 32], [0u8; 32]);
+        key.copy_from_slice(&output[..32]);
+        chain_code.copy_from_slice(&output[32..]);
+        (key, chain_code)
+    }
+
+    // Derive the master key, then each child key along the path.
+    let (mut key, mut chain_code) = hmac_sha512(curve, &[seed]);
+    for index in path {
+        (key, chain_code) = hmac_sha512(&chain_code, &[&[0], &key, &(index | HARDENED_OFFSET).to_be_bytes()]);
+    }
+    key
+}
@@ -0,0 +1,164 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, ensure, Result};
+use qrcode::{Color, EcLevel, Version};
+use std::fmt::Write;
+
+/// A QR code of version 10, with 57 x 57 modules, that encodes bytes at the low error correction level.
+///
+/// The codes are of a single version, which a phone camera reads reliably from a terminal. A payload that
+/// exceeds the capacity of one code is split across several by [`QrCode::encode_chunks`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QrCode {
+    /// The modules, row by row, which are `true` if dark.
+    modules: Vec<bool>,
+}
+
+impl QrCode {
+    /// The number of bytes a code holds in byte mode.
+    pub const CAPACITY: usize = 271;
+    /// The number of light modules around a rendered code.
+    const QUIET_ZONE: usize = 4;
+    /// The number of modules on each side.
+    pub const SIZE: usize = 17 + 4 * Self::VERSION;
+    /// The version of the codes.
+    const VERSION: usize = 10;
+
+    /// Encodes the given data in a single code.
+    pub fn encode(data: &[u8]) -> Result<Self> {
+        ensure!(data.len() <= Self::CAPACITY, "A QR code holds at most {} bytes, found {}", Self::CAPACITY, data.len());
+
+        let code = qrcode::QrCode::with_version(data, Version::Normal(Self::VERSION as i16), EcLevel::L)
+            .map_err(|error| anyhow!("Failed to encode the QR code: {error}"))?;
+        Ok(Self { modules: code.to_colors().into_iter().map(|color| color == Color::Dark).collect() })
+    }
+
+    /// Encodes the given payload in as many codes as it takes. If more than one code is needed, each starts
+    /// with its position as `{index}/{count}:`, counting from 1, so that the signer can reassemble the payload.
+    pub fn encode_chunks(payload: &[u8]) -> Result<Vec<Self>> {
+        if payload.len() <= Self::CAPACITY {
+            return Ok(vec![Self::encode(payload)?]);
+        }
+        // Reserve room for the position, which is at most `999/999:`.
+        let chunk_size = Self::CAPACITY - 8;
+        let count = (payload.len() + chunk_size - 1) / chunk_size;
+        ensure!(count <= 999, "The payload of {} bytes exceeds 999 QR codes", payload.len());
+
+        payload
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let mut data = format!("{}/{count}:", index + 1).into_bytes();
+                data.extend_from_slice(chunk);
+                Self::encode(&data)
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the module at the given column and row is dark.
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * Self::SIZE + x]
+    }
+
+    /// Renders the code for a terminal, with two rows of modules per line. The light modules are drawn in the
+    /// foreground color, which suits the dark background of most terminals.
+    pub fn to_terminal(&self) -> String {
+        let is_light = |x: usize, y: usize| {
+            let in_code = |i: usize| (Self::QUIET_ZONE..Self::QUIET_ZONE + Self::SIZE).contains(&i);
+            !(in_code(x) && in_code(y) && self.is_dark(x - Self::QUIET_ZONE, y - Self::QUIET_ZONE))
+        };
+        let side = Self::SIZE + 2 * Self::QUIET_ZONE;
+        let mut output = String::new();
+        for y in (0..side).step_by(2) {
+            for x in 0..side {
+                output.push(match (is_light(x, y), is_light(x, y + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                });
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Renders the given codes as an SVG image, one below the other.
+    pub fn to_svg(codes: &[Self]) -> String {
+        const PIXELS_PER_MODULE: usize = 8;
+
+        let side = Self::SIZE + 2 * Self::QUIET_ZONE;
+        let mut path = String::new();
+        for (index, code) in codes.iter().enumerate() {
+            let top = index * side + Self::QUIET_ZONE;
+            for y in 0..Self::SIZE {
+                for x in (0..Self::SIZE).filter(|x| code.is_dark(*x, y)) {
+                    let _ = write!(path, "M{},{}h1v1h-1z", x + Self::QUIET_ZONE, top + y);
+                }
+            }
+        }
+        let height = side * codes.len();
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {side} {height}\" \
+             shape-rendering=\"crispEdges\">\n<rect width=\"100%\" height=\"100%\" fill=\"#fff\"/>\n\
+             <path d=\"{path}\" fill=\"#000\"/>\n</svg>\n",
+            side * PIXELS_PER_MODULE,
+            height * PIXELS_PER_MODULE,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let code = QrCode::encode(b"snarkos").unwrap();
+        assert_eq!(code, QrCode::encode(b"snarkos").unwrap());
+        assert_eq!(code.modules.len(), QrCode::SIZE * QrCode::SIZE);
+
+        // Ensure the finder patterns, their separators, the timing patterns, and the dark module are drawn.
+        for (x, y) in [(0, 0), (QrCode::SIZE - 1, 0), (0, QrCode::SIZE - 1), (3, 3), (8, QrCode::SIZE - 8)] {
+            assert!(code.is_dark(x, y));
+        }
+        assert!(!code.is_dark(7, 7) && !code.is_dark(QrCode::SIZE - 8, 7) && !code.is_dark(7, QrCode::SIZE - 8));
+        assert!((8..QrCode::SIZE - 8).all(|i| code.is_dark(i, 6) == (i % 2 == 0)));
+
+        // Ensure a payload beyond the capacity is rejected.
+        assert!(QrCode::encode(&[0; QrCode::CAPACITY]).is_ok());
+        assert!(QrCode::encode(&[0; QrCode::CAPACITY + 1]).is_err());
+    }
+
+    #[test]
+    fn test_encode_chunks() {
+        // Ensure a payload within the capacity is encoded as is.
+        let payload = [b'a'; QrCode::CAPACITY];
+        assert_eq!(QrCode::encode_chunks(&payload).unwrap(), vec![QrCode::encode(&payload).unwrap()]);
+
+        // Ensure a larger payload is split, with the position of each code.
+        let payload = [b'a'; 600];
+        let codes = QrCode::encode_chunks(&payload).unwrap();
+        assert_eq!(codes.len(), 3);
+        let mut first = b"1/3:".to_vec();
+        first.extend_from_slice(&payload[..QrCode::CAPACITY - 8]);
+        assert_eq!(codes[0], QrCode::encode(&first).unwrap());
+
+        // Ensure the renderings hold every code.
+        let side = QrCode::SIZE + 2 * QrCode::QUIET_ZONE;
+        assert_eq!(codes[0].to_terminal().lines().count(), (side + 1) / 2);
+        assert!(QrCode::to_svg(&codes).contains(&format!("viewBox=\"0 0 {side} {}\"", 3 * side)));
+    }
+}
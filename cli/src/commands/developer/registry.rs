@@ -0,0 +1,181 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{object_store::hex, CurrentNetwork, TlsOptions};
+
+use snarkvm::prelude::ProgramID;
+
+use anyhow::{bail, ensure, Result};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{collections::BTreeMap, path::Path, str::FromStr};
+
+/// A release of a program in a registry, which names the sources of the program and its imports by their
+/// SHA-256 digests.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+pub struct RegistryRelease {
+    /// The digest of the source of the program.
+    pub program: String,
+    /// The digests of the sources of the imports of the program, including those imported indirectly, by program ID.
+    #[serde(default)]
+    pub imports: BTreeMap<String, String>,
+}
+
+/// A program registry, which serves published programs over HTTP.
+///
+/// The release of a program version is served at `{url}/programs/{program_id}/{version}`, and each source it
+/// names is served at `{url}/artifacts/{digest}`. As the sources are content-addressed, each is verified
+/// against its digest once fetched.
+pub struct Registry<'a> {
+    url: &'a str,
+}
+
+impl<'a> Registry<'a> {
+    /// Returns the registry at the given URL.
+    pub fn new(url: &'a str) -> Self {
+        Self { url: url.trim_end_matches('/') }
+    }
+
+    /// Fetches the given version of the program and its imports, and writes them as a package to the given
+    /// directory, replacing any previous contents. Nothing is written unless every source matches its digest.
+    pub fn fetch_package(&self, program_id: ProgramID<CurrentNetwork>, version: &str, directory: &Path) -> Result<()> {
+        // Ensure the version names a single path segment, as it is part of the URL and of the package directory.
+        ensure!(
+            !version.is_empty() && !version.contains(['/', '\\']) && !version.contains(".."),
+            "❌ The registry version '{version}' must not be empty, or contain a path separator or '..'"
+        );
+
+        // Fetch the release of the program.
+        let endpoint = format!("{}/programs/{program_id}/{version}", self.url);
+        let release: RegistryRelease = match TlsOptions::http_agent().get(&endpoint).call() {
+            Ok(response) => response.into_json()?,
+            Err(error) => bail!(
+                "❌ Failed to fetch version {version} of '{program_id}' from the registry: {}",
+                TlsOptions::describe_error(error)
+            ),
+        };
+
+        // Fetch and verify the sources of the program and its imports.
+        let program = self.fetch_artifact(&release.program)?;
+        let mut imports = Vec::with_capacity(release.imports.len());
+        for (import_id, digest) in &release.imports {
+            imports.push((ProgramID::<CurrentNetwork>::from_str(import_id)?, self.fetch_artifact(digest)?));
+        }
+
+        // Write the package.
+        if directory.exists() {
+            std::fs::remove_dir_all(directory)?;
+        }
+        std::fs::create_dir_all(directory.join("imports"))?;
+        let manifest = serde_json::json!({
+            "program": program_id.to_string(),
+            "version": version,
+            "description": format!("Fetched from {}", self.url),
+            "license": "",
+        });
+        std::fs::write(directory.join("program.json"), serde_json::to_string_pretty(&manifest)?)?;
+        std::fs::write(directory.join("main.aleo"), program)?;
+        for (import_id, source) in imports {
+            std::fs::write(directory.join("imports").join(import_id.to_string()), source)?;
+        }
+        Ok(())
+    }
+
+    /// Fetches the source with the given SHA-256 digest, and ensures it matches the digest.
+    fn fetch_artifact(&self, digest: &str) -> Result<String> {
+        let endpoint = format!("{}/artifacts/{digest}", self.url);
+        let source = match TlsOptions::http_agent().get(&endpoint).call() {
+            Ok(response) => response.into_string()?,
+            Err(error) => bail!(
+                "❌ Failed to fetch the artifact {digest} from the registry: {}",
+                TlsOptions::describe_error(error)
+            ),
+        };
+        let actual = hex(&Sha256::digest(source.as_bytes()));
+        ensure!(
+            actual.eq_ignore_ascii_case(digest),
+            "❌ The registry artifact {digest} does not match its digest (found {actual}), refusing to deploy it"
+        );
+        Ok(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{
+        developer::{test_helpers::mock_http_server, CurrentAleo},
+        Developer,
+    };
+
+    const PROGRAM: &str = "\
+program hello.aleo;
+
+function main:
+    input r0 as u32.public;
+    input r1 as u32.private;
+    add r0 r1 into r2;
+    output r2 as u32.private;
+";
+
+    /// Starts a registry that serves the given bodies by request path, and returns its URL.
+    fn mock_registry(routes: Vec<(String, String)>) -> String {
+        let (url, _) = mock_http_server(usize::MAX, move |request| {
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            match routes.iter().find(|(route, _)| route == path) {
+                Some((_, body)) => ("200 OK", body.clone()),
+                None => ("404 Not Found", String::new()),
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_fetch_package() {
+        let digest = hex(&Sha256::digest(PROGRAM.as_bytes()));
+        let tampered = "00".repeat(32);
+        let url = mock_registry(vec![
+            ("/programs/hello.aleo/1.0.0".to_string(), format!(r#"{{"program": "{digest}"}}"#)),
+            (format!("/artifacts/{digest}"), PROGRAM.to_string()),
+            ("/programs/hello.aleo/1.0.1".to_string(), format!(r#"{{"program": "{tampered}"}}"#)),
+            (format!("/artifacts/{tampered}"), PROGRAM.to_string()),
+        ]);
+        let program_id = ProgramID::from_str("hello.aleo").unwrap();
+        let directory = std::env::temp_dir().join(format!("snarkos_registry_{}", std::process::id()));
+
+        // Ensure the fetched program is loaded as the package to deploy, and deploys.
+        Registry::new(&url).fetch_package(program_id, "1.0.0", &directory).unwrap();
+        let package = Developer::parse_package(program_id, Some(directory.to_string_lossy().to_string())).unwrap();
+        assert_eq!(package.program_id(), &program_id);
+        assert_eq!(std::fs::read_to_string(directory.join("main.aleo")).unwrap(), PROGRAM);
+        let deployment = package.deploy::<CurrentAleo>(None).unwrap();
+        assert_eq!(deployment.program_id(), &program_id);
+        assert_eq!(deployment.program().to_string(), package.program().to_string());
+        assert_eq!(deployment.verifying_keys().len(), 1);
+
+        // Ensure a source that does not match its digest is refused, and an unknown version is reported.
+        let error = Registry::new(&url).fetch_package(program_id, "1.0.1", &directory).unwrap_err();
+        assert!(error.to_string().contains("does not match its digest"), "{error}");
+        assert!(Registry::new(&url).fetch_package(program_id, "2.0.0", &directory).is_err());
+        // Ensure a version that could escape the package directory is refused.
+        for version in ["", "../1.0.0", "1.0.0/..", "1.0.0\\main", ".."] {
+            let error = Registry::new(&url).fetch_package(program_id, version, &directory).unwrap_err();
+            assert!(error.to_string().contains("must not be empty, or contain a path separator"), "{error}");
+        }
+        // Ensure the refused releases left the previous package in place.
+        assert_eq!(std::fs::read_to_string(directory.join("main.aleo")).unwrap(), PROGRAM);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
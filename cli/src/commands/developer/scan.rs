@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::CurrentNetwork;
+use super::{CurrentNetwork, Developer, SpentFilter, TlsOptions};
 
 use snarkvm::prelude::{block::Block, Ciphertext, Field, Network, Plaintext, PrivateKey, Record, ViewKey};
 
@@ -20,6 +20,7 @@ use anyhow::{bail, ensure, Result};
 use clap::Parser;
 use std::{
     io::{stdout, Write},
+    path::PathBuf,
     str::FromStr,
 };
 
@@ -35,7 +36,7 @@ pub struct Scan {
     view_key: Option<String>,
 
     /// The block height to start scanning from.
-    #[clap(long, conflicts_with = "last")]
+    #[clap(long, visible_alias = "since-block", conflicts_with = "last")]
     start: Option<u32>,
 
     /// The block height to stop scanning.
@@ -49,9 +50,20 @@ pub struct Scan {
     /// The endpoint to scan blocks from.
     #[clap(long)]
     endpoint: String,
+
+    /// A file recording the last scanned block height, to resume the scan from.
+    #[clap(long, conflicts_with = "last")]
+    checkpoint: Option<PathBuf>,
+
+    /// A file persisting the filter of serial numbers spent in the scanned blocks, across scans.
+    #[clap(long)]
+    spent_filter: Option<PathBuf>,
 }
 
 impl Scan {
+    /// The number of blocks below the checkpoint that are re-scanned, in case they were reorganized.
+    pub(super) const REORG_OVERLAP: u32 = 10;
+
     pub fn parse(self) -> Result<String> {
         // Derive the view key and optional private key.
         let (private_key, view_key) = self.parse_account()?;
@@ -59,8 +71,23 @@ impl Scan {
         // Find the start and end height to scan.
         let (start_height, end_height) = self.parse_block_range()?;
 
+        // Load the filter of spent serial numbers, if one is persisted.
+        let mut spent_filter = match &self.spent_filter {
+            Some(path) => SpentFilter::load(path)?,
+            None => SpentFilter::new(),
+        };
+
         // Fetch the records from the network.
-        let records = Self::fetch_records(private_key, &view_key, &self.endpoint, start_height, end_height)?;
+        let records =
+            Self::fetch_records(private_key, &view_key, &self.endpoint, start_height, end_height, &mut spent_filter)?;
+
+        // Record the highest scanned block height in the checkpoint, along with the spent serial numbers.
+        if let Some(checkpoint) = &self.checkpoint {
+            std::fs::write(checkpoint, end_height.to_string())?;
+        }
+        if let Some(path) = &self.spent_filter {
+            spent_filter.save(path)?;
+        }
 
         // Output the decrypted records associated with the view key.
         if records.is_empty() {
@@ -107,7 +134,13 @@ impl Scan {
 
     /// Returns the `start` and `end` blocks to scan.
     fn parse_block_range(&self) -> Result<(u32, u32)> {
-        match (self.start, self.end, self.last) {
+        // Resume from the checkpoint, if one is given and no start height was specified.
+        let start = match self.start {
+            Some(start) => Some(start),
+            None => self.parse_checkpoint()?,
+        };
+
+        match (start, self.end, self.last) {
             (Some(start), Some(end), None) => {
                 ensure!(end > start, "The given scan range is invalid (start = {start}, end = {end})");
 
@@ -115,8 +148,7 @@ impl Scan {
             }
             (Some(start), None, None) => {
                 // Request the latest block height from the endpoint.
-                let endpoint = format!("{}/testnet3/latest/height", self.endpoint);
-                let latest_height = u32::from_str(&ureq::get(&endpoint).call()?.into_string()?)?;
+                let latest_height = Developer::fetch_latest_height(&self.endpoint)?;
 
                 // Print warning message if the user is attempting to scan the whole chain.
                 if start == 0 {
@@ -128,8 +160,7 @@ impl Scan {
             (None, Some(end), None) => Ok((0, end)),
             (None, None, Some(last)) => {
                 // Request the latest block height from the endpoint.
-                let endpoint = format!("{}/testnet3/latest/height", self.endpoint);
-                let latest_height = u32::from_str(&ureq::get(&endpoint).call()?.into_string()?)?;
+                let latest_height = Developer::fetch_latest_height(&self.endpoint)?;
 
                 Ok((latest_height.saturating_sub(last), latest_height))
             }
@@ -138,14 +169,108 @@ impl Scan {
         }
     }
 
+    /// Returns the block height to resume scanning from, if a checkpoint is given.
+    /// The scan resumes slightly below the checkpoint, to pick up any blocks that were reorganized.
+    fn parse_checkpoint(&self) -> Result<Option<u32>> {
+        match &self.checkpoint {
+            // If the checkpoint does not exist yet, scan from genesis.
+            Some(checkpoint) if !checkpoint.exists() => Ok(Some(0)),
+            Some(checkpoint) => {
+                let height = match u32::from_str(std::fs::read_to_string(checkpoint)?.trim()) {
+                    Ok(height) => height,
+                    Err(error) => bail!("The checkpoint '{}' is invalid - {error}", checkpoint.display()),
+                };
+                Ok(Some(height.saturating_sub(Self::REORG_OVERLAP)))
+            }
+            None => Ok(None),
+        }
+    }
+
     /// Fetch owned ciphertext records from the endpoint.
-    fn fetch_records(
+    ///
+    /// If a private key is provided, the records spent in the scanned blocks are skipped. The serial numbers
+    /// spent in the scanned blocks are added to the given filter, and only the owned records that the filter
+    /// may have seen spent are checked against the endpoint.
+    pub(super) fn fetch_records(
         private_key: Option<PrivateKey<CurrentNetwork>>,
         view_key: &ViewKey<CurrentNetwork>,
         endpoint: &str,
         start_height: u32,
         end_height: u32,
+        spent_filter: &mut SpentFilter,
     ) -> Result<Vec<Record<CurrentNetwork, Plaintext<CurrentNetwork>>>> {
+        let mut records = Vec::new();
+        // The owned records to check for spends once the scan completes, along with their serial numbers.
+        let mut candidates = Vec::new();
+
+        Self::scan_blocks(
+            view_key,
+            endpoint,
+            start_height,
+            end_height,
+            spent_filter,
+            |commitment, ciphertext_record| {
+                match private_key {
+                    // Compute the serial number, to check if the record is spent once the scan completes.
+                    Some(private_key) => {
+                        let serial_number = Record::<CurrentNetwork, Plaintext<CurrentNetwork>>::serial_number(
+                            private_key,
+                            *commitment,
+                        )?;
+                        candidates.push((serial_number, ciphertext_record.clone()));
+                    }
+                    // If no private key was provided, decrypt the record.
+                    None => records.push(ciphertext_record.decrypt(view_key)?),
+                }
+                Ok(())
+            },
+        )?;
+
+        // Decrypt the unspent records.
+        for ciphertext_record in Self::unspent(candidates, spent_filter, endpoint)? {
+            records.push(ciphertext_record.decrypt(view_key)?);
+        }
+
+        Ok(records)
+    }
+
+    /// Fetch the owned ciphertext records from the endpoint, along with their serial numbers, without checking if
+    /// they are spent. The serial numbers spent in the scanned blocks are added to the given filter.
+    pub(super) fn fetch_candidates(
+        private_key: PrivateKey<CurrentNetwork>,
+        view_key: &ViewKey<CurrentNetwork>,
+        endpoint: &str,
+        start_height: u32,
+        end_height: u32,
+        spent_filter: &mut SpentFilter,
+    ) -> Result<Vec<(Field<CurrentNetwork>, Record<CurrentNetwork, Ciphertext<CurrentNetwork>>)>> {
+        let mut candidates = Vec::new();
+        Self::scan_blocks(
+            view_key,
+            endpoint,
+            start_height,
+            end_height,
+            spent_filter,
+            |commitment, ciphertext_record| {
+                let serial_number =
+                    Record::<CurrentNetwork, Plaintext<CurrentNetwork>>::serial_number(private_key, *commitment)?;
+                candidates.push((serial_number, ciphertext_record.clone()));
+                Ok(())
+            },
+        )?;
+        Ok(candidates)
+    }
+
+    /// Scans the blocks in the given range for the records owned by the view key, passing each one to `on_owned`
+    /// along with its commitment, and adds the serial numbers spent in the blocks to the given filter.
+    fn scan_blocks(
+        view_key: &ViewKey<CurrentNetwork>,
+        endpoint: &str,
+        start_height: u32,
+        end_height: u32,
+        spent_filter: &mut SpentFilter,
+        mut on_owned: impl FnMut(&Field<CurrentNetwork>, &Record<CurrentNetwork, Ciphertext<CurrentNetwork>>) -> Result<()>,
+    ) -> Result<()> {
         // Check the bounds of the request.
         if start_height > end_height {
             bail!("Invalid block range");
@@ -156,8 +281,6 @@ impl Scan {
 
         const MAX_BLOCK_RANGE: u32 = 50;
 
-        let mut records = Vec::new();
-
         // Calculate the number of blocks to scan.
         let total_blocks = end_height.saturating_sub(start_height);
 
@@ -177,19 +300,23 @@ impl Scan {
             let blocks_endpoint = format!("{endpoint}/testnet3/blocks?start={request_start}&end={request_end}");
 
             // Fetch blocks
-            let blocks: Vec<Block<CurrentNetwork>> = ureq::get(&blocks_endpoint).call()?.into_json()?;
+            let blocks: Vec<Block<CurrentNetwork>> = match TlsOptions::http_agent().get(&blocks_endpoint).call() {
+                Ok(response) => response.into_json()?,
+                Err(error) => {
+                    bail!("Failed to fetch the blocks from {endpoint}: {}", TlsOptions::describe_error(error))
+                }
+            };
 
             // Scan the blocks for owned records.
             for block in &blocks {
+                // Record the serial numbers spent in the block.
+                for serial_number in block.serial_numbers() {
+                    spent_filter.insert(serial_number);
+                }
                 for (commitment, ciphertext_record) in block.records() {
                     // Check if the record is owned by the given view key.
                     if ciphertext_record.is_owner_with_address_x_coordinate(view_key, &address_x_coordinate) {
-                        // Decrypt and optionally filter the records.
-                        if let Some(record) =
-                            Self::decrypt_record(private_key, view_key, endpoint, *commitment, ciphertext_record)?
-                        {
-                            records.push(record);
-                        }
+                        on_owned(commitment, ciphertext_record)?;
                     }
                 }
             }
@@ -201,43 +328,44 @@ impl Scan {
         println!("\rScanning {total_blocks} blocks for records (100% complete)...   \n");
         stdout().flush()?;
 
-        Ok(records)
+        Ok(())
     }
 
-    /// Decrypts the ciphertext record and filters spend record if a private key was provided.
-    fn decrypt_record(
-        private_key: Option<PrivateKey<CurrentNetwork>>,
-        view_key: &ViewKey<CurrentNetwork>,
+    /// Returns the candidates that are unspent, given their serial numbers, checking the ones that the filter of
+    /// spent serial numbers may have seen spent against the endpoint.
+    pub(super) fn unspent<T>(
+        candidates: Vec<(Field<CurrentNetwork>, T)>,
+        spent_filter: &SpentFilter,
         endpoint: &str,
-        commitment: Field<CurrentNetwork>,
-        ciphertext_record: &Record<CurrentNetwork, Ciphertext<CurrentNetwork>>,
-    ) -> Result<Option<Record<CurrentNetwork, Plaintext<CurrentNetwork>>>> {
-        // Check if a private key was provided.
-        if let Some(private_key) = private_key {
-            // Compute the serial number.
-            let serial_number =
-                Record::<CurrentNetwork, Plaintext<CurrentNetwork>>::serial_number(private_key, commitment)?;
+    ) -> Result<Vec<T>> {
+        Self::filter_unspent(candidates, spent_filter, |serial_number| Self::is_spent(endpoint, serial_number))
+    }
 
-            // Establish the endpoint.
-            let endpoint = format!("{endpoint}/testnet3/find/transitionID/{serial_number}");
-
-            // Check if the record is spent.
-            match ureq::get(&endpoint).call() {
-                // On success, skip as the record is spent.
-                Ok(_) => Ok(None),
-                // On error, add the record.
-                Err(_error) => {
-                    // TODO: Dedup the error types. We're adding the record as valid because the endpoint failed,
-                    //  meaning it couldn't find the serial number (ie. unspent). However if there's a DNS error or request error,
-                    //  we have a false positive here then.
-                    // Decrypt the record.
-                    Ok(Some(ciphertext_record.decrypt(view_key)?))
-                }
+    /// Returns the candidates that are unspent, given their serial numbers and the filter of spent serial numbers.
+    /// A candidate absent from the filter is unspent, while one in the filter is checked with `is_spent`.
+    fn filter_unspent<T>(
+        candidates: Vec<(Field<CurrentNetwork>, T)>,
+        spent_filter: &SpentFilter,
+        mut is_spent: impl FnMut(&Field<CurrentNetwork>) -> Result<bool>,
+    ) -> Result<Vec<T>> {
+        let mut unspent = Vec::with_capacity(candidates.len());
+        for (serial_number, candidate) in candidates {
+            if !spent_filter.contains(&serial_number) || !is_spent(&serial_number)? {
+                unspent.push(candidate);
             }
-        } else {
-            // If no private key was provided, return the record.
-            Ok(Some(ciphertext_record.decrypt(view_key)?))
         }
+        Ok(unspent)
+    }
+
+    /// Returns `true` if the endpoint finds the transition that spent the given serial number.
+    fn is_spent(endpoint: &str, serial_number: &Field<CurrentNetwork>) -> Result<bool> {
+        // Establish the endpoint.
+        let endpoint = format!("{endpoint}/testnet3/find/transitionID/{serial_number}");
+
+        // TODO: Dedup the error types. We're treating the record as unspent because the endpoint failed,
+        //  meaning it couldn't find the serial number (ie. unspent). However if there's a DNS error or request error,
+        //  we have a false positive here then.
+        Ok(TlsOptions::http_agent().get(&endpoint).call().is_ok())
     }
 }
 
@@ -309,25 +437,90 @@ mod tests {
         assert!(config.parse_block_range().is_err());
 
         // `last` conflicts with `start`
-        assert!(
-            Scan::try_parse_from(
-                ["snarkos", "--view-key", "", "--start", "0", "--last", "10", "--endpoint", ""].iter(),
-            )
-            .is_err()
-        );
+        assert!(Scan::try_parse_from(
+            ["snarkos", "--view-key", "", "--start", "0", "--last", "10", "--endpoint", ""].iter(),
+        )
+        .is_err());
 
         // `last` conflicts with `end`
-        assert!(
-            Scan::try_parse_from(["snarkos", "--view-key", "", "--end", "10", "--last", "10", "--endpoint", ""].iter())
-                .is_err()
-        );
+        assert!(Scan::try_parse_from(
+            ["snarkos", "--view-key", "", "--end", "10", "--last", "10", "--endpoint", ""].iter()
+        )
+        .is_err());
+
+        // `since-block` is an alias of `start`.
+        let config = Scan::try_parse_from(
+            ["snarkos", "--view-key", "", "--since-block", "5", "--end", "10", "--endpoint", ""].iter(),
+        )
+        .unwrap();
+        assert_eq!(config.parse_block_range().unwrap(), (5, 10));
 
         // `last` conflicts with `start` and `end`
-        assert!(
-            Scan::try_parse_from(
-                ["snarkos", "--view-key", "", "--start", "0", "--end", "01", "--last", "10", "--endpoint", ""].iter(),
-            )
-            .is_err()
-        );
+        assert!(Scan::try_parse_from(
+            ["snarkos", "--view-key", "", "--start", "0", "--end", "01", "--last", "10", "--endpoint", ""].iter(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_checkpoint() {
+        let checkpoint = std::env::temp_dir().join(format!("snarkos_scan_checkpoint_{}", std::process::id()));
+        let _ = std::fs::remove_file(&checkpoint);
+        let path = checkpoint.to_str().unwrap();
+
+        // Ensure a missing checkpoint scans from genesis.
+        let config =
+            Scan::try_parse_from(["snarkos", "--view-key", "", "--checkpoint", path, "--end", "200", "--endpoint", ""])
+                .unwrap();
+        assert_eq!(config.parse_block_range().unwrap(), (0, 200));
+
+        // Ensure the scan resumes from the checkpoint, with an overlap for reorgs.
+        std::fs::write(&checkpoint, "100").unwrap();
+        assert_eq!(config.parse_block_range().unwrap(), (100 - Scan::REORG_OVERLAP, 200));
+
+        // Ensure an explicit start height takes precedence over the checkpoint.
+        let config = Scan::try_parse_from([
+            "snarkos",
+            "--view-key",
+            "",
+            "--checkpoint",
+            path,
+            "--start",
+            "150",
+            "--end",
+            "200",
+            "--endpoint",
+            "",
+        ])
+        .unwrap();
+        assert_eq!(config.parse_block_range().unwrap(), (150, 200));
+
+        // Ensure an invalid checkpoint is an error.
+        std::fs::write(&checkpoint, "invalid").unwrap();
+        assert!(config.parse_checkpoint().is_err());
+
+        std::fs::remove_file(&checkpoint).unwrap();
+    }
+
+    #[test]
+    fn test_filter_unspent() {
+        let serial_number = Field::<CurrentNetwork>::from_u64;
+        // The candidates 0 to 99 are owned records, of which the even ones were spent in the scanned blocks.
+        let candidates = (0..100).map(|i| (serial_number(i), i)).collect::<Vec<_>>();
+        let mut spent_filter = SpentFilter::new();
+        for i in (0..100).step_by(2) {
+            spent_filter.insert(&serial_number(i));
+        }
+
+        let mut checks = 0;
+        let unspent = Scan::filter_unspent(candidates, &spent_filter, |candidate| {
+            checks += 1;
+            Ok((0..100).step_by(2).any(|i| *candidate == serial_number(i)))
+        })
+        .unwrap();
+
+        // Ensure every unspent record is found, while only the spent records, and any false positives, are checked.
+        assert_eq!(unspent, (1..100).step_by(2).collect::<Vec<_>>());
+        assert!(checks < 55, "{checks} checks");
     }
 }
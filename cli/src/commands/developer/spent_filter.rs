@@ -0,0 +1,120 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CurrentNetwork;
+
+use snarkvm::prelude::Field;
+
+use anyhow::{ensure, Result};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// A bloom filter of the serial numbers spent in the scanned blocks.
+///
+/// A serial number absent from the filter was not spent in the scanned blocks, whereas a serial number in the
+/// filter may be a false positive, and is only known to be spent once it is checked against the endpoint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpentFilter {
+    bits: Vec<u8>,
+}
+
+impl Default for SpentFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpentFilter {
+    /// The number of bits in the filter, which keeps false positives below 1% for up to 100,000 serial numbers.
+    const NUM_BITS: usize = 1 << 20;
+    /// The number of bits set for each serial number.
+    const NUM_HASHES: u64 = 7;
+
+    /// Returns an empty filter.
+    pub fn new() -> Self {
+        Self { bits: vec![0; Self::NUM_BITS / 8] }
+    }
+
+    /// Loads the filter from the given path, or returns an empty filter if it does not exist yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let bits = std::fs::read(path)?;
+        ensure!(
+            bits.len() == Self::NUM_BITS / 8,
+            "The spent filter '{}' is invalid - expected {} bytes, found {}",
+            path.display(),
+            Self::NUM_BITS / 8,
+            bits.len()
+        );
+        Ok(Self { bits })
+    }
+
+    /// Saves the filter to the given path.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        Ok(std::fs::write(path, &self.bits)?)
+    }
+
+    /// Adds the given serial number to the filter.
+    pub fn insert(&mut self, serial_number: &Field<CurrentNetwork>) {
+        for index in Self::indices(serial_number) {
+            self.bits[index / 8] |= 1 << (index % 8);
+        }
+    }
+
+    /// Returns `true` if the given serial number may be in the filter, and `false` if it is definitely not.
+    pub fn contains(&self, serial_number: &Field<CurrentNetwork>) -> bool {
+        Self::indices(serial_number).all(|index| self.bits[index / 8] & (1 << (index % 8)) != 0)
+    }
+
+    /// Returns the bits of the given serial number, derived from its SHA-256 digest by double hashing.
+    fn indices(serial_number: &Field<CurrentNetwork>) -> impl Iterator<Item = usize> {
+        let digest = Sha256::digest(serial_number.to_string().as_bytes());
+        let mut halves = digest.chunks_exact(8).map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()));
+        let (first, second) = (halves.next().unwrap(), halves.next().unwrap());
+        (0..Self::NUM_HASHES)
+            .map(move |i| (first.wrapping_add(i.wrapping_mul(second)) % Self::NUM_BITS as u64) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spent_filter() {
+        let serial_numbers = (0..1000u64).map(Field::<CurrentNetwork>::from_u64).collect::<Vec<_>>();
+
+        let mut filter = SpentFilter::new();
+        for serial_number in &serial_numbers[..500] {
+            filter.insert(serial_number);
+        }
+        // Ensure every inserted serial number is found, and few others are.
+        assert!(serial_numbers[..500].iter().all(|serial_number| filter.contains(serial_number)));
+        assert!(serial_numbers[500..].iter().filter(|serial_number| filter.contains(serial_number)).count() < 5);
+
+        // Ensure the filter persists, and a missing filter loads as empty.
+        let path = std::env::temp_dir().join(format!("snarkos_spent_filter_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(SpentFilter::load(&path).unwrap(), SpentFilter::new());
+        filter.save(&path).unwrap();
+        assert_eq!(SpentFilter::load(&path).unwrap(), filter);
+
+        // Ensure a corrupted filter is an error.
+        std::fs::write(&path, [0u8; 16]).unwrap();
+        assert!(SpentFilter::load(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
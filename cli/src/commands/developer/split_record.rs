@@ -0,0 +1,143 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{CurrentNetwork, Developer};
+
+use snarkvm::prelude::{
+    query::Query,
+    store::{helpers::memory::ConsensusMemory, ConsensusStore},
+    Locator,
+    PrivateKey,
+    Value,
+    VM,
+};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use std::str::FromStr;
+
+/// Executes the `split` function in the `credits.aleo` program, splitting a record into the given amount and change.
+#[derive(Debug, Parser)]
+pub struct SplitRecord {
+    /// The record to split.
+    #[clap(short, long)]
+    record: String,
+    /// The number of microcredits in the split record. The remainder is returned as change.
+    #[clap(long)]
+    amount: u64,
+    /// The private key used to generate the execution.
+    #[clap(short, long)]
+    private_key: String,
+    /// The endpoint to query node state from.
+    #[clap(short, long)]
+    query: String,
+    /// The priority fee in microcredits.
+    #[clap(short, long, default_value_t = 0, requires = "fee_record")]
+    fee: u64,
+    /// The record to spend the fee from. If omitted, the execution is generated without a fee.
+    #[clap(long)]
+    fee_record: Option<String>,
+    /// The endpoint used to broadcast the generated transaction.
+    #[clap(short, long, conflicts_with = "dry_run")]
+    broadcast: Option<String>,
+    /// Performs a dry-run of transaction generation.
+    #[clap(short, long, conflicts_with = "broadcast")]
+    dry_run: bool,
+    /// Store generated split transaction to a local file.
+    #[clap(long)]
+    store: Option<String>,
+}
+
+impl SplitRecord {
+    /// Splits the record with the provided inputs.
+    pub fn parse(self) -> Result<String> {
+        // Ensure that the user has specified an action.
+        if !self.dry_run && self.broadcast.is_none() && self.store.is_none() {
+            bail!("❌ Please specify one of the following actions: --broadcast, --dry-run, --store");
+        }
+
+        // Specify the query
+        let query = Query::from(&self.query);
+
+        // Retrieve the private key.
+        let private_key = PrivateKey::from_str(&self.private_key)?;
+
+        println!("📦 Creating a split of {} microcredits...\n", self.amount);
+
+        // Generate the split transaction.
+        let transaction = {
+            // Initialize an RNG.
+            let rng = &mut rand::thread_rng();
+
+            // Initialize the VM.
+            let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::open(None)?;
+            let vm = VM::from(store)?;
+
+            // Prepare the fees.
+            let fee = match &self.fee_record {
+                Some(fee_record) => Some((Developer::parse_record(&private_key, fee_record)?, self.fee)),
+                None => None,
+            };
+
+            // Prepare the inputs for a split.
+            let record = Developer::parse_record(&private_key, &self.record)?;
+            let inputs = vec![Value::Record(record), Value::from_str(&format!("{}u64", self.amount))?];
+
+            // Create a new transaction.
+            vm.execute(&private_key, ("credits.aleo", "split"), inputs.iter(), fee, Some(query), rng)?
+        };
+        let locator = Locator::<CurrentNetwork>::from_str("credits.aleo/split")?;
+        println!("✅ Created a split of {} microcredits\n", self.amount);
+
+        // Determine if the transaction should be broadcast, stored, or displayed to user.
+        Developer::handle_transaction(self.broadcast, self.dry_run, self.store, transaction, locator.to_string(), 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, CLI};
+
+    #[test]
+    fn clap_snarkos_split_record() {
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "split-record",
+            "--record",
+            "RECORD",
+            "--amount",
+            "1000",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--store",
+            "split.bytes",
+        ];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::SplitRecord(split)) = cli.command {
+            assert_eq!(split.record, "RECORD");
+            assert_eq!(split.amount, 1000);
+            assert_eq!(split.private_key, "PRIVATE_KEY");
+            assert_eq!(split.query, "QUERY");
+            assert_eq!(split.fee_record, None);
+            assert_eq!(split.store, Some("split.bytes".to_string()));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A proving duration of a single deployment, appended to a stats file as a line of JSON.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvingSample {
+    /// The ID of the deployed program.
+    pub program_id: String,
+    /// The time spent proving the deployment and its fee, in milliseconds.
+    pub proving_ms: u128,
+    /// The time of the deployment, as a UNIX timestamp in seconds.
+    pub timestamp: u64,
+}
+
+/// The proving durations of the deployments of a program, in milliseconds.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ProvingSummary {
+    /// The number of deployments.
+    pub count: usize,
+    /// The shortest proving duration.
+    pub min_ms: u128,
+    /// The median proving duration.
+    pub median_ms: u128,
+    /// The longest proving duration.
+    pub max_ms: u128,
+}
+
+/// Summarizes the proving durations per program, as recorded by `developer deploy --stats-file`.
+#[derive(Debug, Parser)]
+pub struct Stats {
+    /// The path to the stats file.
+    path: PathBuf,
+    /// Only summarizes the deployments of the given program.
+    #[clap(long)]
+    program: Option<String>,
+}
+
+impl Stats {
+    /// Summarizes the min/max/median proving durations per program in the stats file.
+    pub fn parse(self) -> Result<String> {
+        let mut summaries = Self::summarize(&std::fs::read_to_string(&self.path)?)?;
+        if let Some(program) = &self.program {
+            summaries.retain(|program_id, _| program_id == program);
+        }
+        if summaries.is_empty() {
+            bail!("❌ No proving durations were recorded in {}", self.path.display());
+        }
+
+        let mut output = String::new();
+        for (program_id, summary) in summaries {
+            writeln!(
+                output,
+                "{program_id}: {} deployments (min: {}ms, median: {}ms, max: {}ms)",
+                summary.count, summary.min_ms, summary.median_ms, summary.max_ms
+            )?;
+        }
+        Ok(output.trim_end().to_string())
+    }
+
+    /// Appends a proving duration of the given program to the stats file, creating the file if needed.
+    pub(super) fn append(path: &Path, program_id: String, proving_ms: u128) -> Result<()> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let sample = ProvingSample { program_id, proving_ms, timestamp };
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(&sample)?)?;
+        Ok(())
+    }
+
+    /// Computes the summary of proving durations per program, from the contents of a stats file.
+    fn summarize(contents: &str) -> Result<BTreeMap<String, ProvingSummary>> {
+        // Group the proving durations by program.
+        let mut durations = BTreeMap::<String, Vec<u128>>::new();
+        for (index, line) in contents.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let sample: ProvingSample = match serde_json::from_str(line) {
+                Ok(sample) => sample,
+                Err(error) => bail!("❌ Line {} of the stats file is invalid: {error}", index + 1),
+            };
+            durations.entry(sample.program_id).or_default().push(sample.proving_ms);
+        }
+
+        // Summarize the proving durations of each program.
+        Ok(durations
+            .into_iter()
+            .map(|(program_id, mut durations)| {
+                durations.sort_unstable();
+                let count = durations.len();
+                let median_ms = match count % 2 {
+                    0 => (durations[count / 2 - 1] + durations[count / 2]) / 2,
+                    _ => durations[count / 2],
+                };
+                let summary = ProvingSummary { count, min_ms: durations[0], median_ms, max_ms: durations[count - 1] };
+                (program_id, summary)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, Developer, CLI};
+
+    #[test]
+    fn clap_snarkos_stats() {
+        let arg_vec = vec!["snarkos", "developer", "stats", "--program", "hello.aleo", "stats.jsonl"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Stats(stats)) = cli.command {
+            assert_eq!(stats.path, PathBuf::from("stats.jsonl"));
+            assert_eq!(stats.program, Some("hello.aleo".to_string()));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let path = std::env::temp_dir().join(format!("snarkos-stats-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // Append the proving durations of two programs.
+        let samples =
+            [("hello.aleo", 300), ("token.aleo", 50), ("hello.aleo", 100), ("hello.aleo", 200), ("hello.aleo", 900)];
+        for (program_id, proving_ms) in samples {
+            Stats::append(&path, program_id.to_string(), proving_ms).unwrap();
+        }
+
+        // Ensure the stats file is appended to, one sample per line.
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 5);
+
+        // Ensure the summary is computed per program.
+        let summaries = Stats::summarize(&contents).unwrap();
+        assert_eq!(summaries["hello.aleo"], ProvingSummary { count: 4, min_ms: 100, median_ms: 250, max_ms: 900 });
+        assert_eq!(summaries["token.aleo"], ProvingSummary { count: 1, min_ms: 50, median_ms: 50, max_ms: 50 });
+
+        // Ensure the summary can be filtered by program.
+        let output = Stats { path: path.clone(), program: Some("token.aleo".to_string()) }.parse().unwrap();
+        assert_eq!(output, "token.aleo: 1 deployments (min: 50ms, median: 50ms, max: 50ms)");
+
+        // Ensure an invalid line is reported.
+        assert!(Stats::summarize("not json").is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CurrentNetwork;
+
+use snarkvm::prelude::{
+    block::Transaction,
+    store::{helpers::memory::ConsensusMemory, ConsensusStore},
+    Address,
+    Ciphertext,
+    Entry,
+    Identifier,
+    Ledger,
+    Literal,
+    Network,
+    Owner,
+    Plaintext,
+    PrivateKey,
+    Program,
+    Record,
+    RecordsFilter,
+    Scalar,
+    TestRng,
+    Uniform,
+    ViewKey,
+    U64,
+    VM,
+};
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    str::FromStr,
+    sync::mpsc,
+};
+
+/// Returns a credits record of the given amount owned by the given private key, encrypted to a ciphertext.
+pub(super) fn sample_ciphertext(
+    private_key: &PrivateKey<CurrentNetwork>,
+    microcredits: u64,
+    rng: &mut TestRng,
+) -> Record<CurrentNetwork, Ciphertext<CurrentNetwork>> {
+    let owner = Owner::Private(Plaintext::from(Literal::Address(Address::try_from(private_key).unwrap())));
+    let randomizer = Scalar::rand(rng);
+    let record = Record::<CurrentNetwork, Plaintext<CurrentNetwork>>::from_plaintext(
+        owner,
+        IndexMap::from_iter([(
+            Identifier::from_str("microcredits").unwrap(),
+            Entry::Private(Plaintext::from(Literal::U64(U64::new(microcredits)))),
+        )]),
+        CurrentNetwork::g_scalar_multiply(&randomizer),
+    )
+    .unwrap();
+    record.encrypt(randomizer).unwrap()
+}
+
+/// The deployment transaction generated by `sample_deployment_transaction`, once generated.
+static DEPLOYMENT_TRANSACTION: Mutex<Option<Transaction<CurrentNetwork>>> = Mutex::new(None);
+
+/// Returns a deployment transaction of `verify_transaction.aleo`, whose fee is paid from a record of the genesis
+/// block of a fresh ledger. The transaction is generated once, and shared by the tests.
+pub(super) fn sample_deployment_transaction() -> Transaction<CurrentNetwork> {
+    DEPLOYMENT_TRANSACTION
+        .lock()
+        .get_or_insert_with(|| {
+            let rng = &mut TestRng::default();
+
+            // Initialize a ledger, whose genesis block mints the starting supply to the private key.
+            let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+            let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::open(None).unwrap();
+            let genesis = VM::from(store).unwrap().genesis(&private_key, rng).unwrap();
+            let ledger = Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis, None).unwrap();
+
+            // Find a record of the genesis block to pay the fee from.
+            let view_key = ViewKey::try_from(&private_key).unwrap();
+            let record = ledger
+                .find_records(&view_key, RecordsFilter::SlowUnspent(private_key))
+                .unwrap()
+                .map(|(_, record)| record)
+                .find(|record| record.microcredits().map_or(false, |amount| amount > 0))
+                .unwrap();
+
+            // Deploy the program.
+            let program = Program::<CurrentNetwork>::from_str(
+                r"
+program verify_transaction.aleo;
+
+function compute:
+    input r0 as u32.public;
+    input r1 as u32.private;
+    add r0 r1 into r2;
+    output r2 as u32.private;",
+            )
+            .unwrap();
+            ledger.vm().deploy(&private_key, &program, (record, 0), None, rng).unwrap()
+        })
+        .clone()
+}
+
+/// Starts a mock HTTP server that answers its first `num_requests` requests with the status line and body the
+/// handler returns for each request. Returns the URL of the server, and a receiver of the requests it answered.
+pub(super) fn mock_http_server<F>(num_requests: usize, mut handler: F) -> (String, mpsc::Receiver<String>)
+where
+    F: FnMut(&str) -> (&'static str, String) + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().take(num_requests) {
+            let mut stream = stream.unwrap();
+            let request = read_http_request(&mut stream);
+            let (status, body) = handler(&request);
+            let response =
+                format!("HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len());
+            stream.write_all(response.as_bytes()).unwrap();
+            // The test may have stopped listening for the requests.
+            let _ = sender.send(request);
+        }
+    });
+    (url, receiver)
+}
+
+/// Starts a mock HTTP server that answers each request with the next of the given status lines and bodies.
+/// Returns the URL of the server, and a receiver of the requests it answered.
+pub(super) fn mock_http_sequence(responses: Vec<(&'static str, String)>) -> (String, mpsc::Receiver<String>) {
+    let num_requests = responses.len();
+    let mut responses = responses.into_iter();
+    mock_http_server(num_requests, move |_| responses.next().unwrap())
+}
+
+/// Reads an HTTP request from the given stream, until the end of the body announced by its `Content-Length`.
+fn read_http_request(stream: &mut impl Read) -> String {
+    let (mut request, mut buffer) = (Vec::new(), [0u8; 1024]);
+    loop {
+        let num_bytes = stream.read(&mut buffer).unwrap();
+        if num_bytes == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..num_bytes]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some((head, body)) = text.split_once("\r\n\r\n") {
+            let length = head
+                .lines()
+                .find_map(|line| line.to_lowercase().strip_prefix("content-length:").map(str::to_string))
+                .map_or(0, |length| length.trim().parse().unwrap());
+            if body.len() >= length {
+                break;
+            }
+        }
+    }
+    String::from_utf8_lossy(&request).to_string()
+}
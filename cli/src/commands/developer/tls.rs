@@ -0,0 +1,203 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, ensure, Result};
+use base64::Engine;
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use parking_lot::RwLock;
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier},
+    Certificate,
+    ClientConfig,
+    RootCertStore,
+    ServerName,
+};
+use std::{fmt::Display, path::PathBuf, sync::Arc, time::SystemTime};
+
+/// The HTTP agent used to reach the endpoints, along with a description of its TLS verification, once installed.
+static HTTP_AGENT: RwLock<Option<(ureq::Agent, String)>> = RwLock::new(None);
+
+/// How the certificates of HTTPS endpoints are verified.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TlsVerify {
+    /// Verifies certificates against the public web PKI roots.
+    Strict,
+    /// Verifies certificates against the CA bundle given with `--tls-ca-file`, for example of a private node.
+    CaFile,
+    /// Does not verify certificates. Anyone on the path to the endpoint can intercept the requests.
+    Insecure,
+}
+
+/// The TLS verification of the HTTPS endpoints that are queried, and broadcast to.
+///
+/// The verification applies to the requests made by the CLI itself. The queries that snarkVM makes for the state
+/// of the fee and input records are made with its own client, which always verifies certificates strictly.
+#[derive(Clone, Debug, Parser)]
+pub struct TlsOptions {
+    /// How the certificates of HTTPS endpoints are verified.
+    #[clap(long, value_enum, default_value_t = TlsVerify::Strict)]
+    tls_verify: TlsVerify,
+    /// The path to a PEM bundle of the CA certificates trusted with `--tls-verify ca-file`.
+    #[clap(long, required_if_eq("tls_verify", "ca-file"))]
+    tls_ca_file: Option<PathBuf>,
+}
+
+impl TlsOptions {
+    /// Installs the HTTP agent with the configured TLS verification, for the requests that follow.
+    pub fn install(&self) -> Result<()> {
+        let (config, description) = match (self.tls_verify, &self.tls_ca_file) {
+            (TlsVerify::Strict, None) => return Ok(()),
+            (TlsVerify::CaFile, Some(path)) => {
+                let mut roots = RootCertStore::empty();
+                for der in Self::parse_pem_certificates(&std::fs::read_to_string(path)?)? {
+                    if let Err(error) = roots.add(&Certificate(der)) {
+                        bail!("❌ The CA bundle '{}' holds an invalid certificate: {error}", path.display())
+                    }
+                }
+                ensure!(!roots.is_empty(), "❌ The CA bundle '{}' holds no certificates", path.display());
+                let config = ClientConfig::builder().with_safe_defaults().with_root_certificates(roots);
+                (config.with_no_client_auth(), format!("TLS verification against the CA bundle '{}'", path.display()))
+            }
+            (TlsVerify::Insecure, None) => {
+                println!(
+                    "{}",
+                    "⚠️  TLS verification is disabled. The requests to HTTPS endpoints can be intercepted and altered."
+                        .yellow()
+                        .bold()
+                );
+                let verifier = Arc::new(NoVerification);
+                let config = ClientConfig::builder().with_safe_defaults().with_custom_certificate_verifier(verifier);
+                (config.with_no_client_auth(), "insecure TLS, without verification".to_string())
+            }
+            (_, Some(_)) => bail!("❌ --tls-ca-file is only used with --tls-verify ca-file"),
+            (TlsVerify::CaFile, None) => bail!("❌ --tls-verify ca-file requires --tls-ca-file"),
+        };
+        let agent = ureq::AgentBuilder::new().tls_config(Arc::new(config)).build();
+        *HTTP_AGENT.write() = Some((agent, description));
+        Ok(())
+    }
+
+    /// Returns the installed HTTP agent, or an agent with strict TLS verification if none was installed.
+    pub(super) fn http_agent() -> ureq::Agent {
+        match &*HTTP_AGENT.read() {
+            Some((agent, _)) => agent.clone(),
+            None => ureq::agent(),
+        }
+    }
+
+    /// Returns the message of the given request error, naming the TLS verification it was made with.
+    pub(super) fn describe_error(error: impl Display) -> String {
+        let description = match &*HTTP_AGENT.read() {
+            Some((_, description)) => description.clone(),
+            None => "strict TLS verification".to_string(),
+        };
+        format!("{error} (using {description})")
+    }
+
+    /// Returns the DER encodings of the certificates in the given PEM bundle.
+    fn parse_pem_certificates(pem: &str) -> Result<Vec<Vec<u8>>> {
+        const BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+        const END: &str = "-----END CERTIFICATE-----";
+
+        let mut certificates = Vec::new();
+        let mut remaining = pem;
+        while let Some(start) = remaining.find(BEGIN) {
+            let body = &remaining[start + BEGIN.len()..];
+            let end = match body.find(END) {
+                Some(end) => end,
+                None => bail!("❌ The CA bundle has a certificate without an end marker"),
+            };
+            let encoded = body[..end].split_whitespace().collect::<String>();
+            match base64::engine::general_purpose::STANDARD.decode(encoded) {
+                Ok(der) => certificates.push(der),
+                Err(error) => bail!("❌ The CA bundle has a certificate that is not valid base64: {error}"),
+            }
+            remaining = &body[end + END.len()..];
+        }
+        Ok(certificates)
+    }
+}
+
+/// A certificate verifier that accepts any certificate, for `--tls-verify insecure`.
+struct NoVerification;
+
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clap_snarkos_tls_options() {
+        // Ensure certificates are verified strictly by default.
+        let options = TlsOptions::try_parse_from(["snarkos"]).unwrap();
+        assert_eq!(options.tls_verify, TlsVerify::Strict);
+        assert_eq!(options.tls_ca_file, None);
+
+        let options =
+            TlsOptions::try_parse_from(["snarkos", "--tls-verify", "ca-file", "--tls-ca-file", "ca.pem"]).unwrap();
+        assert_eq!(options.tls_verify, TlsVerify::CaFile);
+        assert_eq!(options.tls_ca_file, Some(PathBuf::from("ca.pem")));
+        assert_eq!(
+            TlsOptions::try_parse_from(["snarkos", "--tls-verify", "insecure"]).unwrap().tls_verify,
+            TlsVerify::Insecure
+        );
+
+        // Ensure the `ca-file` mode requires a CA bundle.
+        assert!(TlsOptions::try_parse_from(["snarkos", "--tls-verify", "ca-file"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_pem_certificates() {
+        let pem = "-----BEGIN CERTIFICATE-----\nAAEC\nAw==\n-----END CERTIFICATE-----\n\
+                   -----BEGIN CERTIFICATE-----\nBAU=\n-----END CERTIFICATE-----\n";
+        assert_eq!(TlsOptions::parse_pem_certificates(pem).unwrap(), vec![vec![0, 1, 2, 3], vec![4, 5]]);
+        assert!(TlsOptions::parse_pem_certificates("").unwrap().is_empty());
+        // Ensure a truncated or corrupted certificate is rejected.
+        assert!(TlsOptions::parse_pem_certificates("-----BEGIN CERTIFICATE-----\nAAEC").is_err());
+        assert!(
+            TlsOptions::parse_pem_certificates("-----BEGIN CERTIFICATE-----\n!!\n-----END CERTIFICATE-----").is_err()
+        );
+    }
+
+    #[test]
+    fn test_install_rejects_mismatched_options() {
+        let options = |tls_verify, tls_ca_file: Option<&str>| TlsOptions {
+            tls_verify,
+            tls_ca_file: tls_ca_file.map(PathBuf::from),
+        };
+        // Ensure a CA bundle is only accepted with the `ca-file` mode, which requires one.
+        assert!(options(TlsVerify::Strict, Some("ca.pem")).install().is_err());
+        assert!(options(TlsVerify::Insecure, Some("ca.pem")).install().is_err());
+        assert!(options(TlsVerify::CaFile, None).install().is_err());
+        // Ensure a missing CA bundle is reported.
+        assert!(options(TlsVerify::CaFile, Some("/nonexistent/ca.pem")).install().is_err());
+        // Ensure the default mode installs nothing, and errors name it.
+        options(TlsVerify::Strict, None).install().unwrap();
+        assert_eq!(TlsOptions::describe_error("refused"), "refused (using strict TLS verification)");
+    }
+}
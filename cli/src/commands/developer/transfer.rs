@@ -0,0 +1,242 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{CurrentNetwork, Developer};
+
+use snarkvm::prelude::{
+    query::Query,
+    store::{helpers::memory::ConsensusMemory, ConsensusStore},
+    Address,
+    Locator,
+    PrivateKey,
+    Value,
+    VM,
+};
+
+use anyhow::{bail, Result};
+use clap::{Parser, ValueEnum};
+use std::str::FromStr;
+
+/// The visibility of the balances involved in a transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TransferMode {
+    /// Transfers from a private record to a private record, with `transfer_private`.
+    Private,
+    /// Transfers between public balances, with `transfer_public`.
+    Public,
+}
+
+impl TransferMode {
+    /// Returns the name of the `credits.aleo` function used for the transfer.
+    const fn function_name(&self) -> &'static str {
+        match self {
+            Self::Private => "transfer_private",
+            Self::Public => "transfer_public",
+        }
+    }
+}
+
+/// Transfers credits with the `credits.aleo` program.
+#[derive(Debug, Parser)]
+pub struct Transfer {
+    /// The recipient address.
+    #[clap(long)]
+    pub(super) recipient: Address<CurrentNetwork>,
+    /// The number of microcredits to transfer.
+    #[clap(long)]
+    pub(super) amount: u64,
+    /// The visibility of the transfer.
+    #[clap(long, value_enum, default_value_t = TransferMode::Private)]
+    pub(super) mode: TransferMode,
+    /// The input record used to craft a private transfer.
+    #[clap(long, required_if_eq("mode", "private"))]
+    pub(super) input_record: Option<String>,
+    /// The private key used to generate the execution.
+    #[clap(short, long)]
+    pub(super) private_key: String,
+    /// The endpoint to query node state from.
+    #[clap(short, long)]
+    pub(super) query: String,
+    /// The priority fee in microcredits.
+    #[clap(short, long)]
+    pub(super) fee: u64,
+    /// The record to spend the fee from.
+    #[clap(long)]
+    pub(super) record: String,
+    /// The endpoint used to broadcast the generated transaction.
+    #[clap(short, long, conflicts_with = "dry_run")]
+    pub(super) broadcast: Option<String>,
+    /// Performs a dry-run of transaction generation.
+    #[clap(short, long, conflicts_with = "broadcast")]
+    pub(super) dry_run: bool,
+    /// Store generated transfer transaction to a local file.
+    #[clap(long)]
+    pub(super) store: Option<String>,
+}
+
+impl Transfer {
+    /// Creates an Aleo transfer with the provided inputs.
+    pub fn parse(self) -> Result<String> {
+        // Ensure that the user has specified an action.
+        if !self.dry_run && self.broadcast.is_none() && self.store.is_none() {
+            bail!("❌ Please specify one of the following actions: --broadcast, --dry-run, --store");
+        }
+
+        // Specify the query
+        let query = Query::from(&self.query);
+
+        // Retrieve the private key.
+        let private_key = PrivateKey::from_str(&self.private_key)?;
+
+        let mode = format!("{:?}", self.mode).to_lowercase();
+        println!("📦 Creating {mode} transfer of {} microcredits to {}...\n", self.amount, self.recipient);
+
+        // Generate the transfer transaction.
+        let transaction = {
+            // Initialize an RNG.
+            let rng = &mut rand::thread_rng();
+
+            // Initialize the VM.
+            let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::open(None)?;
+            let vm = VM::from(store)?;
+
+            // Prepare the fees.
+            let fee_record = Developer::parse_record(&private_key, &self.record)?;
+            let fee = (fee_record, self.fee);
+
+            // Prepare the inputs for a transfer.
+            let mut inputs = Vec::with_capacity(3);
+            // The input record is only required by clap if the mode is given explicitly, not by default.
+            if self.mode == TransferMode::Private {
+                let input_record = match &self.input_record {
+                    Some(input_record) => input_record,
+                    None => bail!("❌ A private transfer requires an --input-record"),
+                };
+                inputs.push(Value::Record(Developer::parse_record(&private_key, input_record)?));
+            }
+            inputs.push(Value::from_str(&format!("{}", self.recipient))?);
+            inputs.push(Value::from_str(&format!("{}u64", self.amount))?);
+
+            // Create a new transaction.
+            let function = ("credits.aleo", self.mode.function_name());
+            vm.execute(&private_key, function, inputs.iter(), Some(fee), Some(query), rng)?
+        };
+        let locator = Locator::<CurrentNetwork>::from_str(&format!("credits.aleo/{}", self.mode.function_name()))?;
+        println!("✅ Created {mode} transfer of {} microcredits to {}\n", &self.amount, self.recipient);
+
+        // Determine if the transaction should be broadcast, stored, or displayed to user.
+        Developer::handle_transaction(self.broadcast, self.dry_run, self.store, transaction, locator.to_string(), 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, CLI};
+
+    const RECIPIENT: &str = "aleo1q6qstg8q8shwqf5m6q5fcenuwsdqsvp4hhsgfnx5chzjm3secyzqt9mxm8";
+
+    #[test]
+    fn clap_snarkos_transfer() {
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "transfer",
+            "--recipient",
+            RECIPIENT,
+            "--amount",
+            "100",
+            "--mode",
+            "public",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--fee",
+            "10",
+            "--record",
+            "RECORD",
+            "--dry-run",
+        ];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Transfer(transfer)) = cli.command {
+            assert_eq!(transfer.recipient, Address::from_str(RECIPIENT).unwrap());
+            assert_eq!(transfer.amount, 100);
+            assert_eq!(transfer.mode, TransferMode::Public);
+            assert_eq!(transfer.input_record, None);
+            assert_eq!(transfer.private_key, "PRIVATE_KEY");
+            assert_eq!(transfer.query, "QUERY");
+            assert_eq!(transfer.fee, 10);
+            assert_eq!(transfer.record, "RECORD");
+            assert!(transfer.dry_run);
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure a transfer is private by default.
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "transfer",
+            "--recipient",
+            RECIPIENT,
+            "--amount",
+            "100",
+            "--input-record",
+            "INPUT_RECORD",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--fee",
+            "10",
+            "--record",
+            "RECORD",
+            "--store",
+            "transfer.tx",
+        ];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Transfer(transfer)) = cli.command {
+            assert_eq!(transfer.mode, TransferMode::Private);
+            assert_eq!(transfer.input_record, Some("INPUT_RECORD".to_string()));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+
+        // Ensure an explicitly private transfer requires an input record.
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "transfer",
+            "--recipient",
+            RECIPIENT,
+            "--amount",
+            "100",
+            "--mode",
+            "private",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--fee",
+            "10",
+            "--record",
+            "RECORD",
+            "--dry-run",
+        ];
+        assert!(CLI::try_parse_from(arg_vec).is_err());
+    }
+}
@@ -12,21 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{CurrentNetwork, Developer};
+use super::{CurrentNetwork, Transfer, TransferMode};
 
-use snarkvm::prelude::{
-    query::Query,
-    store::{helpers::memory::ConsensusMemory, ConsensusStore},
-    Address,
-    Locator,
-    PrivateKey,
-    Value,
-    VM,
-};
+use snarkvm::prelude::Address;
 
-use anyhow::{bail, Result};
+use anyhow::Result;
 use clap::Parser;
-use std::str::FromStr;
 
 /// Executes the `transfer_private` function in the `credits.aleo` program.
 #[derive(Debug, Parser)]
@@ -58,56 +49,33 @@ pub struct TransferPrivate {
     /// Performs a dry-run of transaction generation.
     #[clap(short, long, conflicts_with = "broadcast")]
     dry_run: bool,
-    /// Store generated deployment transaction to a local file.
+    /// Store generated transfer transaction to a local file.
     #[clap(long)]
     store: Option<String>,
 }
 
 impl TransferPrivate {
     /// Creates an Aleo transfer with the provided inputs.
-    #[allow(clippy::format_in_format_args)]
     pub fn parse(self) -> Result<String> {
-        // Ensure that the user has specified an action.
-        if !self.dry_run && self.broadcast.is_none() && self.store.is_none() {
-            bail!("❌ Please specify one of the following actions: --broadcast, --dry-run, --store");
-        }
-
-        // Specify the query
-        let query = Query::from(&self.query);
-
-        // Retrieve the private key.
-        let private_key = PrivateKey::from_str(&self.private_key)?;
-
-        println!("📦 Creating private transfer of {} microcredits to {}...\n", self.amount, self.recipient);
-
-        // Generate the transfer_private transaction.
-        let transaction = {
-            // Initialize an RNG.
-            let rng = &mut rand::thread_rng();
-
-            // Initialize the VM.
-            let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::open(None)?;
-            let vm = VM::from(store)?;
-
-            // Prepare the fees.
-            let fee_record = Developer::parse_record(&private_key, &self.fee_record)?;
-            let fee = (fee_record, self.fee);
-
-            // Prepare the inputs for a transfer.
-            let input_record = Developer::parse_record(&private_key, &self.input_record)?;
-            let inputs = vec![
-                Value::Record(input_record),
-                Value::from_str(&format!("{}", self.recipient))?,
-                Value::from_str(&format!("{}u64", self.amount))?,
-            ];
-
-            // Create a new transaction.
-            vm.execute(&private_key, ("credits.aleo", "transfer_private"), inputs.iter(), Some(fee), Some(query), rng)?
-        };
-        let locator = Locator::<CurrentNetwork>::from_str("credits.aleo/transfer_private")?;
-        println!("✅ Created private transfer of {} microcredits to {}\n", &self.amount, self.recipient);
+        Transfer::from(self).parse()
+    }
+}
 
-        // Determine if the transaction should be broadcast, stored, or displayed to user.
-        Developer::handle_transaction(self.broadcast, self.dry_run, self.store, transaction, locator.to_string())
+impl From<TransferPrivate> for Transfer {
+    /// Returns the transfer in the private mode, which executes the same `transfer_private` function.
+    fn from(transfer: TransferPrivate) -> Self {
+        Self {
+            recipient: transfer.recipient,
+            amount: transfer.amount,
+            mode: TransferMode::Private,
+            input_record: Some(transfer.input_record),
+            private_key: transfer.private_key,
+            query: transfer.query,
+            fee: transfer.fee,
+            record: transfer.fee_record,
+            broadcast: transfer.broadcast,
+            dry_run: transfer.dry_run,
+            store: transfer.store,
+        }
     }
 }
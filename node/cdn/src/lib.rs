@@ -19,3 +19,6 @@ extern crate tracing;
 
 mod blocks;
 pub use blocks::{load_blocks, sync_ledger_with_cdn};
+
+mod parameters;
+pub use parameters::{download_parameters, verify_parameters};
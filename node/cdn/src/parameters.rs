@@ -0,0 +1,244 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, Result};
+use reqwest::{header::RANGE, StatusCode};
+use sha2::{Digest, Sha256};
+use std::{
+    fmt::{Display, Write as _},
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+/// Returns the path of the partial download for the given destination.
+fn partial_path(path: &Path) -> PathBuf {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    PathBuf::from(partial)
+}
+
+/// Returns the SHA-256 state of the given partial download, along with its length.
+fn resume_hasher(partial: &Path) -> Result<(Sha256, u64)> {
+    let mut hasher = Sha256::new();
+    let mut num_bytes = 0;
+    if let Ok(mut file) = File::open(partial) {
+        let mut buffer = vec![0u8; 1 << 16];
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            num_bytes += read as u64;
+        }
+    }
+    Ok((hasher, num_bytes))
+}
+
+/// Downloads the parameter file at the given URL to the given path, verifying it against the given
+/// hex-encoded SHA-256 checksum. An interrupted download is kept in a `.partial` file, and is resumed
+/// on the next call rather than restarted. The file is only moved into place once its checksum matches.
+pub async fn download_parameters(url: &str, path: &Path, checksum: &str) -> Result<()> {
+    let partial = partial_path(path);
+
+    // Restore the SHA-256 state of any previous partial download.
+    let (mut hasher, mut offset) = resume_hasher(&partial)?;
+
+    // Request the remainder of the file.
+    let mut request = reqwest::Client::new().get(url);
+    if offset > 0 {
+        debug!("Resuming the download of '{url}' from byte {offset}");
+        request = request.header(RANGE, format!("bytes={offset}-"));
+    }
+    let mut response = match request.send().await {
+        Ok(response) => response,
+        Err(error) => bail!("Failed to download '{url}': {error}"),
+    };
+
+    match response.status() {
+        // The server resumed the download from the requested offset.
+        StatusCode::PARTIAL_CONTENT => (),
+        // The partial download already holds the entire file.
+        StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => (),
+        // The server sent the entire file, so the partial download is restarted.
+        status if status.is_success() => {
+            if offset > 0 {
+                debug!("The server does not support resuming '{url}', restarting the download");
+            }
+            File::create(&partial)?;
+            (hasher, offset) = (Sha256::new(), 0);
+        }
+        status => bail!("Failed to download '{url}': status code {status}"),
+    }
+
+    // Append the remainder of the file to the partial download.
+    if response.status() != StatusCode::RANGE_NOT_SATISFIABLE {
+        let mut file = OpenOptions::new().create(true).append(true).open(&partial)?;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk)?;
+            hasher.update(&chunk);
+            offset += chunk.len() as u64;
+        }
+        file.sync_all()?;
+    }
+
+    // Ensure the checksum of the download matches the expected checksum.
+    let candidate = hasher.finalize().iter().map(|byte| format!("{byte:02x}")).collect::<String>();
+    if !candidate.eq_ignore_ascii_case(checksum.trim()) {
+        // Discard the corrupted download, so that it is restarted on the next attempt.
+        std::fs::remove_file(&partial)?;
+        bail!("The checksum of '{url}' ({offset} bytes) is {candidate}, expected {}", checksum.trim());
+    }
+
+    // Move the verified file into place.
+    std::fs::rename(&partial, path)?;
+    Ok(())
+}
+
+/// Loads each of the given parameter sets, using their `load_bytes` functions from `snarkvm::parameters`,
+/// and verifies that they all load successfully. Rather than stopping at the first checksum or size failure,
+/// every parameter set is loaded, and the error lists each parameter set that failed, by name.
+pub fn verify_parameters<E: Display>(parameters: &[(&str, fn() -> Result<Vec<u8>, E>)]) -> Result<()> {
+    let mut failures = String::new();
+    let mut num_failures = 0;
+    for (name, load_bytes) in parameters {
+        match load_bytes() {
+            Ok(bytes) => debug!("Verified the '{name}' parameters ({} bytes)", bytes.len()),
+            Err(error) => {
+                write!(failures, "\n  - {name}: {error}")?;
+                num_failures += 1;
+            }
+        }
+    }
+    if num_failures > 0 {
+        bail!("{num_failures} of {} parameter sets failed to verify:{failures}", parameters.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{
+        net::{SocketAddr, TcpListener},
+        sync::{Arc, Mutex},
+    };
+
+    /// Returns the hex-encoded SHA-256 checksum of the given bytes.
+    fn sha256(bytes: &[u8]) -> String {
+        Sha256::digest(bytes).iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    /// Starts a file server that supports `Range` requests, returning its address and the ranges it was asked for.
+    fn file_server(contents: &'static [u8]) -> (SocketAddr, Arc<Mutex<Vec<Option<String>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let ranges_clone = ranges.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                // Read the request headers.
+                let (mut request, mut buffer) = (Vec::new(), [0u8; 1024]);
+                while !request.ends_with(b"\r\n\r\n") {
+                    let num_bytes = stream.read(&mut buffer).unwrap();
+                    if num_bytes == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buffer[..num_bytes]);
+                }
+                let request = String::from_utf8(request).unwrap();
+                let range = request
+                    .lines()
+                    .find_map(|line| line.to_lowercase().strip_prefix("range: bytes=").map(|range| range.to_string()));
+                ranges_clone.lock().unwrap().push(range.clone());
+
+                // Respond with the requested range of the file.
+                let start = range.map_or(0, |range| range.trim_end_matches('-').parse::<usize>().unwrap());
+                let status = if start > 0 { "206 Partial Content" } else { "200 OK" };
+                let body = &contents[start..];
+                let header =
+                    format!("HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+                stream.write_all(header.as_bytes()).unwrap();
+                stream.write_all(body).unwrap();
+            }
+        });
+        (addr, ranges)
+    }
+
+    #[test]
+    fn test_download_parameters_resumes() {
+        const CONTENTS: &[u8] = b"the contents of a large parameter file";
+        let (addr, ranges) = file_server(CONTENTS);
+        let url = format!("http://{addr}/parameters");
+
+        let directory = std::env::temp_dir().join(format!("snarkos-parameters-{}", addr.port()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("parameters");
+
+        // Simulate an interrupted download.
+        std::fs::write(partial_path(&path), &CONTENTS[..10]).unwrap();
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            // Ensure a mismatched checksum is rejected, and the file is not moved into place.
+            assert!(download_parameters(&url, &path, &sha256(b"other")).await.is_err());
+            assert!(!path.exists());
+            assert!(!partial_path(&path).exists());
+
+            // Simulate another interrupted download, and ensure it is resumed and verified.
+            std::fs::write(partial_path(&path), &CONTENTS[..10]).unwrap();
+            download_parameters(&url, &path, &sha256(CONTENTS)).await.unwrap();
+        });
+
+        // Ensure the downloads were resumed from the end of the partial download.
+        assert_eq!(*ranges.lock().unwrap(), vec![Some("10-".to_string()), Some("10-".to_string())]);
+        // Ensure the verified file was moved into place.
+        assert_eq!(std::fs::read(&path).unwrap(), CONTENTS);
+        assert!(!partial_path(&path).exists());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_verify_parameters_aggregates_failures() {
+        fn valid() -> Result<Vec<u8>, String> {
+            Ok(vec![0u8; 4])
+        }
+        fn mismatched_checksum() -> Result<Vec<u8>, String> {
+            Err("Mismatched checksum".to_string())
+        }
+        fn mismatched_size() -> Result<Vec<u8>, String> {
+            Err("Mismatched size".to_string())
+        }
+
+        // Ensure valid parameters are verified.
+        verify_parameters(&[("valid", valid as fn() -> _), ("also_valid", valid)]).unwrap();
+
+        // Ensure every failure is reported, rather than only the first.
+        let error = verify_parameters(&[
+            ("checksum", mismatched_checksum as fn() -> _),
+            ("valid", valid),
+            ("size", mismatched_size),
+        ])
+        .unwrap_err()
+        .to_string();
+        assert_eq!(
+            error,
+            "2 of 3 parameter sets failed to verify:\n  - checksum: Mismatched checksum\n  - size: Mismatched size"
+        );
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+/// Acknowledges that the challenge response of the connection initiator was verified, concluding a handshake
+/// in which both sides require mutual authentication.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChallengeAck;
+
+impl MessageTrait for ChallengeAck {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> String {
+        "ChallengeAck".to_string()
+    }
+
+    /// Serializes the message into the buffer.
+    #[inline]
+    fn serialize<W: Write>(&self, _writer: &mut W) -> Result<()> {
+        Ok(())
+    }
+
+    /// Deserializes the given buffer into a message.
+    #[inline]
+    fn deserialize(bytes: BytesMut) -> Result<Self> {
+        match bytes.remaining() == 0 {
+            true => Ok(Self),
+            false => bail!("Invalid 'ChallengeAck' message"),
+        }
+    }
+}
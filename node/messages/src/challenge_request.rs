@@ -23,6 +23,8 @@ pub struct ChallengeRequest<N: Network> {
     pub node_type: NodeType,
     pub address: Address<N>,
     pub nonce: u64,
+    pub capabilities: Capabilities,
+    pub user_agent: String,
 }
 
 impl<N: Network> MessageTrait for ChallengeRequest<N> {
@@ -37,7 +39,15 @@ impl<N: Network> MessageTrait for ChallengeRequest<N> {
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
         Ok(bincode::serialize_into(
             writer,
-            &(self.version, self.listener_port, self.node_type, self.address, self.nonce),
+            &(
+                self.version,
+                self.listener_port,
+                self.node_type,
+                self.address,
+                self.nonce,
+                self.capabilities,
+                &self.user_agent,
+            ),
         )?)
     }
 
@@ -46,13 +56,53 @@ impl<N: Network> MessageTrait for ChallengeRequest<N> {
     fn deserialize(bytes: BytesMut) -> Result<Self> {
         let options =
             bincode::options().with_limit(MAXIMUM_MESSAGE_SIZE as u64).with_fixint_encoding().allow_trailing_bytes();
-        let (version, listener_port, node_type, address, nonce) = options.deserialize_from(&mut bytes.reader())?;
-        Ok(Self { version, listener_port, node_type, address, nonce })
+        let mut reader = bytes.reader();
+        let (version, listener_port, node_type, address, nonce) = options.deserialize_from(&mut reader)?;
+        // The capabilities are trailing, so that peers which do not advertise them are treated as having none.
+        // Only the end of the message marks them as absent; malformed capabilities are rejected.
+        let capabilities = match reader.get_ref().has_remaining() {
+            true => options.deserialize_from(&mut reader)?,
+            false => Capabilities::NONE,
+        };
+        // The user agent is trailing as well, so that peers which do not advertise one are treated as having none.
+        let user_agent = match reader.get_ref().has_remaining() {
+            true => {
+                // Ensure the length of the user agent is within the limit, before its bytes are allocated.
+                let length: u64 = options.deserialize_from(&mut reader)?;
+                if length > Self::MAXIMUM_USER_AGENT_LENGTH as u64 {
+                    bail!(
+                        "The user agent of {length} bytes exceeds the limit of {} bytes",
+                        Self::MAXIMUM_USER_AGENT_LENGTH
+                    )
+                }
+                let mut user_agent = vec![0; length as usize];
+                reader.read_exact(&mut user_agent)?;
+                Self::sanitize_user_agent(&String::from_utf8(user_agent)?)
+            }
+            false => String::new(),
+        };
+        Ok(Self { version, listener_port, node_type, address, nonce, capabilities, user_agent })
     }
 }
 
 impl<N: Network> ChallengeRequest<N> {
-    pub fn new(listener_port: u16, node_type: NodeType, address: Address<N>, nonce: u64) -> Self {
-        Self { version: Message::<N>::VERSION, listener_port, node_type, address, nonce }
+    /// The maximum length in bytes of the user agent a peer may advertise.
+    pub const MAXIMUM_USER_AGENT_LENGTH: usize = 256;
+
+    pub fn new(
+        listener_port: u16,
+        node_type: NodeType,
+        address: Address<N>,
+        nonce: u64,
+        capabilities: Capabilities,
+        user_agent: String,
+    ) -> Self {
+        Self { version: Message::<N>::VERSION, listener_port, node_type, address, nonce, capabilities, user_agent }
+    }
+
+    /// Returns the given user agent with its control characters replaced by `?`, so that a peer cannot inject
+    /// escape sequences or line breaks into the logs.
+    pub fn sanitize_user_agent(user_agent: &str) -> String {
+        user_agent.chars().map(|c| if c.is_control() { '?' } else { c }).collect()
     }
 }
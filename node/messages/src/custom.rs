@@ -0,0 +1,51 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use ::bytes::Bytes;
+
+/// A message of an application-level protocol built on top of the router, with a payload opaque to the router.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Custom {
+    /// The ID of the protocol the message belongs to.
+    pub protocol: u16,
+    /// The payload of the message.
+    pub payload: Bytes,
+}
+
+impl MessageTrait for Custom {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> String {
+        "Custom".to_string()
+    }
+
+    /// Serializes the message into the buffer.
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(&self.protocol.to_le_bytes())?;
+        Ok(writer.write_all(&self.payload)?)
+    }
+
+    /// Deserializes the given buffer into a message.
+    #[inline]
+    fn deserialize(mut bytes: BytesMut) -> Result<Self> {
+        if bytes.remaining() < 2 {
+            bail!("Invalid 'Custom' message");
+        }
+        let protocol = bytes.get_u16_le();
+        Ok(Self { protocol, payload: bytes.freeze() })
+    }
+}
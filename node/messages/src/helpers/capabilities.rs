@@ -0,0 +1,62 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+/// The set of optional features a node supports, advertised to its peers during the handshake.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Capabilities(u64);
+
+impl Capabilities {
+    /// The node compresses its messages after the handshake, with any peer that also advertises it.
+    /// The handshake messages themselves are never compressed, as the capabilities are only known once they
+    /// have been exchanged.
+    pub const COMPRESSION: Self = Self(1 << 2);
+    /// The node requires mutual authentication, and acknowledges the verified challenge response of its peer.
+    pub const MUTUAL_AUTH: Self = Self(1 << 3);
+    /// The node does not advertise any optional features.
+    pub const NONE: Self = Self(0);
+    /// The node accepts transactions that pay their fee from a public balance.
+    pub const PUBLIC_FEES: Self = Self(1 << 0);
+    /// The node serves the REST API.
+    pub const REST: Self = Self(1 << 1);
+
+    /// Initializes the capabilities from the given bits. Unknown bits are retained, for forward compatibility.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the bits of the capabilities.
+    pub const fn bits(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns `true` if all of the given capabilities are supported.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the union of the two sets of capabilities.
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl core::ops::BitOr for Capabilities {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
@@ -15,8 +15,10 @@
 use crate::Message;
 use snarkvm::prelude::Network;
 
-use ::bytes::{BufMut, BytesMut};
+use ::bytes::{BufMut, Bytes, BytesMut};
 use core::marker::PhantomData;
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use std::io::{Read, Write};
 use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
 
 /// The maximum size of a message that can be transmitted during the handshake.
@@ -28,21 +30,55 @@ pub(crate) const MAXIMUM_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
 /// The codec used to decode and encode network `Message`s.
 pub struct MessageCodec<N: Network> {
     codec: LengthDelimitedCodec,
+    /// The boolean flag indicating whether the message payloads are compressed.
+    is_compressed: bool,
     _phantom: PhantomData<N>,
 }
 
 impl<N: Network> MessageCodec<N> {
+    /// Initializes a codec for the handshake messages. It never compresses them, even between peers that
+    /// both advertise `Capabilities::COMPRESSION`, as compression is negotiated by the handshake itself.
     pub fn handshake() -> Self {
         let mut codec = Self::default();
         codec.codec.set_max_frame_length(MAXIMUM_HANDSHAKE_MESSAGE_SIZE);
         codec
     }
+
+    /// Initializes a codec that compresses the message payloads with DEFLATE. Both sides of a connection
+    /// must use it, which is negotiated during the handshake with `Capabilities::COMPRESSION`, so it only
+    /// applies to the messages exchanged after the handshake.
+    pub fn compressed() -> Self {
+        Self { is_compressed: true, ..Self::default() }
+    }
+
+    /// Returns `true` if the message payloads are compressed.
+    pub const fn is_compressed(&self) -> bool {
+        self.is_compressed
+    }
+
+    /// Compresses the given message payload.
+    fn compress(payload: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut encoder = DeflateEncoder::new(Vec::with_capacity(payload.len() / 2), Compression::fast());
+        encoder.write_all(payload)?;
+        encoder.finish()
+    }
+
+    /// Decompresses the given message payload, failing if it exceeds the maximum message size once decompressed.
+    fn decompress(payload: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut decompressed = Vec::new();
+        DeflateDecoder::new(payload).take(MAXIMUM_MESSAGE_SIZE as u64 + 1).read_to_end(&mut decompressed)?;
+        if decompressed.len() > MAXIMUM_MESSAGE_SIZE {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "decompressed message is too large"));
+        }
+        Ok(decompressed)
+    }
 }
 
 impl<N: Network> Default for MessageCodec<N> {
     fn default() -> Self {
         Self {
             codec: LengthDelimitedCodec::builder().max_frame_length(MAXIMUM_MESSAGE_SIZE).little_endian().new_codec(),
+            is_compressed: false,
             _phantom: Default::default(),
         }
     }
@@ -59,6 +95,10 @@ impl<N: Network> Encoder<Message<N>> for MessageCodec<N> {
             .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "serialization error"))?;
 
         let serialized_message = dst.split_to(dst.len()).freeze();
+        let serialized_message = match self.is_compressed {
+            true => Bytes::from(Self::compress(&serialized_message)?),
+            false => serialized_message,
+        };
 
         self.codec.encode(serialized_message, dst)
     }
@@ -74,6 +114,10 @@ impl<N: Network> Decoder for MessageCodec<N> {
             Some(bytes) => bytes,
             None => return Ok(None),
         };
+        let bytes = match self.is_compressed {
+            true => BytesMut::from(&Self::decompress(&bytes)?[..]),
+            false => bytes,
+        };
 
         // Convert the bytes to a message, or fail if it is not valid.
         match Message::deserialize(bytes) {
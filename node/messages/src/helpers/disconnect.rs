@@ -47,4 +47,10 @@ pub enum DisconnectReason {
     YouNeedToSyncFirst,
     /// The peer's listening port is closed.
     YourPortIsClosed(u16),
+    /// The node is not ready to accept connections, e.g. because it is still syncing.
+    NotReady,
+    /// The peer's handshake is no longer valid, e.g. because the protocol version policy changed.
+    InvalidHandshake,
+    /// The peer is refused, e.g. because it is already connected under another address.
+    PeerRefused,
 }
@@ -15,6 +15,9 @@
 pub mod block_locators;
 pub use block_locators::*;
 
+mod capabilities;
+pub use capabilities::Capabilities;
+
 mod codec;
 pub use codec::MessageCodec;
 pub(crate) use codec::MAXIMUM_MESSAGE_SIZE;
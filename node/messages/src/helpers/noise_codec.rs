@@ -278,9 +278,11 @@ mod tests {
 
     use crate::{
         BlockRequest,
+        Capabilities,
         ChallengeRequest,
         Disconnect,
         DisconnectReason,
+        MessageTrait,
         NodeType,
         PeerRequest,
         PeerResponse,
@@ -356,11 +358,89 @@ mod tests {
             node_type: NodeType::Client,
             address: Address::new(Group::rand(rng)),
             nonce: 0,
+            capabilities: Capabilities::PUBLIC_FEES,
+            user_agent: "snarkOS/test".to_string(),
         })));
 
         assert_roundtrip(challenge_request);
     }
 
+    #[test]
+    fn challenge_request_trailing_capabilities() {
+        let rng = &mut TestRng::default();
+
+        let challenge_request = ChallengeRequest::<CurrentNetwork> {
+            version: 0,
+            listener_port: 0,
+            node_type: NodeType::Client,
+            address: Address::new(Group::rand(rng)),
+            nonce: 0,
+            capabilities: Capabilities::PUBLIC_FEES,
+            user_agent: String::new(),
+        };
+        let mut bytes = BytesMut::new().writer();
+        challenge_request.serialize(&mut bytes).unwrap();
+        let bytes = bytes.into_inner();
+        // The capabilities are followed by the 8-byte length of the empty user agent.
+        let without_capabilities = bytes.len() - 16;
+
+        // Ensure the request of a peer that advertises no capabilities is accepted.
+        let request = ChallengeRequest::<CurrentNetwork>::deserialize(bytes.clone().split_to(without_capabilities));
+        assert_eq!(request.unwrap().capabilities, Capabilities::NONE);
+
+        // Ensure the request is rejected if its capabilities are truncated.
+        let truncated = bytes.clone().split_to(without_capabilities + 4);
+        assert!(ChallengeRequest::<CurrentNetwork>::deserialize(truncated).is_err());
+    }
+
+    #[test]
+    fn challenge_request_trailing_user_agent() {
+        let rng = &mut TestRng::default();
+
+        let challenge_request = ChallengeRequest::<CurrentNetwork> {
+            version: 0,
+            listener_port: 0,
+            node_type: NodeType::Client,
+            address: Address::new(Group::rand(rng)),
+            nonce: 0,
+            capabilities: Capabilities::PUBLIC_FEES,
+            user_agent: "snarkOS/test".to_string(),
+        };
+        let mut bytes = BytesMut::new().writer();
+        challenge_request.serialize(&mut bytes).unwrap();
+        let bytes = bytes.into_inner();
+        // The user agent is serialized as its 8-byte length, followed by its bytes.
+        let without_user_agent = bytes.len() - 8 - challenge_request.user_agent.len();
+
+        // Ensure the request of a peer that advertises no user agent is accepted.
+        let request = ChallengeRequest::<CurrentNetwork>::deserialize(bytes.clone().split_to(without_user_agent));
+        assert_eq!(request.unwrap().user_agent, "");
+
+        // Ensure the request is rejected if its user agent is truncated, in its length or in its bytes.
+        for len in [without_user_agent + 4, bytes.len() - 1] {
+            let truncated = bytes.clone().split_to(len);
+            assert!(ChallengeRequest::<CurrentNetwork>::deserialize(truncated).is_err());
+        }
+
+        // Ensure an oversized user agent is rejected by its length, without allocating its bytes.
+        let mut oversized = bytes.clone().split_to(without_user_agent);
+        oversized.extend_from_slice(&u64::MAX.to_le_bytes());
+        assert!(ChallengeRequest::<CurrentNetwork>::deserialize(oversized).is_err());
+        let mut oversized = bytes.clone().split_to(without_user_agent);
+        let user_agent = "a".repeat(ChallengeRequest::<CurrentNetwork>::MAXIMUM_USER_AGENT_LENGTH + 1);
+        oversized.extend_from_slice(&(user_agent.len() as u64).to_le_bytes());
+        oversized.extend_from_slice(user_agent.as_bytes());
+        assert!(ChallengeRequest::<CurrentNetwork>::deserialize(oversized).is_err());
+
+        // Ensure the control characters of a user agent are replaced, before it is stored or logged.
+        let mut escaped = bytes.clone().split_to(without_user_agent);
+        let user_agent = "snarkOS\x1b[31m\r\nforged";
+        escaped.extend_from_slice(&(user_agent.len() as u64).to_le_bytes());
+        escaped.extend_from_slice(user_agent.as_bytes());
+        let request = ChallengeRequest::<CurrentNetwork>::deserialize(escaped).unwrap();
+        assert_eq!(request.user_agent, "snarkOS?[31m??forged");
+    }
+
     #[test]
     fn disconnect_roundtrip() {
         let disconnect = MessageOrBytes::Message(Box::new(Message::Disconnect(Disconnect {
@@ -35,12 +35,18 @@ pub use block_request::BlockRequest;
 mod block_response;
 pub use block_response::{BlockResponse, DataBlocks};
 
+mod challenge_ack;
+pub use challenge_ack::ChallengeAck;
+
 mod challenge_request;
 pub use challenge_request::ChallengeRequest;
 
 mod challenge_response;
 pub use challenge_response::ChallengeResponse;
 
+mod custom;
+pub use custom::Custom;
+
 mod disconnect;
 pub use disconnect::Disconnect;
 
@@ -118,6 +124,9 @@ pub enum Message<N: Network> {
     PuzzleResponse(PuzzleResponse<N>),
     UnconfirmedSolution(UnconfirmedSolution<N>),
     UnconfirmedTransaction(UnconfirmedTransaction<N>),
+    // Appended out of order, to keep the IDs of the preceding messages stable.
+    ChallengeAck(ChallengeAck),
+    Custom(Custom),
 }
 
 impl<N: Network> Message<N> {
@@ -144,6 +153,8 @@ impl<N: Network> Message<N> {
             Self::PuzzleResponse(message) => message.name(),
             Self::UnconfirmedSolution(message) => message.name(),
             Self::UnconfirmedTransaction(message) => message.name(),
+            Self::ChallengeAck(message) => message.name(),
+            Self::Custom(message) => message.name(),
         }
     }
 
@@ -167,6 +178,8 @@ impl<N: Network> Message<N> {
             Self::PuzzleResponse(..) => 13,
             Self::UnconfirmedSolution(..) => 14,
             Self::UnconfirmedTransaction(..) => 15,
+            Self::ChallengeAck(..) => 16,
+            Self::Custom(..) => 17,
         }
     }
 
@@ -192,6 +205,8 @@ impl<N: Network> Message<N> {
             Self::PuzzleResponse(message) => message.serialize(writer),
             Self::UnconfirmedSolution(message) => message.serialize(writer),
             Self::UnconfirmedTransaction(message) => message.serialize(writer),
+            Self::ChallengeAck(message) => message.serialize(writer),
+            Self::Custom(message) => message.serialize(writer),
         }
     }
 
@@ -224,6 +239,8 @@ impl<N: Network> Message<N> {
             13 => Self::PuzzleResponse(MessageTrait::deserialize(bytes)?),
             14 => Self::UnconfirmedSolution(MessageTrait::deserialize(bytes)?),
             15 => Self::UnconfirmedTransaction(MessageTrait::deserialize(bytes)?),
+            16 => Self::ChallengeAck(MessageTrait::deserialize(bytes)?),
+            17 => Self::Custom(MessageTrait::deserialize(bytes)?),
             _ => bail!("Unknown message ID {id}"),
         };
 
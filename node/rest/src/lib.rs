@@ -127,6 +127,7 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             .route("/testnet3/peers/count", get(Self::get_peers_count))
             .route("/testnet3/peers/all", get(Self::get_peers_all))
             .route("/testnet3/peers/all/metrics", get(Self::get_peers_all_metrics))
+            .route("/testnet3/peers/all/info", get(Self::get_peers_all_info))
 
             // GET ../program/..
             .route("/testnet3/program/:id", get(Self::get_program))
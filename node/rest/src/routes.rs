@@ -187,6 +187,13 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         ErasedJson::pretty(rest.routing.router().connected_metrics())
     }
 
+    // GET /testnet3/peers/all/info
+    pub(crate) async fn get_peers_all_info(State(rest): State<Self>) -> ErasedJson {
+        let router = rest.routing.router();
+        let peers = router.connected_peers().iter().filter_map(|peer_ip| router.peer_info(peer_ip)).collect::<Vec<_>>();
+        ErasedJson::pretty(peers)
+    }
+
     // GET /testnet3/node/address
     pub(crate) async fn get_node_address(State(rest): State<Self>) -> ErasedJson {
         ErasedJson::pretty(rest.routing.router().address())
@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{Peer, Router};
+use crate::{ConnectError, HandshakeError, Peer, ProbeResult, Router, Transport};
 use snarkos_node_messages::{
+    Capabilities,
+    ChallengeAck,
     ChallengeRequest,
     ChallengeResponse,
     Data,
@@ -29,7 +31,11 @@ use snarkvm::prelude::{block::Header, error, Address, Network};
 use anyhow::{bail, Result};
 use futures::SinkExt;
 use rand::{rngs::OsRng, Rng};
-use std::{io, net::SocketAddr};
+use std::{
+    io,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
 use tokio::net::TcpStream;
 use tokio_stream::StreamExt;
 use tokio_util::codec::Framed;
@@ -52,8 +58,8 @@ macro_rules! expect_message {
                 data
             }
             // Received a disconnect message, abort.
-            Some(Message::Disconnect(reason)) => {
-                return Err(error(format!("'{}' disconnected: {reason:?}", $peer_addr)))
+            Some(Message::Disconnect(disconnect)) => {
+                return Err($crate::HandshakeError::Disconnected(disconnect.reason).into())
             }
             // Received an unexpected message, abort.
             Some(ty) => {
@@ -75,24 +81,26 @@ macro_rules! expect_message {
 /// A macro for cutting a handshake short if message verification fails.
 #[macro_export]
 macro_rules! handle_verification {
-    ($result:expr, $framed:expr, $peer_addr:expr) => {
+    ($router:expr, $result:expr, $framed:expr, $peer_addr:expr) => {
         if let Some(reason) = $result {
             trace!("Sending 'Disconnect' to '{}'", $peer_addr);
+            $router.trace_handshake("Sending", $peer_addr, || format!("Disconnect {{ reason: {reason:?} }}"));
+            $router.record_disconnect(&reason, true);
             $framed.send(Message::Disconnect(Disconnect { reason: reason.clone() })).await?;
-            return Err(error(format!("Dropped '{}' for reason: {reason:?}", $peer_addr)));
+            return Err($crate::HandshakeError::Dropped(reason).into());
         }
     };
 }
 
 impl<N: Network> Router<N> {
     /// Executes the handshake protocol.
-    pub async fn handshake<'a>(
+    pub async fn handshake<'a, S: Transport>(
         &'a self,
         peer_addr: SocketAddr,
-        stream: &'a mut TcpStream,
+        stream: &'a mut S,
         peer_side: ConnectionSide,
         genesis_header: Header<N>,
-    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
+    ) -> io::Result<(SocketAddr, Framed<&'a mut S, MessageCodec<N>>)> {
         // If this is an inbound connection, we log it, but don't know the listening address yet.
         // Otherwise, we can immediately register the listening address.
         let mut peer_ip = if peer_side == ConnectionSide::Initiator {
@@ -103,13 +111,28 @@ impl<N: Network> Router<N> {
             Some(peer_addr)
         };
 
+        // If this node dialed the peer, presume the handshake fails until it concludes, as a handshake that
+        // times out is cancelled before it can record its failure.
+        if peer_side == ConnectionSide::Responder {
+            let timeout = ConnectError::HandshakeFailed("the handshake timed out".to_string());
+            self.connection_errors.write().insert(peer_addr, timeout);
+        }
+
         // Perform the handshake; we pass on a mutable reference to peer_ip in case the process is broken at any point in time.
+        let timer = Instant::now();
         let handshake_result = if peer_side == ConnectionSide::Responder {
             self.handshake_inner_initiator(peer_addr, &mut peer_ip, stream, genesis_header).await
         } else {
             self.handshake_inner_responder(peer_addr, &mut peer_ip, stream, genesis_header).await
         };
 
+        // If the handshake succeeded, add the peer to the router.
+        let handshake_result = handshake_result.map(|(peer, framed)| {
+            let peer_ip = peer.ip();
+            self.insert_connected_peer(peer, peer_addr);
+            (peer_ip, framed)
+        });
+
         // Remove the address from the collection of connecting peers (if the handshake got to the point where it's known).
         if let Some(ip) = peer_ip {
             self.connecting_peers.lock().remove(&ip);
@@ -118,19 +141,71 @@ impl<N: Network> Router<N> {
         // If the handshake succeeded, announce it.
         if let Ok((ref peer_ip, _)) = handshake_result {
             info!("Connected to '{peer_ip}'");
+            self.record_handshake_duration(timer.elapsed());
+        }
+
+        // If the handshake failed, record its cause.
+        if let Err(error) = &handshake_result {
+            self.record_handshake_failure(peer_ip.unwrap_or(peer_addr), HandshakeError::from(error));
+        }
+
+        // If the peer dialed this node, record the outcome of its connection attempt. The attempts dialed by this
+        // node are recorded once the connection concludes, including those that fail before the handshake.
+        if peer_side == ConnectionSide::Initiator {
+            self.record_connection_attempt(handshake_result.is_ok());
+        }
+
+        // If this node dialed the peer, record the outcome of the handshake.
+        if peer_side == ConnectionSide::Responder {
+            match &handshake_result {
+                Ok(_) => self.connection_errors.write().remove(&peer_addr),
+                Err(error) => {
+                    self.connection_errors.write().insert(peer_addr, ConnectError::HandshakeFailed(error.to_string()))
+                }
+            };
         }
 
         handshake_result
     }
 
+    /// Dials the given peer and performs the handshake, then immediately disconnects from it.
+    ///
+    /// The peer is not added to the connected peers, and the probe is not counted in the connection metrics,
+    /// so it can check the reachability of a peer before committing a connection slot to it. A peer that this
+    /// node is not allowed to connect to, e.g. as it is banned, is not dialed.
+    pub async fn probe(&self, peer_ip: SocketAddr, timeout: Duration, genesis_header: Header<N>) -> ProbeResult {
+        // Ensure the attempt is not against the protocol rules, as for a connection.
+        if let Err(forbidden_message) = self.check_connection_attempt(peer_ip) {
+            return ProbeResult::Unreachable { reason: forbidden_message.to_string() };
+        }
+        let timer = Instant::now();
+        let probe = async {
+            let mut stream = TcpStream::connect(peer_ip).await?;
+            let (_, mut framed) =
+                self.handshake_inner_initiator(peer_ip, &mut Some(peer_ip), &mut stream, genesis_header).await?;
+            let latency = timer.elapsed();
+            // Tell the peer to drop the connection, instead of leaving it to time out.
+            framed.send(Message::Disconnect(DisconnectReason::NoReasonGiven.into())).await?;
+            Ok::<_, io::Error>(latency)
+        };
+        let result = match tokio::time::timeout(timeout, probe).await {
+            Ok(Ok(latency)) => ProbeResult::Reachable { latency },
+            Ok(Err(error)) => ProbeResult::Unreachable { reason: error.to_string() },
+            Err(_) => ProbeResult::Unreachable { reason: format!("the probe timed out after {timeout:?}") },
+        };
+        // Release the peer for the connection attempts that follow.
+        self.connecting_peers.lock().remove(&peer_ip);
+        result
+    }
+
     /// The connection initiator side of the handshake.
-    async fn handshake_inner_initiator<'a>(
+    async fn handshake_inner_initiator<'a, S: Transport>(
         &'a self,
         peer_addr: SocketAddr,
         peer_ip: &mut Option<SocketAddr>,
-        stream: &'a mut TcpStream,
+        stream: &'a mut S,
         genesis_header: Header<N>,
-    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
+    ) -> io::Result<(Peer<N>, Framed<&'a mut S, MessageCodec<N>>)> {
         // Construct the stream.
         let mut framed = Framed::new(stream, MessageCodec::<N>::handshake());
 
@@ -145,20 +220,31 @@ impl<N: Network> Router<N> {
         let our_nonce = rng.gen();
 
         // Send a challenge request to the peer.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce);
+        let our_request = ChallengeRequest::new(
+            self.local_ip()?.port(),
+            self.node_type,
+            self.address(),
+            our_nonce,
+            self.advertised_capabilities(),
+            self.user_agent(),
+        );
         trace!("Sending '{}' to '{peer_addr}'", our_request.name());
+        self.trace_handshake("Sending", peer_addr, || redact_challenge_request(&our_request));
         framed.send(Message::ChallengeRequest(our_request)).await?;
 
         /* Step 2: Receive the peer's challenge response followed by the challenge request. */
 
         // Listen for the challenge response message.
         let peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
+        self.trace_handshake("Received", peer_addr, || redact_challenge_response(&peer_response));
 
         // Listen for the challenge request message.
         let peer_request = expect_message!(Message::ChallengeRequest, framed, peer_addr);
+        self.trace_handshake("Received", peer_addr, || redact_challenge_request(&peer_request));
 
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         handle_verification!(
+            self,
             self.verify_challenge_response(peer_addr, peer_request.address, peer_response, genesis_header, our_nonce)
                 .await,
             framed,
@@ -166,7 +252,7 @@ impl<N: Network> Router<N> {
         );
 
         // Verify the challenge request. If a disconnect reason was returned, send the disconnect message and abort.
-        handle_verification!(self.verify_challenge_request(peer_addr, &peer_request), framed, peer_addr);
+        handle_verification!(self, self.verify_challenge_request(peer_addr, &peer_request), framed, peer_addr);
 
         /* Step 3: Send the challenge response. */
 
@@ -179,22 +265,27 @@ impl<N: Network> Router<N> {
         // Send the challenge response.
         let our_response = ChallengeResponse { genesis_header, signature: Data::Object(our_signature) };
         trace!("Sending '{}' to '{peer_addr}'", our_response.name());
+        self.trace_handshake("Sending", peer_addr, || redact_challenge_response(&our_response));
         framed.send(Message::ChallengeResponse(our_response)).await?;
 
-        // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+        /* Step 4: If mutual authentication is required, receive the acknowledgement of the challenge response. */
+
+        if self.is_mutual_auth_enabled() {
+            let peer_ack = expect_message!(Message::ChallengeAck, framed, peer_addr);
+            self.trace_handshake("Received", peer_addr, || format!("{peer_ack:?}"));
+        }
 
-        Ok((peer_ip, framed))
+        Ok((Peer::new(peer_ip, &peer_request, ConnectionSide::Responder), framed))
     }
 
     /// The connection responder side of the handshake.
-    async fn handshake_inner_responder<'a>(
+    async fn handshake_inner_responder<'a, S: Transport>(
         &'a self,
         peer_addr: SocketAddr,
         peer_ip: &mut Option<SocketAddr>,
-        stream: &'a mut TcpStream,
+        stream: &'a mut S,
         genesis_header: Header<N>,
-    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
+    ) -> io::Result<(Peer<N>, Framed<&'a mut S, MessageCodec<N>>)> {
         // Construct the stream.
         let mut framed = Framed::new(stream, MessageCodec::<N>::handshake());
 
@@ -202,6 +293,7 @@ impl<N: Network> Router<N> {
 
         // Listen for the challenge request message.
         let peer_request = expect_message!(Message::ChallengeRequest, framed, peer_addr);
+        self.trace_handshake("Received", peer_addr, || redact_challenge_request(&peer_request));
 
         // Obtain the peer's listening address.
         *peer_ip = Some(SocketAddr::new(peer_addr.ip(), peer_request.listener_port));
@@ -212,8 +304,11 @@ impl<N: Network> Router<N> {
             return Err(error(format!("{forbidden_message}")));
         }
 
+        // Ensure the node is ready to accept connections. If not, send the disconnect message and abort.
+        handle_verification!(self, (!self.is_ready()).then_some(DisconnectReason::NotReady), framed, peer_addr);
+
         // Verify the challenge request. If a disconnect reason was returned, send the disconnect message and abort.
-        handle_verification!(self.verify_challenge_request(peer_addr, &peer_request), framed, peer_addr);
+        handle_verification!(self, self.verify_challenge_request(peer_addr, &peer_request), framed, peer_addr);
 
         /* Step 2: Send the challenge response followed by own challenge request. */
 
@@ -232,30 +327,61 @@ impl<N: Network> Router<N> {
         // Send the challenge response.
         let our_response = ChallengeResponse { genesis_header, signature: Data::Object(our_signature) };
         trace!("Sending '{}' to '{peer_addr}'", our_response.name());
+        self.trace_handshake("Sending", peer_addr, || redact_challenge_response(&our_response));
         framed.send(Message::ChallengeResponse(our_response)).await?;
 
         // Send the challenge request.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce);
+        let our_request = ChallengeRequest::new(
+            self.local_ip()?.port(),
+            self.node_type,
+            self.address(),
+            our_nonce,
+            self.advertised_capabilities(),
+            self.user_agent(),
+        );
         trace!("Sending '{}' to '{peer_addr}'", our_request.name());
+        self.trace_handshake("Sending", peer_addr, || redact_challenge_request(&our_request));
         framed.send(Message::ChallengeRequest(our_request)).await?;
 
         /* Step 3: Receive the challenge response. */
 
         // Listen for the challenge response message.
         let peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
+        self.trace_handshake("Received", peer_addr, || redact_challenge_response(&peer_response));
 
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         handle_verification!(
+            self,
             self.verify_challenge_response(peer_addr, peer_request.address, peer_response, genesis_header, our_nonce)
                 .await,
             framed,
             peer_addr
         );
 
-        // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+        /* Step 4: If mutual authentication is required, acknowledge the verified challenge response. */
 
-        Ok((peer_ip, framed))
+        if self.is_mutual_auth_enabled() {
+            trace!("Sending '{}' to '{peer_addr}'", ChallengeAck.name());
+            self.trace_handshake("Sending", peer_addr, || format!("{ChallengeAck:?}"));
+            framed.send(Message::ChallengeAck(ChallengeAck)).await?;
+        }
+
+        Ok((Peer::new(peer_ip, &peer_request, ConnectionSide::Initiator), framed))
+    }
+
+    /// Returns the capabilities advertised in the challenge request, including mutual authentication if it is required.
+    fn advertised_capabilities(&self) -> Capabilities {
+        match self.is_mutual_auth_enabled() {
+            true => self.capabilities() | Capabilities::MUTUAL_AUTH,
+            false => self.capabilities(),
+        }
+    }
+
+    /// Logs the given description of a handshake message at trace level, if handshake tracing is enabled.
+    pub(crate) fn trace_handshake(&self, action: &str, peer_addr: SocketAddr, description: impl FnOnce() -> String) {
+        if self.is_handshake_tracing_enabled() {
+            trace!("{action} handshake message with '{peer_addr}': {}", description());
+        }
     }
 
     /// Ensure the peer is allowed to connect.
@@ -276,6 +402,10 @@ impl<N: Network> Router<N> {
         if self.is_restricted(&peer_ip) {
             bail!("Dropping connection request from '{peer_ip}' (restricted)")
         }
+        // Ensure the peer is not banned.
+        if self.is_banned(&peer_ip.ip()) {
+            bail!("Dropping connection request from '{peer_ip}' (banned)")
+        }
         // Ensure the peer is not spamming connection attempts.
         if !peer_ip.ip().is_loopback() {
             // Add this connection attempt and retrieve the number of attempts.
@@ -297,14 +427,35 @@ impl<N: Network> Router<N> {
         message: &ChallengeRequest<N>,
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge request.
-        let &ChallengeRequest { version, listener_port: _, node_type, address, nonce: _ } = message;
+        let &ChallengeRequest { version, listener_port: _, node_type, address, nonce: _, capabilities, user_agent: _ } =
+            message;
 
         // Ensure the message protocol version is not outdated.
-        if version < Message::<N>::VERSION {
+        if version < self.minimum_peer_version() {
             warn!("Dropping '{peer_addr}' on version {version} (outdated)");
             return Some(DisconnectReason::OutdatedClientVersion);
         }
 
+        // Ensure the subnet of the peer does not exceed its cap on connected peers.
+        if self.is_subnet_full(peer_addr.ip()) {
+            warn!("Dropping '{peer_addr}' (too many peers in its subnet)");
+            return Some(DisconnectReason::TooManyPeers);
+        }
+
+        // Ensure the peer also requires mutual authentication if this node does, as it would not conclude it otherwise.
+        if self.is_mutual_auth_enabled() && !capabilities.contains(Capabilities::MUTUAL_AUTH) {
+            warn!("Dropping '{peer_addr}' (does not require mutual authentication)");
+            return Some(DisconnectReason::InvalidHandshake);
+        }
+
+        // Ensure the peer is not already connected under another address, if duplicate peers are refused.
+        if self.is_duplicate_peer_rejection_enabled()
+            && self.connected_peers.read().values().any(|peer| peer.address() == address)
+        {
+            warn!("Dropping '{peer_addr}' (already connected as '{address}')");
+            return Some(DisconnectReason::PeerRefused);
+        }
+
         // TODO (howardwu): Remove this after Phase 2.
         if !self.is_dev
             && node_type.is_beacon()
@@ -353,3 +504,23 @@ impl<N: Network> Router<N> {
         None
     }
 }
+
+/// Returns the contents of the given challenge request for tracing, with its nonce redacted.
+fn redact_challenge_request<N: Network>(request: &ChallengeRequest<N>) -> String {
+    let ChallengeRequest { version, listener_port, node_type, address, nonce: _, capabilities, user_agent } = request;
+    format!(
+        "ChallengeRequest {{ version: {version}, listener_port: {listener_port}, node_type: {node_type}, address: {address}, nonce: <redacted>, capabilities: {:#x}, user_agent: {user_agent:?} }}",
+        capabilities.bits()
+    )
+}
+
+/// Returns the contents of the given challenge response for tracing, with its signature redacted.
+fn redact_challenge_response<N: Network>(response: &ChallengeResponse<N>) -> String {
+    let header = &response.genesis_header;
+    let root = header.to_root().map(|root| root.to_string()).unwrap_or_else(|_| "<invalid>".to_string());
+    format!(
+        "ChallengeResponse {{ genesis_header: {{ network: {}, height: {}, root: {root} }}, signature: <redacted> }}",
+        header.network(),
+        header.height()
+    )
+}
@@ -0,0 +1,56 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fmt, io};
+
+/// The reason the most recent attempt to connect to a peer failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectError {
+    /// The peer did not accept the connection before the dial timed out.
+    DialTimeout,
+    /// The peer refused the connection.
+    Refused,
+    /// The connection was established, but the handshake with the peer failed.
+    HandshakeFailed(String),
+    /// The peer is banned.
+    Banned,
+    /// The attempt was dropped by the connection rules, e.g. as the peer is already connected.
+    Rejected(String),
+    /// The connection failed due to another I/O error.
+    Io(String),
+}
+
+impl From<&io::Error> for ConnectError {
+    /// Classifies an error of the dial, for a connection that did not reach the handshake.
+    fn from(error: &io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::TimedOut => Self::DialTimeout,
+            io::ErrorKind::ConnectionRefused => Self::Refused,
+            _ => Self::Io(error.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DialTimeout => write!(f, "the dial timed out"),
+            Self::Refused => write!(f, "the connection was refused"),
+            Self::HandshakeFailed(reason) => write!(f, "the handshake failed: {reason}"),
+            Self::Banned => write!(f, "the peer is banned"),
+            Self::Rejected(reason) => write!(f, "the attempt was rejected: {reason}"),
+            Self::Io(reason) => write!(f, "{reason}"),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// A consistent snapshot of the number of peers in each connection state.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    /// The number of peers that are shaking hands and are not yet connected.
+    pub connecting: usize,
+    /// The number of connected peers.
+    pub connected: usize,
+    /// The number of candidate peers.
+    pub candidate: usize,
+    /// The number of restricted peers.
+    pub restricted: usize,
+}
@@ -0,0 +1,56 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_messages::DisconnectReason;
+
+use std::{error, fmt, io};
+
+/// The cause of a failed handshake.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HandshakeError {
+    /// This node dropped the peer for the given reason, e.g. as it failed verification.
+    Dropped(DisconnectReason),
+    /// The peer disconnected during the handshake, giving the given reason.
+    Disconnected(DisconnectReason),
+    /// The peer broke the handshake protocol, or the connection failed, as described.
+    Failed(String),
+}
+
+impl From<HandshakeError> for io::Error {
+    fn from(error: HandshakeError) -> Self {
+        io::Error::new(io::ErrorKind::Other, error)
+    }
+}
+
+impl From<&io::Error> for HandshakeError {
+    /// Recovers the cause of a handshake that failed with the given error.
+    fn from(error: &io::Error) -> Self {
+        match error.get_ref().and_then(|inner| inner.downcast_ref::<Self>()) {
+            Some(handshake_error) => handshake_error.clone(),
+            None => Self::Failed(error.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for HandshakeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dropped(reason) => write!(f, "dropped the peer for reason: {reason:?}"),
+            Self::Disconnected(reason) => write!(f, "the peer disconnected: {reason:?}"),
+            Self::Failed(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl error::Error for HandshakeError {}
@@ -0,0 +1,46 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_messages::Message;
+use snarkvm::prelude::Network;
+
+use std::io;
+use tokio::sync::oneshot;
+
+/// A message held back for a peer under `InFlightPolicy::Backpressure`, along with the sender of its delivery result.
+pub(crate) type HeldMessage<N> = (Message<N>, oneshot::Sender<io::Result<()>>);
+
+/// The action taken when a peer exceeds its cap on in-flight messages.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InFlightPolicy {
+    /// Holds back further messages to the peer, and sends them in order as its in-flight messages are written out.
+    /// Beyond `InFlightLimit::MAXIMUM_HELD_MESSAGES` held messages, further messages are dropped.
+    Backpressure,
+    /// Disconnects from the peer with `DisconnectReason::ProtocolViolation`.
+    Disconnect,
+}
+
+/// A cap on the number of outbound messages queued for delivery to a single peer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InFlightLimit {
+    /// The maximum number of messages queued for delivery to a peer.
+    pub max_messages: usize,
+    /// The action taken when a peer exceeds the cap.
+    pub policy: InFlightPolicy,
+}
+
+impl InFlightLimit {
+    /// The maximum number of messages held back for a peer under `InFlightPolicy::Backpressure`.
+    pub const MAXIMUM_HELD_MESSAGES: usize = 1024;
+}
@@ -0,0 +1,24 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+
+/// A handler of the `Custom` messages of an application-level protocol, registered with the router under the
+/// ID of the protocol. Only peers that speak the protocol should be sent its messages, as the nodes that predate
+/// `Custom` messages are unable to decode them.
+pub trait MessageHandler: Send + Sync {
+    /// Handles the payload of a message received from the given peer, returning the payload of the response
+    /// to send back under the same protocol, if any. It is called as the message is processed, so it should not block.
+    fn handle(&self, peer_ip: SocketAddr, payload: &[u8]) -> Option<Vec<u8>>;
+}
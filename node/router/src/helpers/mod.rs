@@ -15,11 +15,62 @@
 mod cache;
 pub use cache::Cache;
 
+mod connect_error;
+pub use connect_error::*;
+
+mod connection_stats;
+pub use connection_stats::*;
+
+mod handshake_error;
+pub use handshake_error::*;
+
+mod in_flight_limit;
+pub use in_flight_limit::*;
+
+mod message_handler;
+pub use message_handler::*;
+
 mod peer;
 pub use peer::*;
 
+mod peer_book;
+pub use peer_book::*;
+
+mod peer_count;
+pub use peer_count::*;
+
+mod peer_info;
+pub use peer_info::*;
+
+mod probe;
+pub use probe::*;
+
+mod prometheus;
+pub(crate) use prometheus::*;
+
+mod reputation_summary;
+pub use reputation_summary::*;
+
 mod resolver;
 pub(crate) use resolver::*;
 
+mod router_error;
+pub use router_error::*;
+
+mod scheduler;
+pub use scheduler::*;
+
+mod subnet_limit;
+pub use subnet_limit::*;
+
 mod sync;
 pub use sync::*;
+
+mod throughput;
+pub use throughput::*;
+
+mod transport;
+pub use transport::*;
+
+mod traffic_stats;
+pub use traffic_stats::*;
@@ -12,10 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use snarkos_node_messages::{ChallengeRequest, NodeType};
+use snarkos_node_messages::{Capabilities, ChallengeRequest, NodeType};
+use snarkos_node_tcp::ConnectionSide;
 use snarkvm::prelude::{Address, Network};
 
-use std::{net::SocketAddr, time::Instant};
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// A metric by which connected peers can be ranked.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PeerMetric {
+    /// The reputation of the peer; a lower reputation is worse.
+    Reputation,
+    /// The latency of the peer; a higher latency is worse, and peers without a measured latency rank last.
+    Latency,
+    /// The age of the connection to the peer; a younger connection is worse, as it is less established.
+    ConnectionAge,
+}
 
 /// The state for each connected peer.
 #[derive(Clone, Debug)]
@@ -28,22 +43,52 @@ pub struct Peer<N: Network> {
     node_type: NodeType,
     /// The message version of the peer.
     version: u32,
+    /// The optional features the peer advertised during the handshake.
+    capabilities: Capabilities,
+    /// The user agent the peer advertised during the handshake, which is empty if it advertised none.
+    user_agent: String,
+    /// The connection side of the peer; `Initiator` if the peer dialed this node.
+    side: ConnectionSide,
     /// The timestamp of the first message received from the peer.
     first_seen: Instant,
     /// The timestamp of the last message received from this peer.
     last_seen: Instant,
+    /// The timestamp of the last message of any kind received from this peer after the handshake, if any.
+    last_message: Option<Instant>,
+    /// The reputation of the peer.
+    reputation: i32,
+    /// The latest measured latency of the peer, if any.
+    latency: Option<Duration>,
+    /// The timestamp of the oldest ping sent to the peer that it has not yet answered, if any.
+    ping_sent: Option<Instant>,
+    /// The time until which the peer is quarantined, if it was quarantined.
+    quarantined_until: Option<Instant>,
+    /// The number of messages of the peer dropped while it was quarantined.
+    throttled_messages: u64,
+    /// The number of messages to the peer dropped by backpressure, as too many messages were held back.
+    dropped_messages: u64,
 }
 
 impl<N: Network> Peer<N> {
     /// Initializes a new instance of `Peer`.
-    pub fn new(listening_ip: SocketAddr, challenge_request: &ChallengeRequest<N>) -> Self {
+    pub fn new(listening_ip: SocketAddr, challenge_request: &ChallengeRequest<N>, side: ConnectionSide) -> Self {
         Self {
             peer_ip: listening_ip,
             address: challenge_request.address,
             node_type: challenge_request.node_type,
             version: challenge_request.version,
+            capabilities: challenge_request.capabilities,
+            user_agent: challenge_request.user_agent.clone(),
+            side,
             first_seen: Instant::now(),
             last_seen: Instant::now(),
+            last_message: None,
+            reputation: 0,
+            latency: None,
+            ping_sent: None,
+            quarantined_until: None,
+            throttled_messages: 0,
+            dropped_messages: 0,
         }
     }
 
@@ -87,6 +132,21 @@ impl<N: Network> Peer<N> {
         self.version
     }
 
+    /// Returns the optional features the peer advertised during the handshake.
+    pub const fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Returns the user agent the peer advertised during the handshake, which is empty if it advertised none.
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Returns the connection side of the peer; `Initiator` if the peer dialed this node.
+    pub const fn side(&self) -> ConnectionSide {
+        self.side
+    }
+
     /// Returns the first seen timestamp of the peer.
     pub fn first_seen(&self) -> Instant {
         self.first_seen
@@ -96,6 +156,42 @@ impl<N: Network> Peer<N> {
     pub fn last_seen(&self) -> Instant {
         self.last_seen
     }
+
+    /// Returns the timestamp of the last message of any kind received from the peer, if any.
+    pub const fn last_message(&self) -> Option<Instant> {
+        self.last_message
+    }
+
+    /// Returns the reputation of the peer.
+    pub const fn reputation(&self) -> i32 {
+        self.reputation
+    }
+
+    /// Returns the latest measured latency of the peer, if any.
+    pub const fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
+
+    /// Returns `true` if the peer is quarantined, i.e. its messages are rate-limited and it is not counted
+    /// as a validator.
+    pub fn is_quarantined(&self) -> bool {
+        self.quarantined_until.map_or(false, |until| until > Instant::now())
+    }
+
+    /// Returns the time until which the peer is quarantined, if it was quarantined.
+    pub const fn quarantined_until(&self) -> Option<Instant> {
+        self.quarantined_until
+    }
+
+    /// Returns the number of messages of the peer dropped while it was quarantined.
+    pub const fn throttled_messages(&self) -> u64 {
+        self.throttled_messages
+    }
+
+    /// Returns the number of messages to the peer dropped by backpressure, as too many messages were held back.
+    pub const fn dropped_messages(&self) -> u64 {
+        self.dropped_messages
+    }
 }
 
 impl<N: Network> Peer<N> {
@@ -113,4 +209,58 @@ impl<N: Network> Peer<N> {
     pub fn set_last_seen(&mut self, last_seen: Instant) {
         self.last_seen = last_seen;
     }
+
+    /// Records that a message of any kind was received from the peer at the given time.
+    pub fn set_last_message(&mut self, last_message: Instant) {
+        self.last_message = Some(last_message);
+    }
+
+    /// Adjusts the reputation of the peer by the given delta.
+    pub fn adjust_reputation(&mut self, delta: i32) {
+        self.reputation = self.reputation.saturating_add(delta);
+    }
+
+    /// Updates the latest measured latency of the peer.
+    pub fn set_latency(&mut self, latency: Duration) {
+        self.latency = Some(latency);
+    }
+
+    /// Records that a ping was sent to the peer, unless an earlier ping is still unanswered.
+    pub fn record_ping_sent(&mut self, sent: Instant) {
+        self.ping_sent.get_or_insert(sent);
+    }
+
+    /// Records that the peer answered the outstanding ping, measuring its round-trip latency.
+    pub fn record_pong_received(&mut self) {
+        if let Some(sent) = self.ping_sent.take() {
+            self.latency = Some(sent.elapsed());
+        }
+    }
+
+    /// Quarantines the peer until the given time.
+    pub fn set_quarantined_until(&mut self, until: Instant) {
+        self.quarantined_until = Some(until);
+    }
+
+    /// Records a message of the peer that was dropped while it was quarantined.
+    pub fn record_throttled_message(&mut self) {
+        self.throttled_messages = self.throttled_messages.saturating_add(1);
+    }
+
+    /// Records a message to the peer that was dropped by backpressure, as too many messages were held back.
+    pub fn record_dropped_message(&mut self) {
+        self.dropped_messages = self.dropped_messages.saturating_add(1);
+    }
+
+    /// Carries over the long-lived state and the metrics of the given previous connection of this peer.
+    pub fn migrate_from(&mut self, previous: &Peer<N>) {
+        self.first_seen = self.first_seen.min(previous.first_seen);
+        self.last_seen = self.last_seen.max(previous.last_seen);
+        self.last_message = self.last_message.max(previous.last_message);
+        self.reputation = self.reputation.saturating_add(previous.reputation);
+        self.latency = self.latency.or(previous.latency);
+        self.quarantined_until = self.quarantined_until.max(previous.quarantined_until);
+        self.throttled_messages = self.throttled_messages.saturating_add(previous.throttled_messages);
+        self.dropped_messages = self.dropped_messages.saturating_add(previous.dropped_messages);
+    }
 }
@@ -0,0 +1,36 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_messages::NodeType;
+
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+/// A snapshot of the good peers of a router, persisted to seed reconnection after a restart.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerBook {
+    /// The peers, ordered from best to worst reputation.
+    pub peers: Vec<PeerBookEntry>,
+}
+
+/// A peer in the peer book.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerBookEntry {
+    /// The listening IP of the peer.
+    pub ip: SocketAddr,
+    /// The node type of the peer.
+    pub node_type: NodeType,
+    /// The reputation of the peer, at the time of the snapshot.
+    pub reputation: i32,
+}
@@ -0,0 +1,57 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use tokio::sync::mpsc;
+
+/// A notification that the number of connected peers crossed a watermark.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PeerCountEvent {
+    /// The number of connected peers dropped below the low watermark.
+    BelowLow { connected: usize },
+    /// The number of connected peers rose above the high watermark.
+    AboveHigh { connected: usize },
+}
+
+/// A registered watcher of the number of connected peers.
+pub(crate) struct PeerCountWatcher {
+    /// The low watermark; crossing below it emits `PeerCountEvent::BelowLow`.
+    low: Option<usize>,
+    /// The high watermark; crossing above it emits `PeerCountEvent::AboveHigh`.
+    high: Option<usize>,
+    /// The sender for the notifications.
+    sender: mpsc::UnboundedSender<PeerCountEvent>,
+}
+
+impl PeerCountWatcher {
+    /// Initializes a new watcher for the given watermarks.
+    pub(crate) fn new(low: Option<usize>, high: Option<usize>, sender: mpsc::UnboundedSender<PeerCountEvent>) -> Self {
+        Self { low, high, sender }
+    }
+
+    /// Notifies the watcher if the transition from `previous` to `current` crosses a watermark.
+    /// Returns `false` if the receiver was dropped, in which case the watcher should be removed.
+    pub(crate) fn notify(&self, previous: usize, current: usize) -> bool {
+        if let Some(low) = self.low {
+            if previous >= low && current < low {
+                return self.sender.send(PeerCountEvent::BelowLow { connected: current }).is_ok();
+            }
+        }
+        if let Some(high) = self.high {
+            if previous <= high && current > high {
+                return self.sender.send(PeerCountEvent::AboveHigh { connected: current }).is_ok();
+            }
+        }
+        !self.sender.is_closed()
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Peer;
+use snarkos_node_messages::NodeType;
+use snarkvm::prelude::Network;
+
+use serde::Serialize;
+use std::net::SocketAddr;
+
+/// The state of a connected peer, as reported to operators.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PeerInfo {
+    /// The IP address of the peer, with the port set to the listener port.
+    pub ip: SocketAddr,
+    /// The node type of the peer.
+    pub node_type: NodeType,
+    /// The message version of the peer.
+    pub version: u32,
+    /// The user agent the peer advertised during the handshake, which is empty if it advertised none.
+    pub user_agent: String,
+    /// The reputation of the peer.
+    pub reputation: i32,
+    /// The latest measured latency of the peer in milliseconds, if any.
+    pub latency_ms: Option<u64>,
+    /// Whether the peer is quarantined.
+    pub is_quarantined: bool,
+    /// The number of messages of the peer dropped while it was quarantined.
+    pub throttled_messages: u64,
+    /// The number of messages to the peer dropped by backpressure, as too many messages were held back.
+    pub dropped_messages: u64,
+}
+
+impl<N: Network> From<&Peer<N>> for PeerInfo {
+    fn from(peer: &Peer<N>) -> Self {
+        Self {
+            ip: peer.ip(),
+            node_type: peer.node_type(),
+            version: peer.version(),
+            user_agent: peer.user_agent().to_string(),
+            reputation: peer.reputation(),
+            latency_ms: peer.latency().map(|latency| latency.as_millis() as u64),
+            is_quarantined: peer.is_quarantined(),
+            throttled_messages: peer.throttled_messages(),
+            dropped_messages: peer.dropped_messages(),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+/// The outcome of probing a peer, which dials it and performs the handshake without keeping the connection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProbeResult {
+    /// The peer completed the handshake, after the given time since it was dialed.
+    Reachable { latency: Duration },
+    /// The peer could not be dialed, or the handshake failed or timed out, for the given reason.
+    Unreachable { reason: String },
+}
+
+impl ProbeResult {
+    /// Returns `true` if the peer completed the handshake.
+    pub const fn is_reachable(&self) -> bool {
+        matches!(self, Self::Reachable { .. })
+    }
+
+    /// Returns the time it took the peer to complete the handshake, if it did.
+    pub const fn latency(&self) -> Option<Duration> {
+        match self {
+            Self::Reachable { latency } => Some(*latency),
+            Self::Unreachable { .. } => None,
+        }
+    }
+}
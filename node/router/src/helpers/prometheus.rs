@@ -0,0 +1,66 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Write;
+
+/// A builder of metrics in the Prometheus text exposition format.
+#[derive(Default)]
+pub(crate) struct PrometheusText(String);
+
+impl PrometheusText {
+    /// Appends a metric family of the given type. Each sample is given as the suffix of its name and its labels
+    /// (e.g. `_sum`, or `{state="connected"}`), along with its value.
+    pub(crate) fn family(&mut self, name: &str, kind: &str, help: &str, samples: &[(String, f64)]) {
+        let _ = writeln!(self.0, "# HELP {name} {help}");
+        let _ = writeln!(self.0, "# TYPE {name} {kind}");
+        for (suffix, value) in samples {
+            let _ = writeln!(self.0, "{name}{suffix} {value}");
+        }
+    }
+
+    /// Returns the given labels in the exposition format, escaping their values.
+    pub(crate) fn labels(labels: &[(&str, &str)]) -> String {
+        let labels = labels
+            .iter()
+            .map(|(name, value)| {
+                let value = value.replace('\\', r"\\").replace('"', "\\\"").replace('\n', r"\n");
+                format!("{name}=\"{value}\"")
+            })
+            .collect::<Vec<_>>();
+        format!("{{{}}}", labels.join(","))
+    }
+
+    /// Returns the rendered metrics.
+    pub(crate) fn finish(self) -> String {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_text() {
+        let mut text = PrometheusText::default();
+        let labels = PrometheusText::labels(&[("reason", "a \"quoted\" \\ reason")]);
+        assert_eq!(labels, r#"{reason="a \"quoted\" \\ reason"}"#);
+
+        text.family("peers", "gauge", "The number of peers.", &[(labels, 2.0)]);
+        assert_eq!(
+            text.finish(),
+            "# HELP peers The number of peers.\n# TYPE peers gauge\npeers{reason=\"a \\\"quoted\\\" \\\\ reason\"} 2\n"
+        );
+    }
+}
@@ -0,0 +1,24 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// An aggregate of the reputations of the connected peers.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ReputationSummary {
+    /// The lowest reputation of a connected peer.
+    pub min: i32,
+    /// The highest reputation of a connected peer.
+    pub max: i32,
+    /// The mean reputation of the connected peers.
+    pub mean: f64,
+}
@@ -0,0 +1,46 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fmt, io, net::SocketAddr};
+
+/// An error of the router, in setting up its networking.
+#[derive(Debug)]
+pub enum RouterError {
+    /// The listening address is already in use, e.g. by another node.
+    AddressInUse(SocketAddr),
+    /// The listener could not be started due to another I/O error.
+    Io(io::Error),
+}
+
+impl fmt::Display for RouterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AddressInUse(addr) => write!(
+                f,
+                "the listening address '{addr}' is already in use - the port may be occupied by another node, \
+                 so stop it or choose another port"
+            ),
+            Self::Io(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for RouterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::AddressInUse(_) => None,
+            Self::Io(error) => Some(error),
+        }
+    }
+}
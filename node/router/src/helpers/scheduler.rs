@@ -0,0 +1,104 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use std::{
+    net::SocketAddr,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+use tokio::sync::Notify;
+
+/// The priority with which the messages of a peer are processed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PeerPriority {
+    /// Messages are processed after those of normal and high-priority peers.
+    Low = 0,
+    /// The default priority.
+    #[default]
+    Normal = 1,
+    /// Messages are processed before those of normal and low-priority peers.
+    High = 2,
+}
+
+/// Orders the processing of inbound messages by the priority of the peer that sent them.
+#[derive(Default)]
+pub struct Scheduler {
+    /// The map of peer IPs to their priority, for peers that are not of normal priority.
+    priorities: RwLock<IndexMap<SocketAddr, PeerPriority>>,
+    /// The number of messages being processed, for each priority.
+    in_flight: [AtomicUsize; 3],
+    /// The notification to waiting messages that no more messages of some priority are being processed.
+    drained: Notify,
+}
+
+/// A guard that marks a message as being processed, until it is dropped.
+pub struct ScheduleGuard<'a> {
+    counter: &'a AtomicUsize,
+    drained: &'a Notify,
+}
+
+impl Drop for ScheduleGuard<'_> {
+    fn drop(&mut self) {
+        // Wake the waiting messages once the last message of this priority is processed.
+        if self.counter.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.drained.notify_waiters();
+        }
+    }
+}
+
+impl Scheduler {
+    /// The maximum duration in milliseconds a message waits for higher-priority messages, to avoid starvation.
+    const MAXIMUM_DELAY_IN_MS: u64 = 1000;
+
+    /// Sets the priority of the given peer IP.
+    pub fn set_priority(&self, peer_ip: SocketAddr, priority: PeerPriority) {
+        match priority {
+            PeerPriority::Normal => self.priorities.write().remove(&peer_ip),
+            priority => self.priorities.write().insert(peer_ip, priority),
+        };
+    }
+
+    /// Returns the priority of the given peer IP.
+    pub fn priority(&self, peer_ip: &SocketAddr) -> PeerPriority {
+        self.priorities.read().get(peer_ip).copied().unwrap_or_default()
+    }
+
+    /// Returns the number of messages being processed with a higher priority than the given one.
+    fn num_in_flight_above(&self, priority: PeerPriority) -> usize {
+        self.in_flight[priority as usize + 1..].iter().map(|counter| counter.load(Ordering::SeqCst)).sum()
+    }
+
+    /// Waits until no higher-priority messages are being processed (or the maximum delay passes),
+    /// and returns a guard that marks the message from the given peer IP as being processed.
+    pub async fn schedule(&self, peer_ip: SocketAddr) -> ScheduleGuard<'_> {
+        let priority = self.priority(&peer_ip);
+        let (timer, maximum_delay) = (Instant::now(), Duration::from_millis(Self::MAXIMUM_DELAY_IN_MS));
+        loop {
+            // Register for the notification before checking, so that a message finishing in between is not missed.
+            let drained = self.drained.notified();
+            if self.num_in_flight_above(priority) == 0 {
+                break;
+            }
+            let remaining = maximum_delay.saturating_sub(timer.elapsed());
+            if tokio::time::timeout(remaining, drained).await.is_err() {
+                break;
+            }
+        }
+        let counter = &self.in_flight[priority as usize];
+        counter.fetch_add(1, Ordering::SeqCst);
+        ScheduleGuard { counter, drained: &self.drained }
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::IpAddr;
+
+/// A cap on the number of connected peers within a single subnet (CIDR block).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SubnetLimit {
+    /// The prefix length of the IPv4 subnets, e.g. `24` for a /24 block.
+    pub ipv4_prefix_len: u8,
+    /// The prefix length of the IPv6 subnets, e.g. `64` for a /64 block.
+    pub ipv6_prefix_len: u8,
+    /// The maximum number of connected peers permitted within a subnet.
+    pub max_peers: usize,
+}
+
+impl SubnetLimit {
+    /// Returns `true` if the given IPs are within the same subnet.
+    pub fn is_same_subnet(&self, a: IpAddr, b: IpAddr) -> bool {
+        match (a, b) {
+            (IpAddr::V4(a), IpAddr::V4(b)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.ipv4_prefix_len.min(32))).unwrap_or(0);
+                u32::from(a) & mask == u32::from(b) & mask
+            }
+            (IpAddr::V6(a), IpAddr::V6(b)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.ipv6_prefix_len.min(128))).unwrap_or(0);
+                u128::from(a) & mask == u128::from(b) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_same_subnet() {
+        let limit = SubnetLimit { ipv4_prefix_len: 24, ipv6_prefix_len: 64, max_peers: 5 };
+
+        let ip = |ip: &str| ip.parse::<IpAddr>().unwrap();
+        assert!(limit.is_same_subnet(ip("10.0.0.1"), ip("10.0.0.254")));
+        assert!(!limit.is_same_subnet(ip("10.0.0.1"), ip("10.0.1.1")));
+        assert!(limit.is_same_subnet(ip("2001:db8::1"), ip("2001:db8::ffff")));
+        assert!(!limit.is_same_subnet(ip("2001:db8::1"), ip("2001:db8:0:1::1")));
+        assert!(!limit.is_same_subnet(ip("10.0.0.1"), ip("::ffff:10.0.0.1")));
+
+        // Ensure a zero-length prefix covers every address.
+        let limit = SubnetLimit { ipv4_prefix_len: 0, ipv6_prefix_len: 0, max_peers: 5 };
+        assert!(limit.is_same_subnet(ip("10.0.0.1"), ip("192.168.0.1")));
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+
+/// The throughput of a connected peer over the latest sampling interval.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ThroughputSample {
+    /// The IP address of the peer, with the port set to the listener port.
+    pub peer_ip: SocketAddr,
+    /// The number of bytes received from the peer per second.
+    pub bytes_in_per_sec: f64,
+    /// The number of bytes sent to the peer per second.
+    pub bytes_out_per_sec: f64,
+}
+
+impl ThroughputSample {
+    /// Initializes the sample from the bytes received and sent over the given number of seconds.
+    pub(crate) fn new(peer_ip: SocketAddr, bytes_in: u64, bytes_out: u64, elapsed_secs: f64) -> Self {
+        // Guard against a zero interval, which would yield an infinite throughput.
+        let elapsed_secs = elapsed_secs.max(f64::EPSILON);
+        Self {
+            peer_ip,
+            bytes_in_per_sec: bytes_in as f64 / elapsed_secs,
+            bytes_out_per_sec: bytes_out as f64 / elapsed_secs,
+        }
+    }
+}
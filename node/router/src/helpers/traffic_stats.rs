@@ -0,0 +1,40 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_tcp::Stats;
+
+/// The aggregate traffic exchanged with a group of peers.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TrafficStats {
+    /// The number of messages sent.
+    pub msgs_sent: u64,
+    /// The number of bytes sent.
+    pub bytes_sent: u64,
+    /// The number of messages received.
+    pub msgs_received: u64,
+    /// The number of bytes received.
+    pub bytes_received: u64,
+}
+
+impl TrafficStats {
+    /// Adds the traffic recorded in the given peer stats.
+    pub fn add(&mut self, stats: &Stats) {
+        let (msgs_sent, bytes_sent) = stats.sent();
+        let (msgs_received, bytes_received) = stats.received();
+        self.msgs_sent = self.msgs_sent.saturating_add(msgs_sent);
+        self.bytes_sent = self.bytes_sent.saturating_add(bytes_sent);
+        self.msgs_received = self.msgs_received.saturating_add(msgs_received);
+        self.bytes_received = self.bytes_received.saturating_add(bytes_received);
+    }
+}
@@ -16,6 +16,7 @@ use crate::{Outbound, Peer};
 use snarkos_node_messages::{
     BeaconPropose,
     BlockRequest,
+    Custom,
     DataBlocks,
     Message,
     PeerResponse,
@@ -39,6 +40,8 @@ use tokio::task::spawn_blocking;
 pub trait Inbound<N: Network>: Reading + Outbound<N> {
     /// The maximum number of puzzle requests per interval.
     const MAXIMUM_PUZZLE_REQUESTS_PER_INTERVAL: usize = 5;
+    /// The maximum number of messages of a quarantined peer processed per 5 seconds.
+    const MAXIMUM_QUARANTINED_MESSAGES_PER_INTERVAL: usize = 10;
     /// The duration in seconds to sleep in between ping requests with a connected peer.
     const PING_SLEEP_IN_SECS: u64 = 9; // 9 seconds
 
@@ -50,12 +53,28 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
             None => bail!("Unable to resolve the (ambiguous) peer address '{peer_addr}'"),
         };
 
+        // Record the time of the message, regardless of how it is handled.
+        self.router().record_message_received(peer_ip);
+
         // Drop the peer, if they have sent more than 1000 messages in the last 5 seconds.
         let num_messages = self.router().cache.insert_inbound_message(peer_ip, 5);
         if num_messages >= 1000 {
             bail!("Dropping '{peer_ip}' for spamming messages (num_messages = {num_messages})")
         }
 
+        // Log the messages of a quarantined peer for observation, and drop those beyond its rate limit.
+        if self.router().is_quarantined(&peer_ip) {
+            if num_messages > Self::MAXIMUM_QUARANTINED_MESSAGES_PER_INTERVAL {
+                debug!("Throttled '{}' from quarantined peer '{peer_ip}'", message.name());
+                self.router().record_throttled_message(peer_ip);
+                return Ok(());
+            }
+            info!("Received '{}' from quarantined peer '{peer_ip}'", message.name());
+        }
+
+        // Wait for higher-priority peers to be processed first.
+        let _guard = self.router().schedule(peer_ip).await;
+
         trace!("Received '{}' from '{peer_ip}'", message.name());
 
         // This match statement handles the inbound message by deserializing the message,
@@ -162,11 +181,27 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     false => bail!("Peer '{peer_ip}' sent an invalid block response"),
                 }
             }
-            Message::ChallengeRequest(..) | Message::ChallengeResponse(..) => {
+            Message::ChallengeAck(..) | Message::ChallengeRequest(..) | Message::ChallengeResponse(..) => {
                 // Disconnect as the peer is not following the protocol.
                 bail!("Peer '{peer_ip}' is not following the protocol")
             }
+            Message::Custom(message) => {
+                // Dispatch the message to the handler of its protocol, and send back its response, if any.
+                match self.router().message_handler(message.protocol) {
+                    Some(handler) => {
+                        if let Some(response) = handler.handle(peer_ip, &message.payload) {
+                            let response = Custom { protocol: message.protocol, payload: response.into() };
+                            self.send(peer_ip, Message::Custom(response));
+                        }
+                    }
+                    None => {
+                        debug!("Ignoring a message of the unregistered protocol {} from '{peer_ip}'", message.protocol)
+                    }
+                }
+                Ok(())
+            }
             Message::Disconnect(message) => {
+                self.router().record_disconnect(&message.reason, false);
                 bail!("Disconnecting peer '{peer_ip}' for the following reason: {:?}", message.reason)
             }
             Message::PeerRequest(..) => match self.peer_request(peer_ip) {
@@ -181,10 +216,14 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 true => Ok(()),
                 false => bail!("Peer '{peer_ip}' sent an invalid ping"),
             },
-            Message::Pong(message) => match self.pong(peer_ip, message) {
-                true => Ok(()),
-                false => bail!("Peer '{peer_ip}' sent an invalid pong"),
-            },
+            Message::Pong(message) => {
+                // Measure the latency of the peer, from the ping it answered.
+                self.router().record_pong_received(peer_ip);
+                match self.pong(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid pong"),
+                }
+            }
             Message::PuzzleRequest(..) => {
                 // Insert the puzzle request for the peer, and fetch the recent frequency.
                 let frequency = self.router().cache.insert_inbound_puzzle_request(peer_ip);
@@ -296,6 +335,10 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
 
     /// Handles a `PeerRequest` message.
     fn peer_request(&self, peer_ip: SocketAddr) -> bool {
+        // If peer sharing is disabled, do not advertise the connected peers.
+        if !self.router().is_peer_sharing_enabled() {
+            return true;
+        }
         // Retrieve the connected peers.
         let peers = self.router().connected_peers();
         // Filter out bogon addresses.
@@ -307,6 +350,10 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
 
     /// Handles a `PeerResponse` message.
     fn peer_response(&self, _peer_ip: SocketAddr, peers: &[SocketAddr]) -> bool {
+        // If peer sharing is disabled, ignore the advertised peers.
+        if !self.router().is_peer_sharing_enabled() {
+            return true;
+        }
         // Filter out bogon addresses.
         let peers = peers.iter().copied().filter(|addr| !is_bogon_address(addr.ip())).collect::<Vec<_>>();
         // Adds the given peer IPs to the list of candidate peers.
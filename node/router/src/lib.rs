@@ -38,17 +38,30 @@ mod routing;
 pub use routing::*;
 
 use snarkos_account::Account;
-use snarkos_node_messages::NodeType;
-use snarkos_node_tcp::{Config, Tcp};
+use snarkos_node_messages::{Capabilities, ChallengeRequest, DisconnectReason, Message, MessageCodec, NodeType};
+use snarkos_node_tcp::{protocols::ProtocolName, Config, ConnectionSide, Tcp};
 use snarkvm::prelude::{Address, Network, PrivateKey, ViewKey};
 
 use anyhow::{bail, Result};
 #[cfg(not(feature = "test"))]
 use core::str::FromStr;
+use futures::stream::{self, StreamExt};
 use indexmap::{IndexMap, IndexSet};
 use parking_lot::{Mutex, RwLock};
-use std::{collections::HashSet, future::Future, net::SocketAddr, ops::Deref, sync::Arc, time::Instant};
-use tokio::task::JoinHandle;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    future::Future,
+    io,
+    net::{IpAddr, SocketAddr},
+    ops::Deref,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{sync::mpsc, task::JoinHandle};
 
 #[derive(Clone)]
 pub struct Router<N: Network>(Arc<InnerRouter<N>>);
@@ -74,6 +87,8 @@ pub struct InnerRouter<N: Network> {
     resolver: Resolver,
     /// The sync pool.
     sync: Sync<N>,
+    /// The scheduler of inbound messages, by peer priority.
+    scheduler: Scheduler,
     /// The set of trusted peers.
     trusted_peers: IndexSet<SocketAddr>,
     /// The map of connected peer IPs to their peer handlers.
@@ -87,6 +102,54 @@ pub struct InnerRouter<N: Network> {
     candidate_peers: RwLock<IndexSet<SocketAddr>>,
     /// The set of restricted peer IPs.
     restricted_peers: RwLock<IndexMap<SocketAddr, Instant>>,
+    /// The map of banned peer IPs to the time their ban expires.
+    banned_peers: RwLock<IndexMap<IpAddr, Instant>>,
+    /// The map of attempted peer IPs to the reason their most recent connection attempt failed.
+    connection_errors: RwLock<IndexMap<SocketAddr, ConnectError>>,
+    /// The traffic exchanged with disconnected peers over the node's lifetime, grouped by peer type.
+    lifetime_traffic: RwLock<IndexMap<NodeType, TrafficStats>>,
+    /// The number of disconnects sent to and received from peers, by direction and reason.
+    disconnect_totals: RwLock<IndexMap<(&'static str, String), u64>>,
+    /// The number of successful handshakes, along with their total duration.
+    handshake_durations: Mutex<(u64, Duration)>,
+    /// The time the last successful handshake completed, if any.
+    last_handshake: Mutex<Option<Instant>>,
+    /// The most recent failed handshakes, from the oldest, with the peer IP, the cause, and the time of the failure.
+    handshake_failures: Mutex<VecDeque<(SocketAddr, HandshakeError, Instant)>>,
+    /// The time and outcome of the recent connection attempts, from the oldest, within the success rate window.
+    connection_attempts: Mutex<VecDeque<(Instant, bool)>>,
+    /// The watchers of the number of connected peers.
+    peer_count_watchers: Mutex<Vec<PeerCountWatcher>>,
+    /// The cap on the number of connected peers within a single subnet, if any.
+    subnet_limit: RwLock<Option<SubnetLimit>>,
+    /// The cap on the number of outbound messages queued for a single peer, if any.
+    in_flight_limit: RwLock<Option<InFlightLimit>>,
+    /// The queues of the messages held back for each peer under `InFlightPolicy::Backpressure`.
+    held_messages: Mutex<HashMap<SocketAddr, mpsc::Sender<HeldMessage<N>>>>,
+    /// The minimum message version accepted from peers.
+    minimum_peer_version: AtomicU32,
+    /// The bits of the optional features this node advertises to its peers.
+    capabilities: AtomicU64,
+    /// The user agent this node advertises to its peers.
+    user_agent: RwLock<String>,
+    /// The boolean flag indicating whether the node is ready to accept inbound connections.
+    ready: AtomicBool,
+    /// The boolean flag indicating whether peer lists are advertised to and requested from peers.
+    peer_sharing: AtomicBool,
+    /// The boolean flag indicating whether inbound peers may be evicted to make room for priority outbound peers.
+    priority_dialing: AtomicBool,
+    /// The boolean flag indicating whether the redacted contents of handshake messages are logged at trace level.
+    handshake_tracing: AtomicBool,
+    /// The boolean flag indicating whether a peer that is already connected under another address is refused.
+    duplicate_peer_rejection: AtomicBool,
+    /// The boolean flag indicating whether a handshake must conclude with both sides authenticated to the other.
+    mutual_auth: AtomicBool,
+    /// The handlers of the `Custom` messages of application-level protocols, by protocol ID.
+    message_handlers: RwLock<IndexMap<u16, Arc<dyn MessageHandler>>>,
+    /// The timestamp of the last eviction made to make room for a priority outbound peer.
+    last_priority_eviction: Mutex<Option<Instant>>,
+    /// The handle of the idle-connection reaper, if it is running.
+    idle_reaper: Mutex<Option<JoinHandle<()>>>,
     /// The spawned handles.
     handles: Mutex<Vec<JoinHandle<()>>>,
     /// The boolean flag for the development mode.
@@ -94,17 +157,34 @@ pub struct InnerRouter<N: Network> {
 }
 
 impl<N: Network> Router<N> {
+    /// The delay in milliseconds between connection attempts to a bootstrap peer.
+    const BOOTSTRAP_RETRY_DELAY_IN_MS: u64 = 500;
+    /// The duration in seconds of the sliding window over which the connection success rate is computed.
+    const CONNECTION_SUCCESS_WINDOW_IN_SECS: u64 = 600;
+    /// The maximum number of connection attempts made to each bootstrap peer.
+    const MAXIMUM_BOOTSTRAP_ATTEMPTS: usize = 3;
+    /// The maximum number of bootstrap peers connected to concurrently.
+    const MAXIMUM_BOOTSTRAP_CONCURRENCY: usize = 8;
     /// The maximum number of candidate peers permitted to be stored in the node.
     const MAXIMUM_CANDIDATE_PEERS: usize = 10_000;
+    /// The maximum number of connection attempts retained within the success rate window.
+    const MAXIMUM_CONNECTION_ATTEMPTS: usize = 1024;
     /// The maximum number of connection failures permitted by an inbound connecting peer.
     const MAXIMUM_CONNECTION_FAILURES: usize = 5;
+    /// The maximum number of failed handshakes retained for diagnosis.
+    const MAXIMUM_HANDSHAKE_FAILURES: usize = 64;
+    /// The maximum number of throughput samples queued for a subscriber that has not yet received them.
+    const MAXIMUM_QUEUED_THROUGHPUT_SAMPLES: usize = 16;
+    /// The minimum duration in seconds between evictions made to make room for priority outbound peers.
+    const PRIORITY_EVICTION_COOLDOWN_IN_SECS: u64 = 60;
     /// The duration in seconds after which a connected peer is considered inactive or
     /// disconnected if no message has been received in the meantime.
     const RADIO_SILENCE_IN_SECS: u64 = 150; // 2.5 minutes
 }
 
 impl<N: Network> Router<N> {
-    /// Initializes a new `Router` instance.
+    /// Initializes a new `Router` instance. If the port of the node IP is unavailable, the listener fails with
+    /// `RouterError::AddressInUse`; port 0 listens on a port chosen by the OS.
     pub async fn new(
         node_ip: SocketAddr,
         node_type: NodeType,
@@ -123,11 +203,36 @@ impl<N: Network> Router<N> {
             cache: Default::default(),
             resolver: Default::default(),
             sync: Default::default(),
+            scheduler: Default::default(),
             trusted_peers: trusted_peers.iter().copied().collect(),
             connected_peers: Default::default(),
             connecting_peers: Default::default(),
             candidate_peers: Default::default(),
             restricted_peers: Default::default(),
+            banned_peers: Default::default(),
+            connection_errors: Default::default(),
+            lifetime_traffic: Default::default(),
+            disconnect_totals: Default::default(),
+            handshake_durations: Default::default(),
+            last_handshake: Default::default(),
+            handshake_failures: Default::default(),
+            connection_attempts: Default::default(),
+            peer_count_watchers: Default::default(),
+            subnet_limit: Default::default(),
+            in_flight_limit: Default::default(),
+            held_messages: Default::default(),
+            minimum_peer_version: AtomicU32::new(Message::<N>::VERSION),
+            capabilities: AtomicU64::new(Capabilities::NONE.bits()),
+            user_agent: RwLock::new(format!("snarkOS/{}", env!("CARGO_PKG_VERSION"))),
+            ready: AtomicBool::new(true),
+            peer_sharing: AtomicBool::new(true),
+            priority_dialing: AtomicBool::new(false),
+            handshake_tracing: AtomicBool::new(false),
+            duplicate_peer_rejection: AtomicBool::new(false),
+            mutual_auth: AtomicBool::new(false),
+            message_handlers: Default::default(),
+            last_priority_eviction: Default::default(),
+            idle_reaper: Default::default(),
             handles: Default::default(),
             is_dev,
         })))
@@ -138,26 +243,102 @@ impl<N: Network> Router<N> {
         // Return early if the attempt is against the protocol rules.
         if let Err(forbidden_message) = self.check_connection_attempt(peer_ip) {
             warn!("{forbidden_message}");
+            // Count the refusal as a failed attempt, unless the peer is already connected or connecting.
+            if !self.is_connected(&peer_ip) && !self.connecting_peers.lock().contains(&peer_ip) {
+                self.record_connection_attempt(false);
+            }
+            let connect_error = match self.is_banned(&peer_ip.ip()) {
+                true => ConnectError::Banned,
+                false => ConnectError::Rejected(forbidden_message.to_string()),
+            };
+            self.connection_errors.write().insert(peer_ip, connect_error);
             return None;
         }
 
         let router = self.clone();
         Some(tokio::spawn(async move {
+            // Clear the failure of any previous attempt, so that a handshake failure recorded from now on is this attempt's.
+            router.connection_errors.write().remove(&peer_ip);
             // Attempt to connect to the candidate peer.
             match router.tcp.connect(peer_ip).await {
                 // Remove the peer from the candidate peers.
-                Ok(()) => router.remove_candidate_peer(peer_ip),
+                Ok(()) => {
+                    router.record_connection_attempt(true);
+                    router.remove_candidate_peer(peer_ip)
+                }
                 // If the connection was not allowed, log the error.
                 Err(error) => {
+                    router.record_connection_attempt(false);
                     router.connecting_peers.lock().remove(&peer_ip);
+                    // Record the failure of the dial, unless the handshake already recorded its own failure.
+                    router.connection_errors.write().entry(peer_ip).or_insert_with(|| ConnectError::from(&error));
                     warn!("Unable to connect to '{peer_ip}' - {error}")
                 }
             }
         }))
     }
 
+    /// Attempts to connect to the given priority peer IP. If the node is at capacity and priority dialing is enabled,
+    /// the inbound peer with the lowest reputation is evicted to make room, at most once per cooldown period.
+    pub async fn connect_priority(&self, peer_ip: SocketAddr) -> Option<JoinHandle<()>> {
+        if self.is_priority_dialing_enabled() && self.number_of_connected_peers() >= self.max_connected_peers() {
+            if let Some(evicted_ip) = self.priority_eviction_candidate() {
+                info!("Evicting inbound peer '{evicted_ip}' to make room for priority peer '{peer_ip}'");
+                let _ = self.disconnect(evicted_ip).await;
+            }
+        }
+        self.connect(peer_ip)
+    }
+
+    /// Returns the untrusted inbound peer with the lowest reputation, if the eviction cooldown has elapsed.
+    fn priority_eviction_candidate(&self) -> Option<SocketAddr> {
+        let mut last_eviction = self.last_priority_eviction.lock();
+        // Ensure evictions are bounded, to avoid thrashing.
+        let cooldown = Duration::from_secs(Self::PRIORITY_EVICTION_COOLDOWN_IN_SECS);
+        if last_eviction.map_or(false, |timestamp| timestamp.elapsed() < cooldown) {
+            return None;
+        }
+        let candidate = self
+            .peers_sorted_by(PeerMetric::Reputation)
+            .into_iter()
+            .find(|peer| peer.side() == ConnectionSide::Initiator && !self.trusted_peers.contains(&peer.ip()))?;
+        *last_eviction = Some(Instant::now());
+        Some(candidate.ip())
+    }
+
+    /// Attempts to connect to the given bootstrap peers, retrying each failed peer a bounded number of times.
+    /// Failed bootstrap connections are logged, but are not fatal; the number of connected bootstrap peers is returned.
+    pub async fn connect_to_bootstrap_peers(&self, bootstrap_peers: &[SocketAddr]) -> usize {
+        let num_connected = stream::iter(bootstrap_peers.iter().copied())
+            .map(|peer_ip| async move {
+                for attempt in 1..=Self::MAXIMUM_BOOTSTRAP_ATTEMPTS {
+                    // Stop if the attempt is against the protocol rules, e.g. if the peer is already connected.
+                    let Some(handle) = self.connect(peer_ip) else { break };
+                    let _ = handle.await;
+                    if self.is_connected(&peer_ip) {
+                        return true;
+                    }
+                    debug!("Bootstrap connection attempt {attempt} to '{peer_ip}' failed");
+                    if attempt < Self::MAXIMUM_BOOTSTRAP_ATTEMPTS {
+                        tokio::time::sleep(Duration::from_millis(Self::BOOTSTRAP_RETRY_DELAY_IN_MS)).await;
+                    }
+                }
+                let is_connected = self.is_connected(&peer_ip);
+                if !is_connected {
+                    warn!("Unable to connect to bootstrap peer '{peer_ip}'");
+                }
+                is_connected
+            })
+            .buffer_unordered(Self::MAXIMUM_BOOTSTRAP_CONCURRENCY)
+            .filter(|is_connected| futures::future::ready(*is_connected))
+            .count()
+            .await;
+        info!("Connected to {num_connected} of {} bootstrap peers", bootstrap_peers.len());
+        num_connected
+    }
+
     /// Ensure we are allowed to connect to the given peer.
-    fn check_connection_attempt(&self, peer_ip: SocketAddr) -> Result<()> {
+    pub(crate) fn check_connection_attempt(&self, peer_ip: SocketAddr) -> Result<()> {
         // Ensure the peer IP is not this node.
         if self.is_local_ip(&peer_ip) {
             bail!("Dropping connection attempt to '{peer_ip}' (attempted to self-connect)")
@@ -174,6 +355,10 @@ impl<N: Network> Router<N> {
         if self.is_restricted(&peer_ip) {
             bail!("Dropping connection attempt to '{peer_ip}' (restricted)")
         }
+        // Ensure the peer is not banned.
+        if self.is_banned(&peer_ip.ip()) {
+            bail!("Dropping connection attempt to '{peer_ip}' (banned)")
+        }
         // Ensure the node is not already connecting to this peer.
         if !self.connecting_peers.lock().insert(peer_ip) {
             bail!("Dropping connection attempt to '{peer_ip}' (already shaking hands as the initiator)")
@@ -193,15 +378,262 @@ impl<N: Network> Router<N> {
         })
     }
 
-    /// Returns the IP address of this node.
-    pub fn local_ip(&self) -> SocketAddr {
-        self.tcp.listening_addr().expect("The TCP listener is not enabled")
+    /// Stops accepting new inbound connections, without dropping existing connections or outbound dialing.
+    pub fn pause_accepting(&self) {
+        self.tcp.pause_accepting();
+    }
+
+    /// Resumes accepting new inbound connections.
+    pub fn resume_accepting(&self) {
+        self.tcp.resume_accepting();
+    }
+
+    /// Registers a watcher that is notified when the number of connected peers drops below `low`
+    /// or rises above `high`. A notification is sent once per crossing of a watermark.
+    pub fn watch_peer_count(&self, low: Option<usize>, high: Option<usize>) -> mpsc::UnboundedReceiver<PeerCountEvent> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.peer_count_watchers.lock().push(PeerCountWatcher::new(low, high, sender));
+        receiver
+    }
+
+    /// Notifies the watchers of a change in the number of connected peers, removing any closed watchers.
+    fn notify_peer_count(&self, previous: usize, current: usize) {
+        if previous != current {
+            self.peer_count_watchers.lock().retain(|watcher| watcher.notify(previous, current));
+        }
+    }
+
+    /// Sets the cap on the number of connected peers within a single subnet, enforced at handshake.
+    /// Passing `None` removes the cap.
+    pub fn set_subnet_limit(&self, subnet_limit: Option<SubnetLimit>) {
+        *self.subnet_limit.write() = subnet_limit;
+    }
+
+    /// Returns `true` if connecting to the given IP would exceed the cap on connected peers within its subnet.
+    pub fn is_subnet_full(&self, ip: IpAddr) -> bool {
+        match *self.subnet_limit.read() {
+            Some(limit) => {
+                let num_peers =
+                    self.connected_peers.read().keys().filter(|peer_ip| limit.is_same_subnet(peer_ip.ip(), ip)).count();
+                num_peers >= limit.max_peers
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the cap on the number of outbound messages queued for a single peer, or removes it if `None`.
+    pub fn set_in_flight_limit(&self, in_flight_limit: Option<InFlightLimit>) {
+        *self.in_flight_limit.write() = in_flight_limit;
+    }
+
+    /// Returns the cap on the number of outbound messages queued for a single peer, if any.
+    pub fn in_flight_limit(&self) -> Option<InFlightLimit> {
+        *self.in_flight_limit.read()
+    }
+
+    /// Returns the queue of the messages held back for the given peer, initializing it with the given function
+    /// if the peer has none.
+    pub(crate) fn held_messages(
+        &self,
+        peer_ip: SocketAddr,
+        initialize: impl FnOnce() -> mpsc::Sender<HeldMessage<N>>,
+    ) -> mpsc::Sender<HeldMessage<N>> {
+        self.held_messages.lock().entry(peer_ip).or_insert_with(initialize).clone()
+    }
+
+    /// Returns the minimum message version accepted from peers.
+    pub fn minimum_peer_version(&self) -> u32 {
+        self.minimum_peer_version.load(Ordering::SeqCst)
+    }
+
+    /// Sets the minimum message version accepted from peers in the handshake.
+    /// Use `Outbound::enforce_minimum_peer_version` to also drop the connected peers that are now obsolete.
+    pub fn set_minimum_peer_version(&self, version: u32) {
+        self.minimum_peer_version.store(version, Ordering::SeqCst);
+    }
+
+    /// Sets whether the node is ready to accept inbound connections; while not ready,
+    /// inbound handshakes are refused with `DisconnectReason::NotReady`.
+    pub fn set_ready(&self, ready: bool) {
+        self.ready.store(ready, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the node is ready to accept inbound connections.
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::SeqCst)
+    }
+
+    /// Stops advertising and requesting peer lists, so the node only knows of the peers it is explicitly connected to.
+    pub fn disable_peer_sharing(&self) {
+        self.peer_sharing.store(false, Ordering::SeqCst);
+    }
+
+    /// Resumes advertising and requesting peer lists.
+    pub fn enable_peer_sharing(&self) {
+        self.peer_sharing.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the node advertises and requests peer lists.
+    pub fn is_peer_sharing_enabled(&self) -> bool {
+        self.peer_sharing.load(Ordering::SeqCst)
+    }
+
+    /// Allows inbound peers to be evicted to make room for priority outbound peers when at capacity.
+    pub fn enable_priority_dialing(&self) {
+        self.priority_dialing.store(true, Ordering::SeqCst);
+    }
+
+    /// Stops evicting inbound peers to make room for priority outbound peers.
+    pub fn disable_priority_dialing(&self) {
+        self.priority_dialing.store(false, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if inbound peers may be evicted to make room for priority outbound peers.
+    pub fn is_priority_dialing_enabled(&self) -> bool {
+        self.priority_dialing.load(Ordering::SeqCst)
+    }
+
+    /// Refuses a connection with `DisconnectReason::PeerRefused` if the peer's Aleo address is already connected,
+    /// so that a peer that dials the node again, or that is dialed again, under another address is linked only once.
+    pub fn enable_duplicate_peer_rejection(&self) {
+        self.duplicate_peer_rejection.store(true, Ordering::SeqCst);
     }
 
-    /// Returns `true` if the given IP is this node.
+    /// Accepts connections from peers that are already connected under another address.
+    pub fn disable_duplicate_peer_rejection(&self) {
+        self.duplicate_peer_rejection.store(false, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if peers that are already connected under another address are refused.
+    pub fn is_duplicate_peer_rejection_enabled(&self) -> bool {
+        self.duplicate_peer_rejection.load(Ordering::SeqCst)
+    }
+
+    /// Requires mutual authentication, refusing peers that do not require it with `DisconnectReason::InvalidHandshake`.
+    /// A connection is then only established once each side has verified the signature of the other, which the
+    /// connection responder acknowledges to the initiator, instead of the initiator presuming it.
+    pub fn enable_mutual_auth(&self) {
+        self.mutual_auth.store(true, Ordering::SeqCst);
+    }
+
+    /// Stops requiring mutual authentication.
+    pub fn disable_mutual_auth(&self) {
+        self.mutual_auth.store(false, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if mutual authentication is required.
+    pub fn is_mutual_auth_enabled(&self) -> bool {
+        self.mutual_auth.load(Ordering::SeqCst)
+    }
+
+    /// Logs the redacted contents of every handshake message exchanged at trace level, for debugging
+    /// handshake incompatibilities. Nonces and signatures are never logged.
+    pub fn enable_handshake_tracing(&self) {
+        self.handshake_tracing.store(true, Ordering::SeqCst);
+    }
+
+    /// Stops logging the contents of handshake messages.
+    pub fn disable_handshake_tracing(&self) {
+        self.handshake_tracing.store(false, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the redacted contents of handshake messages are logged at trace level.
+    pub fn is_handshake_tracing_enabled(&self) -> bool {
+        self.handshake_tracing.load(Ordering::SeqCst)
+    }
+
+    /// Returns the optional features this node advertises to its peers.
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities::from_bits(self.capabilities.load(Ordering::SeqCst))
+    }
+
+    /// Sets the optional features this node advertises to its peers, from the next handshake onwards.
+    pub fn set_capabilities(&self, capabilities: Capabilities) {
+        self.capabilities.store(capabilities.bits(), Ordering::SeqCst);
+    }
+
+    /// Returns the optional features the given connected peer advertised, or `None` if it is not connected.
+    pub fn peer_capabilities(&self, peer_ip: &SocketAddr) -> Option<Capabilities> {
+        self.connected_peers.read().get(peer_ip).map(|peer| peer.capabilities())
+    }
+
+    /// Returns the user agent this node advertises to its peers, which defaults to `snarkOS/<version>`.
+    pub fn user_agent(&self) -> String {
+        self.user_agent.read().clone()
+    }
+
+    /// Sets the user agent this node advertises to its peers, from the next handshake onwards.
+    pub fn set_user_agent(&self, user_agent: impl Into<String>) -> Result<()> {
+        let user_agent = user_agent.into();
+        if user_agent.len() > ChallengeRequest::<N>::MAXIMUM_USER_AGENT_LENGTH {
+            bail!(
+                "The user agent of {} bytes exceeds the limit of {} bytes",
+                user_agent.len(),
+                ChallengeRequest::<N>::MAXIMUM_USER_AGENT_LENGTH
+            )
+        }
+        if user_agent != ChallengeRequest::<N>::sanitize_user_agent(&user_agent) {
+            bail!("The user agent must not contain control characters")
+        }
+        *self.user_agent.write() = user_agent;
+        Ok(())
+    }
+
+    /// Returns the user agent the given connected peer advertised, or `None` if it is not connected.
+    pub fn peer_user_agent(&self, peer_ip: &SocketAddr) -> Option<String> {
+        self.connected_peers.read().get(peer_ip).map(|peer| peer.user_agent().to_string())
+    }
+
+    /// Returns the codec for the messages exchanged with the given peer after the handshake, which compresses
+    /// them if both this node and the peer advertised `Capabilities::COMPRESSION`. The handshake messages are
+    /// never compressed, as neither side knows whether the other supports compression until they are exchanged.
+    pub fn message_codec(&self, peer_addr: SocketAddr) -> MessageCodec<N> {
+        let is_compressed = self.capabilities().contains(Capabilities::COMPRESSION)
+            && self
+                .resolve_to_listener(&peer_addr)
+                .and_then(|peer_ip| self.peer_capabilities(&peer_ip))
+                .map_or(false, |capabilities| capabilities.contains(Capabilities::COMPRESSION));
+        match is_compressed {
+            true => MessageCodec::compressed(),
+            false => MessageCodec::default(),
+        }
+    }
+
+    /// Returns the names of the protocols enabled on this node, which, along with its capabilities,
+    /// describe how it treats a connection.
+    pub fn enabled_protocols(&self) -> Vec<ProtocolName> {
+        self.tcp.enabled_protocols()
+    }
+
+    /// Starts listening for inbound connections, and returns the listening address.
+    pub async fn bind_listener(&self) -> Result<SocketAddr, RouterError> {
+        match self.tcp.enable_listener().await {
+            Ok(listening_addr) => Ok(listening_addr),
+            Err(error) if error.kind() == io::ErrorKind::AddrInUse => {
+                // As the listener is not bound, report the configured address.
+                let config = self.tcp.config();
+                match (config.listener_ip, config.desired_listening_port) {
+                    (Some(ip), Some(port)) => Err(RouterError::AddressInUse(SocketAddr::new(ip, port))),
+                    _ => Err(RouterError::Io(error)),
+                }
+            }
+            Err(error) => Err(RouterError::Io(error)),
+        }
+    }
+
+    /// Returns the IP address of this node, or an error if its listener is not enabled yet.
+    pub fn local_ip(&self) -> io::Result<SocketAddr> {
+        self.tcp.listening_addr()
+    }
+
+    /// Returns `true` if the given IP is this node. No IP is this node before its listener is enabled.
     pub fn is_local_ip(&self, ip: &SocketAddr) -> bool {
-        *ip == self.local_ip()
-            || (ip.ip().is_unspecified() || ip.ip().is_loopback()) && ip.port() == self.local_ip().port()
+        match self.local_ip() {
+            Ok(local_ip) => {
+                *ip == local_ip || (ip.ip().is_unspecified() || ip.ip().is_loopback()) && ip.port() == local_ip.port()
+            }
+            Err(_) => false,
+        }
     }
 
     /// Returns the node type.
@@ -283,6 +715,11 @@ impl<N: Network> Router<N> {
             .unwrap_or(false)
     }
 
+    /// Returns `true` if the given IP is banned.
+    pub fn is_banned(&self, ip: &IpAddr) -> bool {
+        self.banned_peers.read().get(ip).map_or(false, |expiry| *expiry > Instant::now())
+    }
+
     /// Returns the maximum number of connected peers.
     pub fn max_connected_peers(&self) -> usize {
         self.tcp.config().max_connections as usize
@@ -300,7 +737,7 @@ impl<N: Network> Router<N> {
 
     /// Returns the number of connected validators.
     pub fn number_of_connected_validators(&self) -> usize {
-        self.connected_peers.read().values().filter(|peer| peer.is_validator()).count()
+        self.connected_peers.read().values().filter(|peer| peer.is_validator() && !peer.is_quarantined()).count()
     }
 
     /// Returns the number of connected provers.
@@ -323,6 +760,34 @@ impl<N: Network> Router<N> {
         self.restricted_peers.read().len()
     }
 
+    /// Returns a snapshot of the number of peers in each connection state, taken while holding all
+    /// of the peer locks, so that the counts are consistent with one another.
+    pub fn connection_stats(&self) -> ConnectionStats {
+        // Acquire the locks in a fixed order.
+        let connected_peers = self.connected_peers.read();
+        let connecting_peers = self.connecting_peers.lock();
+        let candidate_peers = self.candidate_peers.read();
+        let restricted_peers = self.restricted_peers.read();
+
+        ConnectionStats {
+            // A peer remains in the connecting set until its handshake concludes, so exclude connected peers.
+            connecting: connecting_peers.iter().filter(|peer_ip| !connected_peers.contains_key(*peer_ip)).count(),
+            connected: connected_peers.len(),
+            candidate: candidate_peers.len(),
+            restricted: restricted_peers.len(),
+        }
+    }
+
+    /// Returns the lowest, highest, and mean reputation of the connected peers, or `None` if there are none.
+    pub fn reputation_summary(&self) -> Option<ReputationSummary> {
+        let connected_peers = self.connected_peers.read();
+        let reputations = connected_peers.values().map(|peer| peer.reputation());
+        let min = reputations.clone().min()?;
+        let max = reputations.clone().max()?;
+        let mean = reputations.map(f64::from).sum::<f64>() / connected_peers.len() as f64;
+        Some(ReputationSummary { min, max, mean })
+    }
+
     /// Returns the connected peer given the peer IP, if it exists.
     pub fn get_connected_peer(&self, ip: &SocketAddr) -> Option<Peer<N>> {
         self.connected_peers.read().get(ip).cloned()
@@ -333,6 +798,12 @@ impl<N: Network> Router<N> {
         self.connected_peers.read().values().cloned().collect()
     }
 
+    /// Returns the connection side of the given connected peer; `ConnectionSide::Initiator` if the peer
+    /// dialed this node (inbound), and `ConnectionSide::Responder` if this node dialed the peer (outbound).
+    pub fn peer_direction(&self, peer_ip: &SocketAddr) -> Option<ConnectionSide> {
+        self.connected_peers.read().get(peer_ip).map(|peer| peer.side())
+    }
+
     /// Returns the list of connected peers.
     pub fn connected_peers(&self) -> Vec<SocketAddr> {
         self.connected_peers.read().keys().copied().collect()
@@ -345,7 +816,12 @@ impl<N: Network> Router<N> {
 
     /// Returns the list of connected validators.
     pub fn connected_validators(&self) -> Vec<SocketAddr> {
-        self.connected_peers.read().iter().filter(|(_, peer)| peer.is_validator()).map(|(ip, _)| *ip).collect()
+        self.connected_peers
+            .read()
+            .iter()
+            .filter(|(_, peer)| peer.is_validator() && !peer.is_quarantined())
+            .map(|(ip, _)| *ip)
+            .collect()
     }
 
     /// Returns the list of connected provers.
@@ -368,6 +844,23 @@ impl<N: Network> Router<N> {
         self.restricted_peers.read().keys().copied().collect()
     }
 
+    /// Returns the reason the most recent attempt to connect to the given peer IP failed, if it did.
+    /// The reason is cleared once a new attempt is made, and while that attempt is in progress.
+    pub fn last_connection_error(&self, peer_ip: &SocketAddr) -> Option<ConnectError> {
+        self.connection_errors.read().get(peer_ip).cloned()
+    }
+
+    /// Returns the list of banned peer IPs, along with the time their ban expires.
+    pub fn banned_peers(&self) -> Vec<(IpAddr, Instant)> {
+        let now = Instant::now();
+        self.banned_peers
+            .read()
+            .iter()
+            .filter(|(_, expiry)| **expiry > now)
+            .map(|(ip, expiry)| (*ip, *expiry))
+            .collect()
+    }
+
     /// Returns the list of trusted peers.
     pub fn trusted_peers(&self) -> &IndexSet<SocketAddr> {
         &self.trusted_peers
@@ -405,6 +898,358 @@ impl<N: Network> Router<N> {
         vec![]
     }
 
+    /// Sets the priority of the given peer IP; the messages of lower-priority peers are processed
+    /// after those of higher-priority peers during contention, without disconnecting them.
+    pub fn set_peer_priority(&self, peer_ip: SocketAddr, priority: PeerPriority) {
+        self.scheduler.set_priority(peer_ip, priority);
+    }
+
+    /// Returns the priority of the given peer IP.
+    pub fn peer_priority(&self, peer_ip: &SocketAddr) -> PeerPriority {
+        self.scheduler.priority(peer_ip)
+    }
+
+    /// Waits for the message from the given peer IP to be scheduled, according to the peer priority.
+    /// The message is considered to be processing until the returned guard is dropped.
+    pub async fn schedule(&self, peer_ip: SocketAddr) -> ScheduleGuard<'_> {
+        self.scheduler.schedule(peer_ip).await
+    }
+
+    /// Returns the total traffic exchanged over the node's lifetime, grouped by peer type.
+    pub fn traffic_by_node_type(&self) -> IndexMap<NodeType, TrafficStats> {
+        let mut traffic = self.lifetime_traffic.read().clone();
+        // Include the traffic exchanged with the currently-connected peers.
+        for (peer_ip, peer) in self.connected_peers.read().iter() {
+            if let Some(stats) = self.resolve_to_ambiguous(peer_ip).and_then(|addr| self.tcp.known_peers().get(addr)) {
+                traffic.entry(peer.node_type()).or_default().add(&stats);
+            }
+        }
+        traffic
+    }
+
+    /// Returns a channel that receives the throughput of each connected peer every `interval`, derived from the bytes
+    /// exchanged with it since the previous sample. A newly-connected peer is sampled from the interval after it was
+    /// first seen connected. Sampling stops once the receiver is dropped.
+    pub fn subscribe_throughput(&self, interval: Duration) -> mpsc::Receiver<Vec<ThroughputSample>> {
+        let (sender, receiver) = mpsc::channel(Self::MAXIMUM_QUEUED_THROUGHPUT_SAMPLES);
+        // The bytes received from and sent to each peer, as of the previous sample.
+        let mut previous = self.peer_byte_totals();
+        let mut last_sampled = Instant::now();
+        let router = self.clone();
+        self.spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let elapsed_secs = last_sampled.elapsed().as_secs_f64();
+                last_sampled = Instant::now();
+
+                // Derive the throughput of each peer from the bytes exchanged since the previous sample.
+                let current = router.peer_byte_totals();
+                let samples = current
+                    .iter()
+                    .filter_map(|(peer_ip, (bytes_in, bytes_out))| {
+                        let (previous_in, previous_out) = previous.get(peer_ip)?;
+                        Some(ThroughputSample::new(
+                            *peer_ip,
+                            bytes_in.saturating_sub(*previous_in),
+                            bytes_out.saturating_sub(*previous_out),
+                            elapsed_secs,
+                        ))
+                    })
+                    .collect();
+                previous = current;
+
+                // Stop sampling once the subscriber is gone.
+                if sender.send(samples).await.is_err() {
+                    break;
+                }
+            }
+        });
+        receiver
+    }
+
+    /// Returns the total bytes received from and sent to each connected peer.
+    fn peer_byte_totals(&self) -> IndexMap<SocketAddr, (u64, u64)> {
+        self.connected_peers()
+            .into_iter()
+            .filter_map(|peer_ip| {
+                let stats = self.resolve_to_ambiguous(&peer_ip).and_then(|addr| self.tcp.known_peers().get(addr))?;
+                Some((peer_ip, (stats.received().1, stats.sent().1)))
+            })
+            .collect()
+    }
+
+    /// Records a disconnect sent to or received from a peer.
+    pub(crate) fn record_disconnect(&self, reason: &DisconnectReason, is_sent: bool) {
+        // The reason is labelled by its name, without any of its fields.
+        let reason = format!("{reason:?}").split('(').next().unwrap_or_default().to_string();
+        let direction = if is_sent { "sent" } else { "received" };
+        *self.disconnect_totals.write().entry((direction, reason)).or_default() += 1;
+    }
+
+    /// Records the duration of a successful handshake, along with the time it completed.
+    pub(crate) fn record_handshake_duration(&self, duration: Duration) {
+        let mut handshake_durations = self.handshake_durations.lock();
+        handshake_durations.0 += 1;
+        handshake_durations.1 += duration;
+        *self.last_handshake.lock() = Some(Instant::now());
+    }
+
+    /// Records a failed handshake with the given peer, evicting the oldest failure if the record is full.
+    pub(crate) fn record_handshake_failure(&self, peer_ip: SocketAddr, error: HandshakeError) {
+        let mut handshake_failures = self.handshake_failures.lock();
+        if handshake_failures.len() == Self::MAXIMUM_HANDSHAKE_FAILURES {
+            handshake_failures.pop_front();
+        }
+        handshake_failures.push_back((peer_ip, error, Instant::now()));
+    }
+
+    /// Returns the most recent failed handshakes, from the oldest, with their causes and the time they failed.
+    /// A node that cannot connect to any peer is diagnosed by the causes of its failed handshakes.
+    pub fn recent_handshake_failures(&self) -> Vec<(SocketAddr, HandshakeError, Instant)> {
+        self.handshake_failures.lock().iter().cloned().collect()
+    }
+
+    /// Records the outcome of a connection attempt, evicting the attempts that fell out of the success rate window.
+    pub(crate) fn record_connection_attempt(&self, is_success: bool) {
+        let window = Duration::from_secs(Self::CONNECTION_SUCCESS_WINDOW_IN_SECS);
+        let mut connection_attempts = self.connection_attempts.lock();
+        while connection_attempts.front().map_or(false, |(time, _)| time.elapsed() > window)
+            || connection_attempts.len() >= Self::MAXIMUM_CONNECTION_ATTEMPTS
+        {
+            connection_attempts.pop_front();
+        }
+        connection_attempts.push_back((Instant::now(), is_success));
+    }
+
+    /// Returns the fraction of the connection attempts within the sliding window that completed the handshake,
+    /// or `None` if there were none. The attempts are those dialed by this node and those made by inbound peers.
+    /// The attempts this node refuses to dial, e.g. to a banned peer, to itself, or beyond its maximum number of
+    /// peers, count as failures. The inbound connections that the TCP stack refuses before the handshake, while
+    /// it is not accepting connections or is at its connection limit, are not counted.
+    /// A low rate indicates that the node cannot effectively connect to the network.
+    pub fn connection_success_rate(&self) -> Option<f64> {
+        let window = Duration::from_secs(Self::CONNECTION_SUCCESS_WINDOW_IN_SECS);
+        let connection_attempts = self.connection_attempts.lock();
+        let outcomes =
+            connection_attempts.iter().filter(|(time, _)| time.elapsed() <= window).map(|(_, is_success)| is_success);
+        let (successes, total) =
+            outcomes.fold((0, 0), |(successes, total), is_success| (successes + *is_success as u32, total + 1));
+        (total > 0).then_some(successes as f64 / total as f64)
+    }
+
+    /// Returns the time elapsed since any peer last completed a handshake, or `None` if none has yet.
+    /// A node without recent handshakes may be isolated from the network.
+    pub fn time_since_last_handshake(&self) -> Option<Duration> {
+        self.last_handshake.lock().map(|last_handshake| last_handshake.elapsed())
+    }
+
+    /// Returns the connection counts, disconnect totals, traffic counters, and handshake durations of the router,
+    /// in the Prometheus text exposition format.
+    pub fn prometheus_metrics(&self) -> String {
+        let mut text = PrometheusText::default();
+
+        let stats = self.connection_stats();
+        let peers = [
+            ("connecting", stats.connecting),
+            ("connected", stats.connected),
+            ("candidate", stats.candidate),
+            ("restricted", stats.restricted),
+        ]
+        .map(|(state, count)| (PrometheusText::labels(&[("state", state)]), count as f64));
+        text.family("snarkos_router_peers", "gauge", "The number of peers in each connection state.", &peers);
+
+        let disconnects = self
+            .disconnect_totals
+            .read()
+            .iter()
+            .map(|((direction, reason), count)| {
+                (PrometheusText::labels(&[("direction", *direction), ("reason", reason.as_str())]), *count as f64)
+            })
+            .collect::<Vec<_>>();
+        let help = "The number of disconnects sent to and received from peers, by reason.";
+        text.family("snarkos_router_disconnects_total", "counter", help, &disconnects);
+
+        let traffic = self.traffic_by_node_type();
+        let by_node_type = |f: fn(&TrafficStats) -> u64| {
+            let samples = traffic.iter().map(|(node_type, stats)| {
+                (PrometheusText::labels(&[("node_type", node_type.to_string().as_str())]), f(stats) as f64)
+            });
+            samples.collect::<Vec<_>>()
+        };
+        let bytes_sent = by_node_type(|stats| stats.bytes_sent);
+        text.family("snarkos_router_bytes_sent_total", "counter", "The number of bytes sent to peers.", &bytes_sent);
+        let bytes_received = by_node_type(|stats| stats.bytes_received);
+        let help = "The number of bytes received from peers.";
+        text.family("snarkos_router_bytes_received_total", "counter", help, &bytes_received);
+
+        let (count, total) = *self.handshake_durations.lock();
+        let durations = [("_sum".to_string(), total.as_secs_f64()), ("_count".to_string(), count as f64)];
+        let help = "The duration of successful handshakes, in seconds.";
+        text.family("snarkos_router_handshake_duration_seconds", "summary", help, &durations);
+
+        text.finish()
+    }
+
+    /// Returns the connected peers, ordered from worst to best by the given metric,
+    /// so that the peers to drop under pressure come first.
+    pub fn peers_sorted_by(&self, metric: PeerMetric) -> Vec<Peer<N>> {
+        let mut peers = self.get_connected_peers();
+        match metric {
+            PeerMetric::Reputation => peers.sort_by_key(|peer| peer.reputation()),
+            PeerMetric::Latency => {
+                peers.sort_by_key(|peer| (peer.latency().is_none(), core::cmp::Reverse(peer.latency())))
+            }
+            PeerMetric::ConnectionAge => peers.sort_by_key(|peer| core::cmp::Reverse(peer.first_seen())),
+        }
+        peers
+    }
+
+    /// Writes the connected peers without a negative reputation to the given path, best first,
+    /// so that they can be imported with [`Router::import_peer_book`] after a restart.
+    pub fn export_peer_book(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut peers = self.peers_sorted_by(PeerMetric::Reputation);
+        peers.retain(|peer| peer.reputation() >= 0);
+        peers.reverse();
+        let peers = peers
+            .iter()
+            .map(|peer| PeerBookEntry { ip: peer.ip(), node_type: peer.node_type(), reputation: peer.reputation() })
+            .collect();
+        std::fs::write(path, serde_json::to_vec_pretty(&PeerBook { peers })?)?;
+        Ok(())
+    }
+
+    /// Reads the peer book at the given path, and queues its peers for reconnection as candidate peers,
+    /// best first. Returns the number of peers that were queued.
+    pub fn import_peer_book(&self, path: impl AsRef<Path>) -> Result<usize> {
+        let mut peer_book: PeerBook = serde_json::from_slice(&std::fs::read(path)?)?;
+        peer_book.peers.sort_by_key(|entry| core::cmp::Reverse(entry.reputation));
+        let peer_ips = peer_book.peers.iter().map(|entry| entry.ip).collect::<Vec<_>>();
+        let num_candidates = self.number_of_candidate_peers();
+        self.insert_candidate_peers(&peer_ips);
+        Ok(self.number_of_candidate_peers().saturating_sub(num_candidates))
+    }
+
+    /// Migrates the state of the peer connected at `old_ip` to its connection at `new_ip`, e.g. after an IP change,
+    /// and disconnects the old connection. Returns `None` if either IP is not connected, or if the connections
+    /// do not belong to the same peer; otherwise, returns the handle of the disconnect.
+    pub fn replace_peer(&self, old_ip: SocketAddr, new_ip: SocketAddr) -> Option<JoinHandle<()>> {
+        {
+            let mut connected_peers = self.connected_peers.write();
+            let (old_peer, new_peer) = (connected_peers.get(&old_ip)?, connected_peers.get(&new_ip)?);
+            // Ensure the connections belong to the same peer.
+            let is_same_peer = old_peer.address() == new_peer.address() && old_peer.node_type() == new_peer.node_type();
+            if old_ip == new_ip || !is_same_peer {
+                warn!("Unable to replace '{old_ip}' with '{new_ip}' (not the same peer)");
+                return None;
+            }
+            let old_peer = old_peer.clone();
+            connected_peers.get_mut(&new_ip)?.migrate_from(&old_peer);
+        }
+        // Carry over the traffic exchanged with the old connection. Its stats are removed, so that the traffic
+        // is not counted twice once the old connection is dropped.
+        let known_peers = self.tcp.known_peers();
+        if let Some(new_stats) = self.resolve_to_ambiguous(&new_ip).and_then(|addr| known_peers.get(addr)) {
+            if let Some(old_stats) = self.resolve_to_ambiguous(&old_ip).and_then(|addr| known_peers.remove(addr)) {
+                new_stats.register_stats(&old_stats);
+            }
+        }
+        self.set_peer_priority(new_ip, self.peer_priority(&old_ip));
+        debug!("Replacing the connection to '{old_ip}' with '{new_ip}'");
+        Some(self.disconnect(old_ip))
+    }
+
+    /// Adjusts the reputation of the given connected peer by the given delta. Returns `false` if it is not connected.
+    pub fn adjust_peer_reputation(&self, peer_ip: SocketAddr, delta: i32) -> bool {
+        self.connected_peers.write().get_mut(&peer_ip).map(|peer| peer.adjust_reputation(delta)).is_some()
+    }
+
+    /// Records the measured latency of the given connected peer. Returns `false` if it is not connected.
+    pub fn set_peer_latency(&self, peer_ip: SocketAddr, latency: Duration) -> bool {
+        self.connected_peers.write().get_mut(&peer_ip).map(|peer| peer.set_latency(latency)).is_some()
+    }
+
+    /// Registers the handler of the `Custom` messages of the given protocol, returning the handler it replaced, if any.
+    pub fn register_message_handler(
+        &self,
+        protocol: u16,
+        handler: Arc<dyn MessageHandler>,
+    ) -> Option<Arc<dyn MessageHandler>> {
+        self.message_handlers.write().insert(protocol, handler)
+    }
+
+    /// Removes the handler of the `Custom` messages of the given protocol, returning it, if it was registered.
+    pub fn unregister_message_handler(&self, protocol: u16) -> Option<Arc<dyn MessageHandler>> {
+        self.message_handlers.write().shift_remove(&protocol)
+    }
+
+    /// Returns the handler of the `Custom` messages of the given protocol, if one is registered.
+    pub fn message_handler(&self, protocol: u16) -> Option<Arc<dyn MessageHandler>> {
+        self.message_handlers.read().get(&protocol).cloned()
+    }
+
+    /// Returns the round-trip latency of the given connected peer, as last measured by a ping and its pong.
+    pub fn peer_latency(&self, peer_ip: &SocketAddr) -> Option<Duration> {
+        self.connected_peers.read().get(peer_ip).and_then(|peer| peer.latency())
+    }
+
+    /// Returns the time the given connected peer last sent a message of any kind, if it sent one since connecting.
+    pub fn last_message_time(&self, peer_ip: &SocketAddr) -> Option<Instant> {
+        self.connected_peers.read().get(peer_ip).and_then(|peer| peer.last_message())
+    }
+
+    /// Records that a message was received from the given connected peer.
+    pub(crate) fn record_message_received(&self, peer_ip: SocketAddr) {
+        if let Some(peer) = self.connected_peers.write().get_mut(&peer_ip) {
+            peer.set_last_message(Instant::now());
+        }
+    }
+
+    /// Records that a ping was sent to the given connected peer.
+    pub(crate) fn record_ping_sent(&self, peer_ip: SocketAddr) {
+        if let Some(peer) = self.connected_peers.write().get_mut(&peer_ip) {
+            peer.record_ping_sent(Instant::now());
+        }
+    }
+
+    /// Records that a pong was received from the given connected peer.
+    pub(crate) fn record_pong_received(&self, peer_ip: SocketAddr) {
+        if let Some(peer) = self.connected_peers.write().get_mut(&peer_ip) {
+            peer.record_pong_received();
+        }
+    }
+
+    /// Quarantines the given connected peer for the given duration, as a step short of banning it. The peer stays
+    /// connected, but its messages are logged and heavily rate-limited, and it is not counted as a validator.
+    /// Returns `false` if it is not connected.
+    pub fn quarantine_peer(&self, peer_ip: SocketAddr, duration: Duration) -> bool {
+        let until = Instant::now() + duration;
+        self.connected_peers.write().get_mut(&peer_ip).map(|peer| peer.set_quarantined_until(until)).is_some()
+    }
+
+    /// Returns `true` if the given peer is connected and quarantined.
+    pub fn is_quarantined(&self, peer_ip: &SocketAddr) -> bool {
+        self.connected_peers.read().get(peer_ip).map_or(false, |peer| peer.is_quarantined())
+    }
+
+    /// Records a message of the given quarantined peer that was dropped by the rate limit.
+    pub(crate) fn record_throttled_message(&self, peer_ip: SocketAddr) {
+        if let Some(peer) = self.connected_peers.write().get_mut(&peer_ip) {
+            peer.record_throttled_message();
+        }
+    }
+
+    /// Records a message to the given peer that was dropped by backpressure, as too many messages were held back.
+    pub(crate) fn record_dropped_message(&self, peer_ip: SocketAddr) {
+        if let Some(peer) = self.connected_peers.write().get_mut(&peer_ip) {
+            peer.record_dropped_message();
+        }
+    }
+
+    /// Returns the state of the given connected peer, including its message counters, if it is connected.
+    pub fn peer_info(&self, peer_ip: &SocketAddr) -> Option<PeerInfo> {
+        self.connected_peers.read().get(peer_ip).map(PeerInfo::from)
+    }
+
     /// Returns the list of metrics for the connected peers.
     pub fn connected_metrics(&self) -> Vec<(SocketAddr, NodeType)> {
         self.connected_peers.read().iter().map(|(ip, peer)| (*ip, peer.node_type())).collect()
@@ -416,7 +1261,14 @@ impl<N: Network> Router<N> {
         // Adds a bidirectional map between the listener address and (ambiguous) peer address.
         self.resolver.insert_peer(peer_ip, peer_addr);
         // Add an entry for this `Peer` in the connected peers.
-        self.connected_peers.write().insert(peer_ip, peer);
+        let (previous, current) = {
+            let mut connected_peers = self.connected_peers.write();
+            let previous = connected_peers.len();
+            connected_peers.insert(peer_ip, peer);
+            (previous, connected_peers.len())
+        };
+        // Notify the watchers of the number of connected peers.
+        self.notify_peer_count(previous, current);
         // Remove this peer from the candidate peers, if it exists.
         self.candidate_peers.write().remove(&peer_ip);
         // Remove this peer from the restricted peers, if it exists.
@@ -443,6 +1295,26 @@ impl<N: Network> Router<N> {
         self.candidate_peers.write().extend(eligible_peers);
     }
 
+    /// Bans the given IP for the given duration, disconnecting from any connected peers on it.
+    pub fn ban_peer(&self, ip: IpAddr, duration: Duration) {
+        // Add the IP to the banned peers, and drop any expired bans.
+        {
+            let mut banned_peers = self.banned_peers.write();
+            let now = Instant::now();
+            banned_peers.retain(|_, expiry| *expiry > now);
+            banned_peers.insert(ip, now + duration);
+        }
+        // Disconnect from the connected peers on this IP.
+        for peer_ip in self.connected_peers().into_iter().filter(|peer_ip| peer_ip.ip() == ip) {
+            self.disconnect(peer_ip);
+        }
+    }
+
+    /// Removes the given IP from the banned peers.
+    pub fn unban_peer(&self, ip: &IpAddr) {
+        self.banned_peers.write().remove(ip);
+    }
+
     /// Inserts the given peer into the restricted peers.
     pub fn insert_restricted_peer(&self, peer_ip: SocketAddr) {
         // Remove this peer from the candidate peers, if it exists.
@@ -473,12 +1345,31 @@ impl<N: Network> Router<N> {
 
     /// Removes the connected peer and adds them to the candidate peers.
     pub fn remove_connected_peer(&self, peer_ip: SocketAddr) {
+        // Retain the traffic exchanged with this peer in the lifetime totals. The peer stats are removed,
+        // so that the traffic is not counted twice if the peer reconnects from the same address.
+        if let Some(node_type) = self.connected_peers.read().get(&peer_ip).map(|peer| peer.node_type()) {
+            let stats = self.resolve_to_ambiguous(&peer_ip).and_then(|addr| self.tcp.known_peers().remove(addr));
+            if let Some(stats) = stats {
+                self.lifetime_traffic.write().entry(node_type).or_default().add(&stats);
+            }
+        }
         // Removes the bidirectional map between the listener address and (ambiguous) peer address.
         self.resolver.remove_peer(&peer_ip);
         // Removes the peer from the sync pool.
         self.sync.remove_peer(&peer_ip);
+        // Resets the priority of the peer.
+        self.scheduler.set_priority(peer_ip, PeerPriority::Normal);
+        // Closes the queue of the messages held back for the peer.
+        self.held_messages.lock().remove(&peer_ip);
         // Remove this peer from the connected peers, if it exists.
-        self.connected_peers.write().remove(&peer_ip);
+        let (previous, current) = {
+            let mut connected_peers = self.connected_peers.write();
+            let previous = connected_peers.len();
+            connected_peers.remove(&peer_ip);
+            (previous, connected_peers.len())
+        };
+        // Notify the watchers of the number of connected peers.
+        self.notify_peer_count(previous, current);
         // Add the peer to the candidate peers.
         self.candidate_peers.write().insert(peer_ip);
     }
@@ -498,9 +1389,43 @@ impl<N: Network> Router<N> {
         self.handles.lock().push(tokio::spawn(future));
     }
 
+    /// Starts a background task that, every `interval`, disconnects from the connected peers that have not sent
+    /// a keepalive message within the `idle_threshold`. Restarts the task if it is already running.
+    pub fn start_idle_reaper(&self, interval: Duration, idle_threshold: Duration) {
+        let router = self.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                // Disconnect from the idle peers.
+                for peer in router.get_connected_peers() {
+                    // A peer is active if it sent a keepalive, or any other message, within the threshold.
+                    let last_active = peer.last_message().map_or(peer.last_seen(), |time| time.max(peer.last_seen()));
+                    if last_active.elapsed() > idle_threshold {
+                        let peer_ip = peer.ip();
+                        info!("Disconnecting from '{peer_ip}' (idle for more than {}ms)", idle_threshold.as_millis());
+                        router.disconnect(peer_ip);
+                    }
+                }
+            }
+        });
+        // Replace any previously running reaper.
+        if let Some(previous) = self.idle_reaper.lock().replace(handle) {
+            previous.abort();
+        }
+    }
+
+    /// Stops the idle-connection reaper, if it is running.
+    pub fn stop_idle_reaper(&self) {
+        if let Some(handle) = self.idle_reaper.lock().take() {
+            handle.abort();
+        }
+    }
+
     /// Shuts down the router.
     pub async fn shut_down(&self) {
         trace!("Shutting down the router...");
+        // Stop the idle-connection reaper.
+        self.stop_idle_reaper();
         // Abort the tasks.
         self.handles.lock().iter().for_each(|handle| handle.abort());
         // Close the listener.
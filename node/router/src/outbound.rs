@@ -12,14 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::Router;
-use snarkos_node_messages::{BlockLocators, Message, Ping};
+use crate::{HeldMessage, InFlightLimit, InFlightPolicy, Peer, Router};
+use snarkos_node_messages::{BlockLocators, DisconnectReason, Message, Ping};
 use snarkos_node_tcp::protocols::Writing;
 use snarkvm::prelude::Network;
 use std::io;
 
-use std::net::SocketAddr;
-use tokio::sync::oneshot;
+use std::{net::SocketAddr, sync::Arc};
+use tokio::sync::{mpsc, oneshot, Semaphore};
 
 pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
     /// Returns a reference to the router.
@@ -48,6 +48,18 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
                 return None;
             }
         };
+        // Ensure the peer has not exceeded its cap on in-flight messages, unless disconnecting from it.
+        let in_flight_limit = self.router().in_flight_limit();
+        if let Some(InFlightLimit { max_messages, policy: InFlightPolicy::Disconnect }) = in_flight_limit {
+            let num_queued = self.queued_messages(peer_addr).unwrap_or_default();
+            if num_queued >= max_messages && !matches!(message, Message::Disconnect(_)) {
+                warn!("Disconnecting from '{peer_ip}' ({num_queued} messages in flight)");
+                self.send(peer_ip, Message::Disconnect(DisconnectReason::ProtocolViolation.into()));
+                // Disconnect from this peer.
+                self.router().disconnect(peer_ip);
+                return None;
+            }
+        }
         // If the message type is a block request, add it to the cache.
         if let Message::BlockRequest(request) = message {
             self.router().cache.insert_outbound_block_request(peer_ip, request);
@@ -56,10 +68,24 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         if matches!(message, Message::PuzzleRequest(_)) {
             self.router().cache.increment_outbound_puzzle_requests(peer_ip);
         }
+        // If the message type is a ping, record when it was sent, to measure the latency once it is answered.
+        if matches!(message, Message::Ping(_)) {
+            self.router().record_ping_sent(peer_ip);
+        }
+        // If the message type is a disconnect, record its reason.
+        if let Message::Disconnect(disconnect) = &message {
+            self.router().record_disconnect(&disconnect.reason, true);
+        }
         // Retrieve the message name.
         let name = message.name();
-        // Send the message to the peer.
+        // Send the message to the peer, holding it back until an in-flight slot is free under backpressure.
+        // A disconnect is sent immediately, as the peer is being dropped.
         trace!("Sending '{name}' to '{peer_ip}'");
+        if let Some(InFlightLimit { max_messages, policy: InFlightPolicy::Backpressure }) = in_flight_limit {
+            if !matches!(message, Message::Disconnect(_)) {
+                return hold_message(self, peer_ip, peer_addr, message, max_messages);
+            }
+        }
         let result = self.unicast(peer_addr, message);
         // If the message was unable to be sent, disconnect.
         if let Err(e) = &result {
@@ -172,6 +198,35 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         }
     }
 
+    /// Disconnects from every connected peer that matches the given predicate, for the given reason.
+    /// Returns the number of peers that were disconnected.
+    fn disconnect_where<F: Fn(&Peer<N>) -> bool>(&self, predicate: F, reason: DisconnectReason) -> usize {
+        // Retrieve the connected peers that match the predicate.
+        let peer_ips = self
+            .router()
+            .get_connected_peers()
+            .into_iter()
+            .filter(|peer| predicate(peer))
+            .map(|peer| peer.ip())
+            .collect::<Vec<_>>();
+
+        // Proceed to send disconnect requests to these peers.
+        for peer_ip in &peer_ips {
+            info!("Disconnecting from '{peer_ip}' ({reason:?})");
+            self.send(*peer_ip, Message::Disconnect(reason.clone().into()));
+            // Disconnect from this peer.
+            self.router().disconnect(*peer_ip);
+        }
+        peer_ips.len()
+    }
+
+    /// Sets the minimum message version accepted from peers, and disconnects the connected peers
+    /// on an obsolete version, so they reconnect under the new policy. Returns the number of peers disconnected.
+    fn enforce_minimum_peer_version(&self, version: u32) -> usize {
+        self.router().set_minimum_peer_version(version);
+        self.disconnect_where(|peer| peer.version() < version, DisconnectReason::InvalidHandshake)
+    }
+
     /// Returns `true` if the message can be sent.
     fn can_send(&self, peer_ip: SocketAddr, message: &Message<N>) -> bool {
         // Ensure the peer is connected before sending.
@@ -200,3 +255,64 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         }
     }
 }
+
+/// Queues the given message behind the messages already held back for the peer, to be sent in order as in-flight
+/// slots free up. Returns `None` if too many messages are already held back for the peer, dropping the message.
+fn hold_message<N: Network, O: Outbound<N>>(
+    node: &O,
+    peer_ip: SocketAddr,
+    peer_addr: SocketAddr,
+    message: Message<N>,
+    max_messages: usize,
+) -> Option<oneshot::Receiver<io::Result<()>>> {
+    let held_messages = node.router().held_messages(peer_ip, || {
+        let (sender, receiver) = mpsc::channel(InFlightLimit::MAXIMUM_HELD_MESSAGES);
+        tokio::spawn(send_held_messages(node.clone(), peer_ip, peer_addr, receiver, max_messages));
+        sender
+    });
+    let (delivery_sender, delivery) = oneshot::channel();
+    match held_messages.try_send((message, delivery_sender)) {
+        Ok(()) => Some(delivery),
+        Err(error) => {
+            debug!("Dropping '{}' to '{peer_ip}' (too many messages held back)", error.into_inner().0.name());
+            node.router().record_dropped_message(peer_ip);
+            None
+        }
+    }
+}
+
+/// Sends the messages held back for the peer in order, each once fewer than `max_messages` are in flight.
+async fn send_held_messages<N: Network, O: Outbound<N>>(
+    node: O,
+    peer_ip: SocketAddr,
+    peer_addr: SocketAddr,
+    mut receiver: mpsc::Receiver<HeldMessage<N>>,
+    max_messages: usize,
+) {
+    let slots = Arc::new(Semaphore::new(max_messages));
+    while let Some((message, delivery_sender)) = receiver.recv().await {
+        // Wait for an in-flight slot, which is freed once the message is written out.
+        let slot = match slots.clone().acquire_owned().await {
+            Ok(slot) => slot,
+            Err(_) => break,
+        };
+        let name = message.name();
+        match node.unicast(peer_addr, message) {
+            Ok(delivery) => {
+                tokio::spawn(async move {
+                    if let Ok(result) = delivery.await {
+                        drop(slot);
+                        let _ = delivery_sender.send(result);
+                    }
+                });
+            }
+            // If the message was unable to be sent, disconnect.
+            Err(e) => {
+                warn!("Failed to send '{name}' to '{peer_ip}': {e}");
+                debug!("Disconnecting from '{peer_ip}' (unable to send)");
+                node.router().disconnect(peer_ip);
+                break;
+            }
+        }
+    }
+}
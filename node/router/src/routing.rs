@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{Heartbeat, Inbound, Outbound};
+use crate::{Heartbeat, Inbound, Outbound, RouterError};
 use snarkos_node_messages::Message;
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake, OnConnect},
@@ -27,7 +27,7 @@ pub trait Routing<N: Network>:
     P2P + Disconnect + OnConnect + Handshake + Inbound<N> + Outbound<N> + Heartbeat<N>
 {
     /// Initialize the routing.
-    async fn initialize_routing(&self) {
+    async fn initialize_routing(&self) -> Result<(), RouterError> {
         // Enable the TCP protocols.
         self.enable_handshake().await;
         self.enable_reading().await;
@@ -35,18 +35,20 @@ pub trait Routing<N: Network>:
         self.enable_disconnect().await;
         self.enable_on_connect().await;
         // Enable the TCP listener. Note: This must be called after the above protocols.
-        self.enable_listener().await;
+        self.enable_listener().await?;
         // Initialize the heartbeat.
         self.initialize_heartbeat();
         // Initialize the report.
         #[cfg(not(feature = "test"))]
         self.initialize_report();
+        Ok(())
     }
 
     // Start listening for inbound connections.
-    async fn enable_listener(&self) {
-        let listening_addr = self.tcp().enable_listener().await.expect("Failed to enable the TCP listener");
+    async fn enable_listener(&self) -> Result<(), RouterError> {
+        let listening_addr = self.router().bind_listener().await?;
         self.router().sync.set_local_ip(listening_addr);
+        Ok(())
     }
 
     /// Initialize a new instance of the heartbeat.
@@ -0,0 +1,115 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_messages::{Message, PeerRequest};
+use snarkos_node_router::Outbound;
+use snarkos_node_tcp::{
+    protocols::{Handshake, Reading, Writing},
+    P2P,
+};
+
+use core::time::Duration;
+use std::net::{IpAddr, Ipv4Addr};
+
+#[tokio::test]
+async fn test_banned_peers_expire() {
+    // Create a router.
+    let node0 = validator(0, 1).await;
+    node0.tcp().enable_listener().await.unwrap();
+    assert!(node0.banned_peers().is_empty());
+
+    // Ban a peer briefly.
+    let banned_ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    node0.ban_peer(banned_ip, Duration::from_millis(200));
+
+    // Ensure the ban took effect.
+    assert!(node0.is_banned(&banned_ip));
+    let banned_peers = node0.banned_peers();
+    assert_eq!(banned_peers.len(), 1);
+    assert_eq!(banned_peers[0].0, banned_ip);
+
+    // Sleep past the ban expiry.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    // Ensure the ban has expired.
+    assert!(!node0.is_banned(&banned_ip));
+    assert!(node0.banned_peers().is_empty());
+}
+
+#[tokio::test]
+async fn test_banned_peer_connection_attempt() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Ban node1, and ensure node0 does not attempt to connect to it.
+    node0.ban_peer(node1.local_ip().unwrap().ip(), Duration::from_secs(60));
+    assert!(node0.connect(node1.local_ip().unwrap()).is_none());
+
+    // Unban node1, and ensure node0 is able to connect to it.
+    node0.unban_peer(&node1.local_ip().unwrap().ip());
+    assert!(node0.connect(node1.local_ip().unwrap()).is_some());
+}
+
+#[tokio::test]
+async fn test_quarantined_peer() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = validator(0, 1).await;
+
+    // Enable the handshake, reading, and writing protocols.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    assert_eq!(node0.number_of_connected_validators(), 1);
+
+    // Quarantine node1, and ensure it is no longer counted as a validator.
+    let peer_ip = node1.local_ip().unwrap();
+    assert!(node0.quarantine_peer(peer_ip, Duration::from_secs(60)));
+    assert!(node0.is_quarantined(&peer_ip));
+    assert!(node0.get_connected_peer(&peer_ip).unwrap().is_quarantined());
+    assert_eq!(node0.number_of_connected_validators(), 0);
+    assert!(node0.connected_validators().is_empty());
+
+    // Send more messages from node1 than a quarantined peer is permitted.
+    for _ in 0..20 {
+        node1.send(node0.local_ip().unwrap(), Message::PeerRequest(PeerRequest));
+    }
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Ensure the messages beyond the rate limit were dropped, while node1 stays connected.
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert_eq!(node0.get_connected_peer(&peer_ip).unwrap().throttled_messages(), 10);
+
+    // Ensure a disconnected peer can not be quarantined.
+    assert!(!node0.quarantine_peer("127.0.0.1:1".parse().unwrap(), Duration::from_secs(60)));
+}
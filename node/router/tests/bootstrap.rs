@@ -0,0 +1,48 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_tcp::{protocols::Handshake, P2P};
+
+use std::net::SocketAddr;
+
+#[tokio::test]
+async fn test_connect_to_bootstrap_peers() {
+    // Create 2 routers.
+    let node0 = validator(0, 2).await;
+    let node1 = client(0, 2).await;
+
+    // Enable handshake protocol.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Connect node1 to a bootstrap list containing node0 and an unreachable peer.
+    let unreachable = SocketAddr::from(([127, 0, 0, 1], 1));
+    let num_connected = node1.connect_to_bootstrap_peers(&[node0.local_ip().unwrap(), unreachable]).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    // Ensure the reachable bootstrap peer was connected, and the unreachable one was not fatal.
+    assert_eq!(num_connected, 1);
+    assert!(node1.is_connected(&node0.local_ip().unwrap()));
+    assert!(!node1.is_connected(&unreachable));
+    assert_eq!(node1.number_of_connected_peers(), 1);
+}
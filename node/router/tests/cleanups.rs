@@ -67,7 +67,7 @@ async fn test_connection_cleanups() {
     // Connect and disconnect in a loop.
     for i in 0..NUM_CONNECTIONS {
         // Connect one of the nodes to the other one.
-        nodes[1].connect(nodes[0].local_ip());
+        nodes[1].connect(nodes[0].local_ip().unwrap());
 
         // Wait until the connection is complete.
         let tcp0 = nodes[0].tcp().clone();
@@ -76,8 +76,8 @@ async fn test_connection_cleanups() {
 
         // Since the connectee doesn't read from the connector, it can't tell that the connector disconnected
         // from it, so it needs to disconnect from it manually.
-        nodes[0].disconnect(nodes[1].local_ip());
-        nodes[1].disconnect(nodes[0].local_ip());
+        nodes[0].disconnect(nodes[1].local_ip().unwrap());
+        nodes[1].disconnect(nodes[0].local_ip().unwrap());
 
         // Wait until the disconnect is complete.
         let tcp0 = nodes[0].tcp().clone();
@@ -20,11 +20,16 @@ use std::{
     env,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     str::FromStr,
+    time::Duration,
 };
 
 use snarkos_account::Account;
 use snarkos_node_messages::NodeType;
 use snarkos_node_router::Router;
+use snarkos_node_tcp::{
+    protocols::{Disconnect, Handshake, Reading, Writing},
+    P2P,
+};
 use snarkvm::prelude::{block::Block, FromBytes, Network, Testnet3 as CurrentNetwork};
 
 /// A helper macro to print the TCP listening address, along with the connected and connecting peers.
@@ -33,7 +38,7 @@ macro_rules! print_tcp {
     ($node:expr) => {
         println!(
             "{}: Active - {:?}, Pending - {:?}",
-            $node.local_ip(),
+            $node.local_ip().unwrap(),
             $node.tcp().connected_addrs(),
             $node.tcp().connecting_addrs()
         );
@@ -69,6 +74,12 @@ pub fn initialize_logger(level: u8) {
     let _ = tracing_subscriber::fmt().with_env_filter(filter).with_target(level == 3).try_init();
 }
 
+/// Initializes an in-memory transport, as a pair of connected streams, to shake hands without opening a TCP connection.
+#[allow(dead_code)]
+pub fn memory_transport() -> (tokio::io::DuplexStream, tokio::io::DuplexStream) {
+    tokio::io::duplex(64 * 1024)
+}
+
 /// Initializes a beacon router. Setting the `listening_port = 0` will result in a random port being assigned.
 #[allow(dead_code)]
 pub async fn beacon(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNetwork> {
@@ -132,3 +143,35 @@ pub async fn validator(listening_port: u16, max_peers: u16) -> TestRouter<Curren
     .expect("couldn't create validator router")
     .into()
 }
+
+/// Returns a validator and a client router, connected with the handshake, reading, writing, and disconnect protocols
+/// enabled. The given function configures the routers before they connect.
+#[allow(dead_code)]
+pub async fn connected_pair(
+    configure: impl FnOnce(&TestRouter<CurrentNetwork>, &TestRouter<CurrentNetwork>),
+) -> (TestRouter<CurrentNetwork>, TestRouter<CurrentNetwork>) {
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+    configure(&node0, &node1);
+
+    // Enable the handshake, reading, writing, and disconnect protocols.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert_eq!(node1.number_of_connected_peers(), 1);
+    (node0, node1)
+}
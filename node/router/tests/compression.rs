@@ -0,0 +1,75 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_messages::{Capabilities, Message, PeerRequest};
+use snarkos_node_router::Outbound;
+use snarkos_node_tcp::P2P;
+use snarkvm::prelude::Testnet3 as CurrentNetwork;
+
+use core::time::Duration;
+
+/// Returns 2 connected routers, advertising the given capabilities.
+async fn connected_pair_with(
+    capabilities0: Capabilities,
+    capabilities1: Capabilities,
+) -> (TestRouter<CurrentNetwork>, TestRouter<CurrentNetwork>) {
+    connected_pair(|node0, node1| {
+        node0.set_capabilities(capabilities0);
+        node1.set_capabilities(capabilities1);
+    })
+    .await
+}
+
+/// Returns `true` if the messages exchanged with the only peer of the given node are compressed.
+fn is_compressed(node: &TestRouter<CurrentNetwork>) -> bool {
+    node.message_codec(node.tcp().connected_addrs()[0]).is_compressed()
+}
+
+/// Sends a few messages in both directions, and ensures the nodes remain connected, as they could not be decoded
+/// otherwise.
+async fn exchange_messages(node0: &TestRouter<CurrentNetwork>, node1: &TestRouter<CurrentNetwork>) {
+    for _ in 0..4 {
+        assert!(node0.send(node1.local_ip().unwrap(), Message::PeerRequest(PeerRequest)).is_some());
+        assert!(node1.send(node0.local_ip().unwrap(), Message::PeerRequest(PeerRequest)).is_some());
+    }
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert_eq!(node1.number_of_connected_peers(), 1);
+}
+
+#[tokio::test]
+async fn test_compression_negotiated() {
+    let (node0, node1) =
+        connected_pair_with(Capabilities::COMPRESSION, Capabilities::COMPRESSION | Capabilities::REST).await;
+
+    // Ensure both nodes compress their messages, and can read those of the other.
+    assert!(is_compressed(&node0));
+    assert!(is_compressed(&node1));
+    exchange_messages(&node0, &node1).await;
+}
+
+#[tokio::test]
+async fn test_compression_not_supported_by_peer() {
+    let (node0, node1) = connected_pair_with(Capabilities::COMPRESSION, Capabilities::REST).await;
+
+    // Ensure neither node compresses its messages, as node1 does not support it.
+    assert!(!is_compressed(&node0));
+    assert!(!is_compressed(&node1));
+    exchange_messages(&node0, &node1).await;
+}
@@ -15,7 +15,12 @@
 mod common;
 use common::*;
 
-use snarkos_node_tcp::{protocols::Handshake, P2P};
+use snarkos_node_messages::Capabilities;
+use snarkos_node_router::{ConnectError, ProbeResult, RouterError, SubnetLimit};
+use snarkos_node_tcp::{
+    protocols::{Disconnect, Handshake, ProtocolName},
+    P2P,
+};
 
 use core::time::Duration;
 
@@ -33,7 +38,7 @@ async fn test_connect_without_handshake() {
 
     {
         // Connect node0 to node1.
-        node0.connect(node1.local_ip());
+        node0.connect(node1.local_ip().unwrap());
         // Sleep briefly.
         tokio::time::sleep(Duration::from_millis(100)).await;
 
@@ -47,7 +52,7 @@ async fn test_connect_without_handshake() {
     }
     {
         // Connect node0 from node1 again.
-        node0.connect(node1.local_ip());
+        node0.connect(node1.local_ip().unwrap());
         // Sleep briefly.
         tokio::time::sleep(Duration::from_millis(100)).await;
 
@@ -61,7 +66,7 @@ async fn test_connect_without_handshake() {
     }
     {
         // Connect node1 from node0.
-        node1.connect(node0.local_ip());
+        node1.connect(node0.local_ip().unwrap());
         // Sleep briefly.
         tokio::time::sleep(Duration::from_millis(100)).await;
 
@@ -93,7 +98,7 @@ async fn test_connect_with_handshake() {
 
     {
         // Connect node0 to node1.
-        node0.connect(node1.local_ip());
+        node0.connect(node1.local_ip().unwrap());
         // Sleep briefly.
         tokio::time::sleep(Duration::from_millis(200)).await;
 
@@ -112,7 +117,7 @@ async fn test_connect_with_handshake() {
     }
     {
         // Connect node0 to node1 again.
-        node0.connect(node1.local_ip());
+        node0.connect(node1.local_ip().unwrap());
         // Sleep briefly.
         tokio::time::sleep(Duration::from_millis(100)).await;
 
@@ -131,7 +136,7 @@ async fn test_connect_with_handshake() {
     }
     {
         // Connect node1 to node0.
-        node1.connect(node0.local_ip());
+        node1.connect(node0.local_ip().unwrap());
         // Sleep briefly.
         tokio::time::sleep(Duration::from_millis(100)).await;
 
@@ -165,9 +170,9 @@ async fn test_connect_simultaneously_with_handshake() {
 
     {
         // Connect node0 to node1.
-        node0.connect(node1.local_ip());
+        node0.connect(node1.local_ip().unwrap());
         // Connect node1 to node0.
-        node1.connect(node0.local_ip());
+        node1.connect(node0.local_ip().unwrap());
         // Sleep briefly.
         tokio::time::sleep(Duration::from_millis(100)).await;
 
@@ -185,3 +190,390 @@ async fn test_connect_simultaneously_with_handshake() {
         assert_eq!(node1.number_of_connected_peers(), 1);
     }
 }
+
+#[tokio::test]
+async fn test_connection_stats_during_churn() {
+    const NUM_PEERS: usize = 4;
+
+    // Create the routers.
+    let node0 = validator(0, NUM_PEERS as u16).await;
+    node0.enable_handshake().await;
+    node0.tcp().enable_listener().await.unwrap();
+
+    let mut peers = Vec::with_capacity(NUM_PEERS);
+    for _ in 0..NUM_PEERS {
+        let peer = client(0, 1).await;
+        peer.enable_handshake().await;
+        peer.tcp().enable_listener().await.unwrap();
+        peers.push(peer);
+    }
+
+    // Connect node0 to every peer.
+    for peer in &peers {
+        node0.connect(peer.local_ip().unwrap());
+    }
+
+    // Ensure every snapshot is internally consistent while the connections are being established.
+    for _ in 0..100 {
+        let stats = node0.connection_stats();
+        assert!(stats.connected + stats.connecting <= NUM_PEERS, "Inconsistent snapshot: {stats:?}");
+        tokio::time::sleep(Duration::from_millis(2)).await;
+    }
+
+    print_tcp!(node0);
+
+    // Ensure the final snapshot reflects the established connections.
+    let stats = node0.connection_stats();
+    assert_eq!(stats.connected, NUM_PEERS);
+    assert_eq!(stats.connecting, 0);
+    assert_eq!(stats.connected, node0.number_of_connected_peers());
+}
+
+#[tokio::test]
+async fn test_connect_when_not_ready() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+
+    // Enable handshake protocol.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Mark node0 as not ready.
+    node0.set_ready(false);
+    assert!(!node0.is_ready());
+
+    // Connect node1 to node0.
+    node1.connect(node0.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    // Ensure the connection was refused.
+    assert_eq!(node0.number_of_connected_peers(), 0);
+    assert_eq!(node1.number_of_connected_peers(), 0);
+    assert_eq!(node0.tcp().num_connected(), 0);
+    // Ensure the failed handshake was recorded.
+    assert!(matches!(node1.last_connection_error(&node0.local_ip().unwrap()), Some(ConnectError::HandshakeFailed(_))));
+
+    // Mark node0 as ready.
+    node0.set_ready(true);
+    assert!(node0.is_ready());
+
+    // Connect node1 to node0.
+    node1.connect(node0.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    // Ensure the connection was accepted, and the previous failure was cleared.
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert_eq!(node1.number_of_connected_peers(), 1);
+    assert_eq!(node1.last_connection_error(&node0.local_ip().unwrap()), None);
+}
+
+#[tokio::test]
+async fn test_last_connection_error() {
+    // Create a router.
+    let node0 = validator(0, 1).await;
+    node0.enable_handshake().await;
+    node0.tcp().enable_listener().await.unwrap();
+
+    // Find a closed port.
+    let closed_ip = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    assert_eq!(node0.last_connection_error(&closed_ip), None);
+
+    // Connect node0 to the closed port.
+    node0.connect(closed_ip).unwrap().await.unwrap();
+
+    // Ensure the refused dial was recorded.
+    assert_eq!(node0.number_of_connected_peers(), 0);
+    assert_eq!(node0.last_connection_error(&closed_ip), Some(ConnectError::Refused));
+
+    // Ban the peer, and ensure the dropped attempt is recorded.
+    node0.ban_peer(closed_ip.ip(), Duration::from_secs(60));
+    assert!(node0.connect(closed_ip).is_none());
+    assert_eq!(node0.last_connection_error(&closed_ip), Some(ConnectError::Banned));
+}
+
+#[tokio::test]
+async fn test_connect_with_subnet_limit() {
+    // Create 3 routers.
+    let node0 = validator(0, 3).await;
+    let node1 = client(0, 1).await;
+    let node2 = client(0, 1).await;
+
+    // Cap node0 to a single connected peer per /24 block.
+    node0.set_subnet_limit(Some(SubnetLimit { ipv4_prefix_len: 24, ipv6_prefix_len: 64, max_peers: 1 }));
+
+    // Enable handshake protocol.
+    for node in [&node0, &node1, &node2] {
+        node.enable_handshake().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node1 to node0.
+    node1.connect(node0.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert!(node0.is_subnet_full(node2.local_ip().unwrap().ip()));
+
+    // Connect node2 to node0, from the same block.
+    node2.connect(node0.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+
+    // Ensure the cap applies to the second peer in the block.
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert!(node0.is_connected(&node1.local_ip().unwrap()));
+    assert!(!node0.is_connected(&node2.local_ip().unwrap()));
+    assert_eq!(node2.number_of_connected_peers(), 0);
+
+    // Remove the cap, and ensure node2 is able to connect.
+    node0.set_subnet_limit(None);
+    node2.connect(node0.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(node0.number_of_connected_peers(), 2);
+}
+
+#[tokio::test]
+async fn test_connect_priority_evicts_inbound_peer() {
+    // Create a router at capacity with 2 peers, and 4 peers to connect with it.
+    let node0 = validator(0, 2).await;
+    let node1 = client(0, 1).await;
+    let node2 = client(0, 1).await;
+    let node3 = validator(0, 1).await;
+    let node4 = validator(0, 1).await;
+
+    // Enable the handshake and disconnect protocols.
+    for node in [&node0, &node1, &node2, &node3, &node4] {
+        node.enable_handshake().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Fill the capacity of node0 with inbound peers.
+    node1.connect(node0.local_ip().unwrap());
+    node2.connect(node0.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(node0.number_of_connected_peers(), 2);
+
+    // Lower the reputation of node2, making it the first to be evicted.
+    assert!(node0.adjust_peer_reputation(node2.local_ip().unwrap(), -5));
+
+    // Ensure no inbound peer is evicted while priority dialing is disabled.
+    assert!(!node0.is_priority_dialing_enabled());
+    assert!(node0.connect_priority(node3.local_ip().unwrap()).await.is_none());
+    assert!(node0.is_connected(&node2.local_ip().unwrap()));
+
+    // Ensure the inbound peer with the lowest reputation is evicted for the priority peer.
+    node0.enable_priority_dialing();
+    node0.connect_priority(node3.local_ip().unwrap()).await.unwrap().await.unwrap();
+    assert!(node0.is_connected(&node1.local_ip().unwrap()));
+    assert!(!node0.is_connected(&node2.local_ip().unwrap()));
+    assert!(node0.is_connected(&node3.local_ip().unwrap()));
+
+    // Ensure another eviction is not made within the cooldown period.
+    assert!(node0.connect_priority(node4.local_ip().unwrap()).await.is_none());
+    assert!(node0.is_connected(&node1.local_ip().unwrap()));
+    assert_eq!(node0.number_of_connected_peers(), 2);
+}
+
+#[tokio::test]
+async fn test_connect_with_capabilities() {
+    // Create 2 routers with differing capabilities.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+    node0.set_capabilities(Capabilities::PUBLIC_FEES | Capabilities::REST);
+    node1.set_capabilities(Capabilities::REST);
+
+    // Enable the handshake protocol.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    // Ensure each node reads the capabilities advertised by the other.
+    let node1_capabilities = node0.peer_capabilities(&node1.local_ip().unwrap()).unwrap();
+    assert_eq!(node1_capabilities, Capabilities::REST);
+    assert!(!node1_capabilities.contains(Capabilities::PUBLIC_FEES));
+    let node0_capabilities = node1.peer_capabilities(&node0.local_ip().unwrap()).unwrap();
+    assert!(node0_capabilities.contains(Capabilities::PUBLIC_FEES | Capabilities::REST));
+
+    // Ensure the capabilities of a disconnected peer are unknown.
+    assert_eq!(node0.peer_capabilities(&"127.0.0.1:1".parse().unwrap()), None);
+}
+
+#[tokio::test]
+async fn test_connect_with_user_agent() {
+    // Create 2 routers, one of which advertises a custom user agent.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+    node0.set_user_agent("snarkOS/1.2.3 myorg").unwrap();
+    assert_eq!(node1.user_agent(), format!("snarkOS/{}", env!("CARGO_PKG_VERSION")));
+
+    // Ensure an oversized user agent is rejected.
+    assert!(node1.set_user_agent("a".repeat(257)).is_err());
+    assert!(node1.set_user_agent("snarkOS/1.2.3\nforged").is_err());
+
+    // Enable the handshake protocol.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    // Ensure each node reads the user agent advertised by the other.
+    assert_eq!(node1.peer_user_agent(&node0.local_ip().unwrap()), Some("snarkOS/1.2.3 myorg".to_string()));
+    assert_eq!(node0.peer_user_agent(&node1.local_ip().unwrap()), Some(node1.user_agent()));
+
+    // Ensure the user agent of a disconnected peer is unknown.
+    assert_eq!(node0.peer_user_agent(&"127.0.0.1:1".parse().unwrap()), None);
+}
+
+#[tokio::test]
+async fn test_connect_duplicate_peer() {
+    // Create 3 routers; node1 and node2 share the same account, and are thus the same peer.
+    let node0 = validator(0, 3).await;
+    let node1 = client(0, 1).await;
+    let node2 = client(0, 1).await;
+
+    // Enable handshake protocol.
+    for node in [&node0, &node1, &node2] {
+        node.enable_handshake().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Refuse peers that are already connected under another address.
+    assert!(!node0.is_duplicate_peer_rejection_enabled());
+    node0.enable_duplicate_peer_rejection();
+    assert!(node0.is_duplicate_peer_rejection_enabled());
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(node0.number_of_connected_peers(), 1);
+
+    // Connect node1 to node0 again, and node2 to node0 as the same peer under another address.
+    node1.connect(node0.local_ip().unwrap());
+    node2.connect(node0.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+    print_tcp!(node2);
+
+    // Ensure only the first connection persists.
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert_eq!(node0.connected_peers(), vec![node1.local_ip().unwrap()]);
+    assert_eq!(node0.tcp().num_connected(), 1);
+    assert_eq!(node2.number_of_connected_peers(), 0);
+}
+
+#[tokio::test]
+async fn test_enabled_protocols() {
+    // Create a router.
+    let node0 = validator(0, 1).await;
+    assert!(node0.enabled_protocols().is_empty());
+
+    // Enable the disconnect and handshake protocols.
+    node0.enable_disconnect().await;
+    node0.enable_handshake().await;
+
+    // Ensure the enabled protocols are listed, in the order they are applied to a connection.
+    assert_eq!(node0.enabled_protocols(), vec![ProtocolName::Handshake, ProtocolName::Disconnect]);
+}
+
+#[tokio::test]
+async fn test_probe() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+
+    // Enable the handshake protocol.
+    node1.enable_handshake().await;
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Probe node1, which completes the handshake.
+    let genesis_header = *sample_genesis_block().header();
+    let result = node0.probe(node1.local_ip().unwrap(), Duration::from_secs(5), genesis_header).await;
+    assert!(result.is_reachable(), "{result:?}");
+    assert!(result.latency().is_some());
+
+    // Ensure the probe did not connect node0 to node1.
+    assert_eq!(node0.number_of_connected_peers(), 0);
+    assert!(!node0.is_connected(&node1.local_ip().unwrap()));
+    assert_eq!(node0.tcp().num_connected(), 0);
+
+    // Probe an address with no listener, which refuses the connection.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let dead_addr = listener.local_addr().unwrap();
+    drop(listener);
+    let result = node0.probe(dead_addr, Duration::from_secs(5), genesis_header).await;
+    assert!(!result.is_reachable());
+    assert_eq!(result.latency(), None);
+    assert_eq!(node0.number_of_connected_peers(), 0);
+
+    // Ensure the probe refuses to dial this node itself.
+    let result = node0.probe(node0.local_ip().unwrap(), Duration::from_secs(5), genesis_header).await;
+    assert_eq!(result, ProbeResult::Unreachable {
+        reason: format!("Dropping connection attempt to '{}' (attempted to self-connect)", node0.local_ip().unwrap())
+    });
+
+    // Ensure the probe refuses to dial a banned peer, and dials it again once it is unbanned.
+    node0.ban_peer(node1.local_ip().unwrap().ip(), Duration::from_secs(60));
+    let result = node0.probe(node1.local_ip().unwrap(), Duration::from_secs(5), genesis_header).await;
+    assert_eq!(result, ProbeResult::Unreachable {
+        reason: format!("Dropping connection attempt to '{}' (banned)", node1.local_ip().unwrap())
+    });
+    node0.unban_peer(&node1.local_ip().unwrap().ip());
+    assert!(node0.probe(node1.local_ip().unwrap(), Duration::from_secs(5), genesis_header).await.is_reachable());
+}
+
+#[tokio::test]
+async fn test_listener_address_in_use() {
+    // Start listening on a random port.
+    let node0 = validator(0, 1).await;
+    let listening_addr = node0.bind_listener().await.unwrap();
+
+    // Ensure a router configured with the same port reports that the address is in use.
+    let node1 = client(listening_addr.port(), 1).await;
+    match node1.bind_listener().await {
+        Err(RouterError::AddressInUse(addr)) => assert_eq!(addr, listening_addr),
+        result => panic!("Unexpected result of binding the listener: {result:?}"),
+    }
+}
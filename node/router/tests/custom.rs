@@ -0,0 +1,95 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_messages::{Custom, Message};
+use snarkos_node_router::{MessageHandler, Outbound};
+use snarkos_node_tcp::{
+    protocols::{Disconnect, Handshake, Reading, Writing},
+    P2P,
+};
+
+use core::time::Duration;
+use parking_lot::Mutex;
+use std::{net::SocketAddr, sync::Arc};
+
+/// The ID of the protocol used in the tests.
+const PROTOCOL: u16 = 7;
+
+/// A handler that records the payloads it receives, and answers a `ping` with a `pong`.
+#[derive(Default)]
+struct PingPong {
+    received: Mutex<Vec<(SocketAddr, Vec<u8>)>>,
+}
+
+impl MessageHandler for PingPong {
+    fn handle(&self, peer_ip: SocketAddr, payload: &[u8]) -> Option<Vec<u8>> {
+        self.received.lock().push((peer_ip, payload.to_vec()));
+        (payload == b"ping").then(|| b"pong".to_vec())
+    }
+}
+
+#[tokio::test]
+async fn test_custom_message_handler() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+
+    // Enable the handshake, reading, writing, and disconnect protocols.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Register a handler of the protocol on both nodes.
+    let (handler0, handler1) = (Arc::new(PingPong::default()), Arc::new(PingPong::default()));
+    assert!(node0.register_message_handler(PROTOCOL, handler0.clone()).is_none());
+    assert!(node1.register_message_handler(PROTOCOL, handler1.clone()).is_none());
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert_eq!(node1.number_of_connected_peers(), 1);
+
+    // Send a ping from node0 to node1, along with a message of a protocol node1 does not handle.
+    let ping = Custom { protocol: PROTOCOL, payload: b"ping".to_vec().into() };
+    let unknown = Custom { protocol: PROTOCOL + 1, payload: b"ping".to_vec().into() };
+    assert!(node0.send(node1.local_ip().unwrap(), Message::Custom(ping)).is_some());
+    assert!(node0.send(node1.local_ip().unwrap(), Message::Custom(unknown)).is_some());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Ensure node1 handled the ping, and node0 handled the pong it sent back.
+    assert_eq!(*handler1.received.lock(), vec![(node0.local_ip().unwrap(), b"ping".to_vec())]);
+    assert_eq!(*handler0.received.lock(), vec![(node1.local_ip().unwrap(), b"pong".to_vec())]);
+
+    // Ensure the unhandled message did not disconnect the nodes.
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert_eq!(node1.number_of_connected_peers(), 1);
+
+    // Ensure a handler can be unregistered.
+    assert!(node1.unregister_message_handler(PROTOCOL).is_some());
+    assert!(node1.message_handler(PROTOCOL).is_none());
+}
@@ -15,7 +15,13 @@
 mod common;
 use common::*;
 
-use snarkos_node_tcp::{protocols::Handshake, P2P};
+use snarkos_node_messages::DisconnectReason;
+use snarkos_node_router::{Outbound, PeerCountEvent};
+use snarkos_node_tcp::{
+    protocols::{Disconnect, Handshake, Writing},
+    ConnectionSide,
+    P2P,
+};
 
 use core::time::Duration;
 
@@ -31,8 +37,12 @@ async fn test_disconnect_without_handshake() {
     node0.tcp().enable_listener().await.unwrap();
     node1.tcp().enable_listener().await.unwrap();
 
+    // Ensure the handshake protocol is not enabled.
+    assert!(!node0.is_handshake_enabled());
+    assert!(!node1.is_handshake_enabled());
+
     // Connect node0 to node1.
-    node0.connect(node1.local_ip());
+    node0.connect(node1.local_ip().unwrap());
     // Sleep briefly.
     tokio::time::sleep(Duration::from_millis(200)).await;
 
@@ -48,7 +58,7 @@ async fn test_disconnect_without_handshake() {
     // note: the lower-level disconnect call is used, as the higher-level
     // collection of connected peers is only altered during the handshake,
     // as well as the address resolver needed for the higher-level calls
-    node0.tcp().disconnect(node1.local_ip()).await;
+    node0.tcp().disconnect(node1.local_ip().unwrap()).await;
     // Sleep briefly.
     tokio::time::sleep(Duration::from_millis(100)).await;
 
@@ -77,8 +87,12 @@ async fn test_disconnect_with_handshake() {
     node0.tcp().enable_listener().await.unwrap();
     node1.tcp().enable_listener().await.unwrap();
 
+    // Ensure the handshake protocol is enabled.
+    assert!(node0.is_handshake_enabled());
+    assert!(node1.is_handshake_enabled());
+
     // Connect node0 to node1.
-    node0.connect(node1.local_ip());
+    node0.connect(node1.local_ip().unwrap());
     // Sleep briefly.
     tokio::time::sleep(Duration::from_millis(200)).await;
 
@@ -95,8 +109,12 @@ async fn test_disconnect_with_handshake() {
     assert_eq!(node0.number_of_connected_peers(), 1);
     assert_eq!(node1.number_of_connected_peers(), 1);
 
+    // Check the connection directions; node0 dialed node1.
+    assert_eq!(node0.peer_direction(&node1.local_ip().unwrap()), Some(ConnectionSide::Responder));
+    assert_eq!(node1.peer_direction(&node0.local_ip().unwrap()), Some(ConnectionSide::Initiator));
+
     // Disconnect node0 from node1.
-    node0.disconnect(node1.local_ip());
+    node0.disconnect(node1.local_ip().unwrap());
     // Sleep briefly.
     tokio::time::sleep(Duration::from_millis(100)).await;
 
@@ -108,3 +126,179 @@ async fn test_disconnect_with_handshake() {
     assert_eq!(node1.tcp().num_connected(), 1); // Router 1 has no way of knowing that Router 0 disconnected.
     assert_eq!(node1.tcp().num_connecting(), 0);
 }
+
+#[tokio::test]
+async fn test_disconnect_where() {
+    // Create 3 routers.
+    let node0 = validator(0, 2).await;
+    let node1 = client(0, 1).await;
+    let node2 = prover(0, 1).await;
+
+    // Enable the handshake protocol.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+    node2.enable_handshake().await;
+    node0.enable_writing().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+    node2.tcp().enable_listener().await.unwrap();
+
+    // Connect node0 to node1 and node2.
+    node0.connect(node1.local_ip().unwrap());
+    node0.connect(node2.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+
+    assert_eq!(node0.tcp().num_connected(), 2);
+    assert_eq!(node0.number_of_connected_peers(), 2);
+
+    // Disconnect node0 from all client peers.
+    let num_disconnected = node0.disconnect_where(|peer| peer.is_client(), DisconnectReason::NoReasonGiven);
+    assert_eq!(num_disconnected, 1);
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    print_tcp!(node0);
+
+    // Ensure only the client peer was dropped.
+    assert_eq!(node0.tcp().num_connected(), 1);
+    assert!(node0.tcp().is_connected(node2.local_ip().unwrap()));
+    assert!(!node0.tcp().is_connected(node1.local_ip().unwrap()));
+}
+
+#[tokio::test]
+async fn test_idle_reaper() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+
+    // Enable handshake and disconnect protocols.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+    node0.enable_disconnect().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Ensure the protocols the reaper relies on are enabled.
+    assert!(node0.is_handshake_enabled());
+    assert!(node0.is_disconnect_enabled());
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(node0.tcp().num_connected(), 1);
+    assert_eq!(node0.number_of_connected_peers(), 1);
+
+    // Start the reaper; node1 never sends a keepalive, so it is considered idle.
+    node0.start_idle_reaper(Duration::from_millis(50), Duration::from_millis(100));
+    // Sleep past the idle threshold.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    print_tcp!(node0);
+
+    // Ensure the idle peer was reaped.
+    assert_eq!(node0.tcp().num_connected(), 0);
+    assert_eq!(node0.number_of_connected_peers(), 0);
+
+    // Stop the reaper.
+    node0.stop_idle_reaper();
+}
+
+#[tokio::test]
+async fn test_enforce_minimum_peer_version() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+
+    // Enable the handshake and writing protocols.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+    node0.enable_writing().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    let version = node0.get_connected_peers()[0].version();
+
+    // Ensure a policy the connected peer satisfies does not drop it.
+    assert_eq!(node0.enforce_minimum_peer_version(version), 0);
+    assert_eq!(node0.number_of_connected_peers(), 1);
+
+    // Raise the minimum version, so the connected peer is now obsolete.
+    assert_eq!(node0.enforce_minimum_peer_version(version + 1), 1);
+    assert_eq!(node0.minimum_peer_version(), version + 1);
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    print_tcp!(node0);
+
+    // Ensure the obsolete peer was dropped.
+    assert_eq!(node0.tcp().num_connected(), 0);
+
+    // Ensure the obsolete peer is refused under the new policy.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(node0.tcp().num_connected(), 0);
+    assert_eq!(node0.number_of_connected_peers(), 0);
+}
+
+#[tokio::test]
+async fn test_peer_count_watermarks() {
+    // Create 3 routers.
+    let node0 = validator(0, 2).await;
+    let node1 = client(0, 1).await;
+    let node2 = prover(0, 1).await;
+
+    // Notify when node0 drops below 2 connected peers, or rises above 1.
+    let mut events = node0.watch_peer_count(Some(2), Some(1));
+
+    // Enable the handshake and disconnect protocols.
+    for node in [&node0, &node1, &node2] {
+        node.enable_handshake().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to node1 and node2.
+    node0.connect(node1.local_ip().unwrap());
+    node0.connect(node2.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(node0.number_of_connected_peers(), 2);
+
+    // Ensure the upward crossing of the high watermark was notified.
+    assert_eq!(events.try_recv().unwrap(), PeerCountEvent::AboveHigh { connected: 2 });
+    assert!(events.try_recv().is_err());
+
+    // Disconnect node0 from node1 and node2.
+    node0.disconnect(node1.local_ip().unwrap()).await.unwrap();
+    node0.disconnect(node2.local_ip().unwrap()).await.unwrap();
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(node0.number_of_connected_peers(), 0);
+
+    // Ensure the downward crossing of the low watermark was notified once.
+    assert_eq!(events.try_recv().unwrap(), PeerCountEvent::BelowLow { connected: 1 });
+    assert!(events.try_recv().is_err());
+}
@@ -0,0 +1,167 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_messages::{DisconnectReason, Message};
+use snarkos_node_router::HandshakeError;
+use snarkos_node_tcp::{protocols::Handshake, P2P};
+use snarkvm::prelude::Testnet3 as CurrentNetwork;
+
+use core::time::Duration;
+use parking_lot::Mutex;
+use std::{io, sync::Arc};
+
+/// A writer that captures the logs in memory.
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_handshake_tracing() {
+    // Capture the trace logs of this (single-threaded) test runtime.
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::TRACE)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+    assert!(!node0.is_handshake_tracing_enabled());
+
+    // Enable the handshake protocol, and the handshake tracing.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.tcp().enable_listener().await.unwrap();
+        node.enable_handshake_tracing();
+    }
+
+    // Fail the handshake, as node0 is not ready to accept connections.
+    node0.set_ready(false);
+    node1.connect(node0.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    assert_eq!(node0.number_of_connected_peers(), 0);
+
+    // Ensure the trace shows the exchanged handshake messages, with their secrets redacted.
+    let logs = String::from_utf8(logs.0.lock().clone()).unwrap();
+    let request = format!("ChallengeRequest {{ version: {}", Message::<CurrentNetwork>::VERSION);
+    assert!(
+        logs.contains(&format!("Sending handshake message with '{}': {request}", node0.local_ip().unwrap())),
+        "{logs}"
+    );
+    assert!(logs.contains("node_type: Client"), "{logs}");
+    assert!(logs.contains("nonce: <redacted>"), "{logs}");
+    assert!(logs.contains("Disconnect { reason: NotReady }"), "{logs}");
+    assert!(!logs.contains("sign1"), "{logs}");
+}
+
+#[tokio::test]
+async fn test_handshake_mutual_auth() {
+    // Create 3 routers; node0 and node1 require mutual authentication, and node2 does not.
+    let node0 = validator(0, 2).await;
+    let node1 = client(0, 1).await;
+    let node2 = client(0, 1).await;
+    node0.enable_mutual_auth();
+    node1.enable_mutual_auth();
+
+    // Enable the handshake protocol.
+    for node in [&node0, &node1, &node2] {
+        node.enable_handshake().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to node1, and ensure the handshake concludes with both sides authenticated.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    assert!(node0.is_connected(&node1.local_ip().unwrap()));
+    assert!(node1.is_connected(&node0.local_ip().unwrap()));
+
+    // Ensure node2 is refused in either direction, as it does not authenticate mutually.
+    node0.connect(node2.local_ip().unwrap());
+    node2.connect(node0.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node2);
+
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert_eq!(node2.number_of_connected_peers(), 0);
+    assert!(node0.prometheus_metrics().contains(r#"reason="InvalidHandshake""#));
+}
+
+#[tokio::test]
+async fn test_recent_handshake_failures() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+    assert!(node0.recent_handshake_failures().is_empty());
+
+    // Enable the handshake protocol.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Fail the handshake, as node0 requires a newer message version than node1 runs.
+    node0.set_minimum_peer_version(Message::<CurrentNetwork>::VERSION + 1);
+    node1.connect(node0.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    assert_eq!(node0.number_of_connected_peers(), 0);
+
+    // Ensure both sides record the cause of the failure.
+    let failures = node0.recent_handshake_failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(
+        (failures[0].0, &failures[0].1),
+        (node1.local_ip().unwrap(), &HandshakeError::Dropped(DisconnectReason::OutdatedClientVersion))
+    );
+    let failures = node1.recent_handshake_failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(
+        (failures[0].0, &failures[0].1),
+        (node0.local_ip().unwrap(), &HandshakeError::Disconnected(DisconnectReason::OutdatedClientVersion))
+    );
+}
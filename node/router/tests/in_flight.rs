@@ -0,0 +1,83 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_messages::{Message, NodeType, PeerRequest};
+use snarkos_node_router::{InFlightLimit, InFlightPolicy, Outbound};
+use snarkvm::prelude::Testnet3 as CurrentNetwork;
+
+use core::time::Duration;
+
+/// Returns 2 connected routers, with node0 capping the in-flight messages to each peer with the given policy.
+async fn connected_pair_with(policy: InFlightPolicy) -> (TestRouter<CurrentNetwork>, TestRouter<CurrentNetwork>) {
+    connected_pair(|node0, _| node0.set_in_flight_limit(Some(InFlightLimit { max_messages: 8, policy }))).await
+}
+
+#[tokio::test]
+async fn test_in_flight_backpressure() {
+    let (node0, node1) = connected_pair_with(InFlightPolicy::Backpressure).await;
+
+    // Flood node1 without yielding, so that none of the queued messages can be written out.
+    let num_sent =
+        (0..32).filter(|_| node0.send(node1.local_ip().unwrap(), Message::PeerRequest(PeerRequest)).is_some()).count();
+
+    // Ensure the messages beyond the cap were held back, rather than dropped.
+    assert_eq!(num_sent, 32);
+    assert!(node0.queued_messages(node1.local_ip().unwrap()).unwrap() <= 8);
+
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Ensure every message arrived, without disconnecting, and none was dropped.
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert_eq!(node1.traffic_by_node_type()[&NodeType::Validator].msgs_received, 32);
+    assert_eq!(node0.get_connected_peer(&node1.local_ip().unwrap()).unwrap().dropped_messages(), 0);
+    assert_eq!(node0.queued_messages(node1.local_ip().unwrap()), Some(0));
+}
+
+#[tokio::test]
+async fn test_in_flight_backpressure_overflow() {
+    let (node0, node1) = connected_pair_with(InFlightPolicy::Backpressure).await;
+
+    // Flood node1 without yielding, beyond the number of messages that can be held back.
+    let num_messages = InFlightLimit::MAXIMUM_HELD_MESSAGES + 16;
+    let num_sent = (0..num_messages)
+        .filter(|_| node0.send(node1.local_ip().unwrap(), Message::PeerRequest(PeerRequest)).is_some())
+        .count();
+
+    // Ensure the messages beyond the held-back ones were dropped, and counted.
+    assert_eq!(num_sent, InFlightLimit::MAXIMUM_HELD_MESSAGES);
+    let peer_info = node0.peer_info(&node1.local_ip().unwrap()).unwrap();
+    assert_eq!(peer_info.dropped_messages, 16);
+    assert_eq!(node0.number_of_connected_peers(), 1);
+}
+
+#[tokio::test]
+async fn test_in_flight_disconnect() {
+    let (node0, node1) = connected_pair_with(InFlightPolicy::Disconnect).await;
+
+    // Flood node1 without yielding, so that none of the queued messages can be written out.
+    for _ in 0..32 {
+        node0.send(node1.local_ip().unwrap(), Message::PeerRequest(PeerRequest));
+    }
+
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Ensure node0 disconnected from node1 for violating the protocol.
+    assert_eq!(node0.number_of_connected_peers(), 0);
+    assert!(node0.prometheus_metrics().contains(r#"reason="ProtocolViolation""#));
+}
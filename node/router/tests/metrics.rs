@@ -0,0 +1,434 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_messages::{Message, PeerRequest};
+use snarkos_node_router::{Outbound, PeerMetric, PeerPriority};
+use snarkos_node_tcp::{
+    protocols::{Disconnect, Handshake, Reading, Writing},
+    P2P,
+};
+
+use core::time::Duration;
+use std::net::SocketAddr;
+
+#[tokio::test]
+async fn test_peers_sorted_by() {
+    // Create 4 routers.
+    let node0 = validator(0, 3).await;
+    let node1 = client(0, 1).await;
+    let node2 = client(0, 1).await;
+    let node3 = client(0, 1).await;
+
+    // Enable the handshake and disconnect protocols.
+    for node in [&node0, &node1, &node2, &node3] {
+        node.enable_handshake().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to the clients, one at a time.
+    let (ip1, ip2, ip3) = (node1.local_ip().unwrap(), node2.local_ip().unwrap(), node3.local_ip().unwrap());
+    for ip in [ip1, ip2, ip3] {
+        node0.connect(ip);
+        // Sleep briefly.
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    print_tcp!(node0);
+
+    assert_eq!(node0.number_of_connected_peers(), 3);
+
+    // Assign distinct reputations and latencies, leaving node3 without a measured latency.
+    assert!(node0.adjust_peer_reputation(ip1, 5));
+    assert!(node0.adjust_peer_reputation(ip2, -3));
+    assert!(node0.set_peer_latency(ip1, Duration::from_millis(50)));
+    assert!(node0.set_peer_latency(ip2, Duration::from_millis(10)));
+    // Ensure metrics cannot be set for a disconnected peer.
+    let unknown = SocketAddr::from(([127, 0, 0, 1], 10001));
+    assert!(!node0.adjust_peer_reputation(unknown, 1));
+    assert!(!node0.set_peer_latency(unknown, Duration::from_millis(1)));
+
+    let sorted = |metric| node0.peers_sorted_by(metric).iter().map(|peer| peer.ip()).collect::<Vec<_>>();
+
+    // Ensure the peers are ordered from worst to best.
+    assert_eq!(sorted(PeerMetric::Reputation), vec![ip2, ip3, ip1]);
+    assert_eq!(sorted(PeerMetric::Latency), vec![ip1, ip2, ip3]);
+    assert_eq!(sorted(PeerMetric::ConnectionAge), vec![ip3, ip2, ip1]);
+}
+
+#[tokio::test]
+async fn test_replace_peer() {
+    // Create 4 routers.
+    let node0 = validator(0, 3).await;
+    let old = client(0, 1).await;
+    let new = client(0, 1).await;
+    let other = prover(0, 1).await;
+
+    // Enable the handshake, reading, writing, and disconnect protocols.
+    for node in [&node0, &old, &new, &other] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to the old and new addresses of the same peer, and to another peer.
+    let (old_ip, new_ip, other_ip) = (old.local_ip().unwrap(), new.local_ip().unwrap(), other.local_ip().unwrap());
+    for ip in [old_ip, new_ip, other_ip] {
+        node0.connect(ip);
+        // Sleep briefly.
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    assert_eq!(node0.number_of_connected_peers(), 3);
+
+    // Build up the state of the old connection.
+    assert!(node0.adjust_peer_reputation(old_ip, 7));
+    assert!(node0.set_peer_latency(old_ip, Duration::from_millis(30)));
+    node0.set_peer_priority(old_ip, PeerPriority::High);
+    assert!(node0.quarantine_peer(old_ip, Duration::from_secs(60)));
+
+    // Exchange messages over the old connection, sending more than a quarantined peer is permitted.
+    for _ in 0..5 {
+        assert!(node0.send(old_ip, Message::PeerRequest(PeerRequest)).is_some());
+    }
+    for _ in 0..20 {
+        old.send(node0.local_ip().unwrap(), Message::PeerRequest(PeerRequest));
+    }
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let old_peer = node0.get_connected_peer(&old_ip).unwrap();
+    assert_eq!(old_peer.throttled_messages(), 10);
+    let traffic = |ip| {
+        let stats = node0.tcp().known_peers().get(ip).unwrap();
+        (stats.sent(), stats.received())
+    };
+    let (((old_msgs_sent, old_bytes_sent), (old_msgs_received, old_bytes_received)), (new_sent, new_received)) =
+        (traffic(old_ip), traffic(new_ip));
+    assert!(old_msgs_sent >= 5 && old_msgs_received >= 20);
+
+    // Ensure a connection cannot be replaced by a different peer.
+    assert!(node0.replace_peer(old_ip, other_ip).is_none());
+    assert!(node0.replace_peer(old_ip, old_ip).is_none());
+
+    // Migrate the peer to its new address.
+    node0.replace_peer(old_ip, new_ip).unwrap().await.unwrap();
+
+    // Ensure the old connection is dropped.
+    assert!(!node0.is_connected(&old_ip));
+    assert_eq!(node0.number_of_connected_peers(), 2);
+
+    // Ensure the state carried over to the new connection.
+    let new_peer = node0.get_connected_peer(&new_ip).unwrap();
+    assert_eq!(new_peer.reputation(), 7);
+    assert_eq!(new_peer.latency(), Some(Duration::from_millis(30)));
+    assert_eq!(new_peer.first_seen(), old_peer.first_seen());
+    assert_eq!(new_peer.last_message(), old_peer.last_message());
+    assert!(new_peer.is_quarantined());
+    assert_eq!(new_peer.quarantined_until(), old_peer.quarantined_until());
+    assert_eq!(new_peer.throttled_messages(), 10);
+    assert_eq!(new_peer.dropped_messages(), old_peer.dropped_messages());
+    assert_eq!(node0.peer_priority(&new_ip), PeerPriority::High);
+
+    // Ensure the traffic of the old connection carried over to the new connection.
+    let ((msgs_sent, bytes_sent), (msgs_received, bytes_received)) = traffic(new_ip);
+    assert_eq!(msgs_sent, old_msgs_sent + new_sent.0);
+    assert_eq!(bytes_sent, old_bytes_sent + new_sent.1);
+    assert_eq!(msgs_received, old_msgs_received + new_received.0);
+    assert_eq!(bytes_received, old_bytes_received + new_received.1);
+}
+
+#[tokio::test]
+async fn test_peer_latency() {
+    // Create 2 routers.
+    let node0 = client(0, 1).await;
+    let node1 = client(0, 1).await;
+
+    // Enable the handshake, reading, and writing protocols.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    // Ensure no latency is known before a ping is answered.
+    assert_eq!(node0.peer_latency(&node1.local_ip().unwrap()), None);
+
+    // Ping node1, which answers with a pong.
+    node0.send_ping(node1.local_ip().unwrap(), None);
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Ensure a plausible round-trip latency was measured.
+    let latency = node0.peer_latency(&node1.local_ip().unwrap()).unwrap();
+    assert!(latency > Duration::ZERO && latency < Duration::from_millis(200));
+    assert_eq!(node0.get_connected_peer(&node1.local_ip().unwrap()).unwrap().latency(), Some(latency));
+    // Ensure the latency of a disconnected peer is unknown.
+    assert_eq!(node0.peer_latency(&SocketAddr::from(([127, 0, 0, 1], 10001))), None);
+}
+
+/// Ensures the given text is valid in the Prometheus text exposition format, returning its samples.
+fn parse_prometheus(text: &str) -> Vec<(String, f64)> {
+    let is_valid_name = |name: &str| {
+        name.chars()
+            .enumerate()
+            .all(|(i, c)| c.is_ascii_alphabetic() || c == '_' || c == ':' || (i > 0 && c.is_ascii_digit()))
+    };
+    let mut family = None;
+    let mut samples = Vec::new();
+    for line in text.lines() {
+        if let Some(comment) = line.strip_prefix("# ") {
+            let mut parts = comment.splitn(3, ' ');
+            let (keyword, name, rest) = (parts.next().unwrap(), parts.next().unwrap(), parts.next().unwrap());
+            assert!(is_valid_name(name), "Invalid metric name in '{line}'");
+            match keyword {
+                "HELP" => assert!(!rest.is_empty()),
+                "TYPE" => {
+                    assert!(
+                        ["counter", "gauge", "summary", "histogram", "untyped"].contains(&rest),
+                        "Invalid '{line}'"
+                    );
+                    family = Some(name.to_string());
+                }
+                _ => panic!("Invalid comment '{line}'"),
+            }
+            continue;
+        }
+        // Ensure the sample belongs to the declared family, and has valid labels and a numeric value.
+        let (sample, value) = line.rsplit_once(' ').unwrap();
+        let name = sample.split('{').next().unwrap();
+        assert!(is_valid_name(name), "Invalid sample name in '{line}'");
+        assert!(name.starts_with(family.as_deref().expect("The sample has no type")), "Undeclared '{line}'");
+        if let Some(labels) = sample.strip_prefix(name) {
+            assert!(labels.is_empty() || (labels.starts_with('{') && labels.ends_with('}')), "Invalid labels '{line}'");
+        }
+        samples.push((sample.to_string(), value.parse::<f64>().unwrap()));
+    }
+    samples
+}
+
+#[tokio::test]
+async fn test_prometheus_metrics() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+
+    // Enable the handshake protocol.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Ensure the metrics are valid before any connections are made.
+    let samples = parse_prometheus(&node0.prometheus_metrics());
+    assert!(samples.contains(&(r#"snarkos_router_peers{state="connected"}"#.to_string(), 0.0)));
+
+    // Refuse a connection from node1, and then connect to it.
+    node0.set_ready(false);
+    node1.connect(node0.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    node0.set_ready(true);
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    // Ensure the metrics are valid, and reflect the connection and the refusal.
+    let metrics = node0.prometheus_metrics();
+    let samples = parse_prometheus(&metrics);
+    assert!(samples.contains(&(r#"snarkos_router_peers{state="connected"}"#.to_string(), 1.0)));
+    let refusal = r#"snarkos_router_disconnects_total{direction="sent",reason="NotReady"}"#;
+    assert!(samples.contains(&(refusal.to_string(), 1.0)));
+    assert!(samples.contains(&("snarkos_router_handshake_duration_seconds_count".to_string(), 1.0)));
+    assert!(samples.iter().any(|(sample, _)| sample == r#"snarkos_router_bytes_sent_total{node_type="Client"}"#));
+}
+
+#[tokio::test]
+async fn test_time_since_last_handshake() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+
+    // Enable the handshake protocol.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Ensure there is no handshake before any connections are made.
+    assert_eq!(node0.time_since_last_handshake(), None);
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+
+    // Ensure the handshake was recent.
+    let elapsed = node0.time_since_last_handshake().unwrap();
+    assert!(elapsed < Duration::from_millis(200));
+
+    // Sleep without any new handshakes.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    // Ensure the time since the handshake grows.
+    assert!(node0.time_since_last_handshake().unwrap() >= elapsed + Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn test_reputation_summary() {
+    // Create 4 routers.
+    let node0 = validator(0, 3).await;
+    let node1 = client(0, 1).await;
+    let node2 = client(0, 1).await;
+    let node3 = client(0, 1).await;
+
+    // Enable the handshake and disconnect protocols.
+    for node in [&node0, &node1, &node2, &node3] {
+        node.enable_handshake().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Ensure there is no summary without connected peers.
+    assert_eq!(node0.reputation_summary(), None);
+
+    // Connect node0 to the clients.
+    let (ip1, ip2, ip3) = (node1.local_ip().unwrap(), node2.local_ip().unwrap(), node3.local_ip().unwrap());
+    for ip in [ip1, ip2, ip3] {
+        node0.connect(ip);
+    }
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+
+    assert_eq!(node0.number_of_connected_peers(), 3);
+
+    // Assign varied reputations, leaving node3 with the initial reputation.
+    let initial = node0.get_connected_peer(&ip3).unwrap().reputation();
+    assert!(node0.adjust_peer_reputation(ip1, 9));
+    assert!(node0.adjust_peer_reputation(ip2, -3));
+
+    // Ensure the summary spans the reputations of all connected peers.
+    let summary = node0.reputation_summary().unwrap();
+    assert_eq!(summary.min, initial - 3);
+    assert_eq!(summary.max, initial + 9);
+    assert!((summary.mean - (f64::from(initial) + 2.0)).abs() < f64::EPSILON);
+}
+
+#[tokio::test]
+async fn test_last_message_time() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+
+    // Enable the handshake, reading, writing, and disconnect protocols.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    // Ensure no message was received after the handshake.
+    assert_eq!(node1.number_of_connected_peers(), 1);
+    assert_eq!(node1.last_message_time(&node0.local_ip().unwrap()), None);
+
+    // Send a message from node0 to node1.
+    assert!(node0.send(node1.local_ip().unwrap(), Message::PeerRequest(PeerRequest)).is_some());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Ensure the time of the message was recorded, and the silence since grows.
+    let first = node1.last_message_time(&node0.local_ip().unwrap()).unwrap();
+    let silence = first.elapsed();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(node1.last_message_time(&node0.local_ip().unwrap()), Some(first));
+    assert!(first.elapsed() >= silence + Duration::from_millis(200));
+
+    // Ensure another message updates the time.
+    assert!(node0.send(node1.local_ip().unwrap(), Message::PeerRequest(PeerRequest)).is_some());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(node1.last_message_time(&node0.local_ip().unwrap()).unwrap() > first);
+}
+
+#[tokio::test]
+async fn test_connection_success_rate() {
+    // Create 3 routers.
+    let node0 = validator(0, 2).await;
+    let node1 = client(0, 2).await;
+    let node2 = client(0, 1).await;
+    assert_eq!(node0.connection_success_rate(), None);
+
+    // Enable the handshake protocol.
+    for node in [&node0, &node1, &node2] {
+        node.enable_handshake().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to node1, and to an address without a listener, which refuses the connection.
+    node0.connect(node1.local_ip().unwrap());
+    let dead_addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    node0.connect(dead_addr);
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Fail the handshake of node2 with node1, as node1 is no longer ready to accept connections.
+    node1.set_ready(false);
+    node2.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+    print_tcp!(node2);
+
+    // Ensure the rates count the attempts dialed by each node, and those made by its inbound peers.
+    assert_eq!(node0.connection_success_rate(), Some(0.5));
+    assert_eq!(node1.connection_success_rate(), Some(0.5));
+    assert_eq!(node2.connection_success_rate(), Some(0.0));
+
+    // Ensure an attempt refused before dialing counts as a failure, unless the peer is already connected.
+    assert!(node0.connect(node0.local_ip().unwrap()).is_none());
+    assert!(node0.connect(node1.local_ip().unwrap()).is_none());
+    assert_eq!(node0.connection_success_rate(), Some(1.0 / 3.0));
+}
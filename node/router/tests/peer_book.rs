@@ -0,0 +1,77 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_messages::NodeType;
+use snarkos_node_router::PeerBook;
+use snarkos_node_tcp::{
+    protocols::{Disconnect, Handshake},
+    P2P,
+};
+
+use core::time::Duration;
+
+#[tokio::test]
+async fn test_export_and_import_peer_book() {
+    // Create 4 routers.
+    let node0 = validator(0, 3).await;
+    let node1 = client(0, 1).await;
+    let node2 = client(0, 1).await;
+    let node3 = prover(0, 1).await;
+
+    // Enable the handshake and disconnect protocols.
+    for node in [&node0, &node1, &node2, &node3] {
+        node.enable_handshake().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to the other routers.
+    let (ip1, ip2, ip3) = (node1.local_ip().unwrap(), node2.local_ip().unwrap(), node3.local_ip().unwrap());
+    for ip in [ip1, ip2, ip3] {
+        node0.connect(ip);
+    }
+
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+
+    assert_eq!(node0.number_of_connected_peers(), 3);
+
+    // Assign distinct reputations, leaving node2 with a negative reputation.
+    assert!(node0.adjust_peer_reputation(ip2, -3));
+    assert!(node0.adjust_peer_reputation(ip3, 5));
+
+    // Export the peer book.
+    let path = std::env::temp_dir().join(format!("snarkos-peer-book-{}.json", node0.local_ip().unwrap().port()));
+    node0.export_peer_book(&path).unwrap();
+
+    // Ensure only the good peers are exported, best first.
+    let peer_book: PeerBook = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    let peers = peer_book.peers.iter().map(|entry| (entry.ip, entry.node_type, entry.reputation)).collect::<Vec<_>>();
+    assert_eq!(peers, vec![(ip3, NodeType::Prover, 5), (ip1, NodeType::Client, 0)]);
+
+    // Import the peer book into a fresh router.
+    let node4 = validator(0, 3).await;
+    node4.tcp().enable_listener().await.unwrap();
+    assert_eq!(node4.import_peer_book(&path).unwrap(), 2);
+
+    // Ensure the peers are queued for reconnection, best first.
+    assert_eq!(node4.candidate_peers().into_iter().collect::<Vec<_>>(), vec![ip3, ip1]);
+
+    std::fs::remove_file(&path).unwrap();
+}
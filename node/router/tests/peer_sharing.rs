@@ -0,0 +1,77 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_messages::{Message, PeerRequest, PeerResponse};
+use snarkos_node_router::Outbound;
+use snarkos_node_tcp::{
+    protocols::{Handshake, Reading, Writing},
+    P2P,
+};
+
+use core::time::Duration;
+
+#[tokio::test]
+async fn test_disable_peer_sharing() {
+    // Create 3 routers.
+    let node0 = validator(0, 2).await;
+    let node1 = client(0, 2).await;
+    let node2 = client(0, 2).await;
+
+    // Keep node0 and node1 isolated from any peers they are not explicitly connected to.
+    node0.disable_peer_sharing();
+    node1.disable_peer_sharing();
+    assert!(!node0.is_peer_sharing_enabled());
+    assert!(!node1.is_peer_sharing_enabled());
+    assert!(node2.is_peer_sharing_enabled());
+
+    // Enable the handshake, reading, and writing protocols.
+    for node in [&node0, &node1, &node2] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to node1, and node1 to node2.
+    node0.connect(node1.local_ip().unwrap());
+    node1.connect(node2.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+    print_tcp!(node2);
+
+    assert!(node0.is_connected(&node1.local_ip().unwrap()));
+    assert!(node1.is_connected(&node2.local_ip().unwrap()));
+
+    // Request the peers of node1 from node0, and advertise node2 to node0 from node1.
+    node0.send(node1.local_ip().unwrap(), Message::PeerRequest(PeerRequest));
+    node1.send(
+        node0.local_ip().unwrap(),
+        Message::PeerResponse(PeerResponse { peers: vec![node2.local_ip().unwrap()] }),
+    );
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Ensure neither node learned of a third peer.
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert!(!node0.is_connected(&node2.local_ip().unwrap()));
+    assert!(node0.candidate_peers().is_empty());
+    assert!(!node1.candidate_peers().contains(&node0.local_ip().unwrap()));
+    assert!(!node2.candidate_peers().contains(&node0.local_ip().unwrap()));
+}
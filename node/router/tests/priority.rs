@@ -0,0 +1,76 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::PeerPriority;
+
+use core::time::Duration;
+use parking_lot::Mutex;
+use std::{net::SocketAddr, sync::Arc};
+
+#[tokio::test]
+async fn test_peer_priority_ordering() {
+    // Create a router.
+    let node0 = validator(0, 3).await;
+
+    let low = SocketAddr::from(([127, 0, 0, 1], 10001));
+    let normal = SocketAddr::from(([127, 0, 0, 1], 10002));
+    let high = SocketAddr::from(([127, 0, 0, 1], 10003));
+
+    // Set the peer priorities.
+    node0.set_peer_priority(low, PeerPriority::Low);
+    node0.set_peer_priority(high, PeerPriority::High);
+    assert_eq!(node0.peer_priority(&low), PeerPriority::Low);
+    assert_eq!(node0.peer_priority(&normal), PeerPriority::Normal);
+    assert_eq!(node0.peer_priority(&high), PeerPriority::High);
+
+    // Start processing messages from the normal and high-priority peers.
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let normal_guard = node0.schedule(normal).await;
+    let high_guard = node0.schedule(high).await;
+
+    // Schedule a message from the low-priority peer under load.
+    let handle = {
+        let (node0, order) = (node0.clone(), order.clone());
+        tokio::spawn(async move {
+            let _guard = node0.schedule(low).await;
+            order.lock().push(low);
+        })
+    };
+
+    // Ensure the low-priority message waits while the higher-priority messages are processing.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(order.lock().is_empty());
+
+    // Finish processing the high-priority message, and ensure the low-priority message still waits.
+    order.lock().push(high);
+    drop(high_guard);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(*order.lock(), vec![high]);
+
+    // Finish processing the normal-priority message, and ensure the low-priority message is woken promptly,
+    // well before its maximum delay.
+    order.lock().push(normal);
+    drop(normal_guard);
+    tokio::time::timeout(Duration::from_millis(100), handle).await.unwrap().unwrap();
+
+    // Ensure the messages were processed in order of priority.
+    assert_eq!(*order.lock(), vec![high, normal, low]);
+
+    // Ensure resetting the priority to normal removes it.
+    node0.set_peer_priority(low, PeerPriority::Normal);
+    assert_eq!(node0.peer_priority(&low), PeerPriority::Normal);
+}
@@ -0,0 +1,129 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_messages::{Message, NodeType, PeerRequest};
+use snarkos_node_router::Outbound;
+use snarkos_node_tcp::{
+    protocols::{Disconnect, Handshake, Reading, Writing},
+    P2P,
+};
+
+use core::time::Duration;
+
+#[tokio::test]
+async fn test_traffic_by_node_type() {
+    // Create 3 routers.
+    let node0 = validator(0, 2).await;
+    let node1 = validator(0, 2).await;
+    let node2 = client(0, 2).await;
+
+    // Enable the handshake, reading, writing, and disconnect protocols.
+    for node in [&node0, &node1, &node2] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to a validator and a client.
+    node0.connect(node1.local_ip().unwrap());
+    node0.connect(node2.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+    print_tcp!(node2);
+
+    assert_eq!(node0.number_of_connected_peers(), 2);
+
+    // Send 1 message to the validator, and 2 messages to the client.
+    node0.send(node1.local_ip().unwrap(), Message::PeerRequest(PeerRequest));
+    node0.send(node2.local_ip().unwrap(), Message::PeerRequest(PeerRequest));
+    node0.send(node2.local_ip().unwrap(), Message::PeerRequest(PeerRequest));
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Ensure the sent traffic is grouped by peer type.
+    let traffic = node0.traffic_by_node_type();
+    assert_eq!(traffic[&NodeType::Validator].msgs_sent, 1);
+    assert_eq!(traffic[&NodeType::Client].msgs_sent, 2);
+    assert!(traffic[&NodeType::Validator].bytes_sent > 0);
+    assert!(traffic[&NodeType::Client].bytes_sent > traffic[&NodeType::Validator].bytes_sent);
+    assert!(!traffic.contains_key(&NodeType::Prover));
+
+    // Ensure each peer received the traffic from a validator.
+    assert_eq!(node1.traffic_by_node_type()[&NodeType::Validator].msgs_received, 1);
+    assert_eq!(node2.traffic_by_node_type()[&NodeType::Validator].msgs_received, 2);
+
+    // Disconnect from the client, and ensure its traffic is retained in the lifetime totals.
+    node0.disconnect(node2.local_ip().unwrap()).await.unwrap();
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    let traffic = node0.traffic_by_node_type();
+    assert_eq!(traffic[&NodeType::Validator].msgs_sent, 1);
+    assert_eq!(traffic[&NodeType::Client].msgs_sent, 2);
+}
+
+#[tokio::test]
+async fn test_subscribe_throughput() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+
+    // Enable the handshake, reading, and writing protocols.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip().unwrap());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    // Subscribe to the throughput of node0, sampled every 200ms.
+    let mut samples = node0.subscribe_throughput(Duration::from_millis(200));
+
+    // Send messages to node1 within the first interval.
+    for _ in 0..10 {
+        node0.send(node1.local_ip().unwrap(), Message::PeerRequest(PeerRequest));
+    }
+
+    // Ensure the sample reflects the outbound bytes over the interval, and node1's responses inbound.
+    let sample = samples.recv().await.unwrap();
+    assert_eq!(sample.len(), 1);
+    assert_eq!(sample[0].peer_ip, node1.local_ip().unwrap());
+    let bytes_sent: u64 = node0.traffic_by_node_type().values().map(|traffic| traffic.bytes_sent).sum();
+    assert!(sample[0].bytes_out_per_sec > 0.0);
+    // The interval is at least 200ms, so at most 5 times the bytes sent can be sent per second.
+    assert!(sample[0].bytes_out_per_sec <= bytes_sent as f64 * 5.0);
+    assert!(sample[0].bytes_in_per_sec > 0.0);
+
+    // Ensure an idle interval yields no throughput.
+    let sample = samples.recv().await.unwrap();
+    assert_eq!(sample[0].bytes_out_per_sec, 0.0);
+    assert_eq!(sample[0].bytes_in_per_sec, 0.0);
+}
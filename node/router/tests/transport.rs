@@ -0,0 +1,90 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_messages::{Disconnect, DisconnectReason, Message};
+use snarkos_node_tcp::ConnectionSide;
+use snarkvm::prelude::Testnet3 as CurrentNetwork;
+
+use futures_util::{SinkExt, StreamExt};
+use std::net::SocketAddr;
+
+#[tokio::test]
+async fn test_disconnect_over_memory_transport() {
+    // Create 2 routers, and bind their listeners to random ports to read back their addresses.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+    node0.bind_listener().await.unwrap();
+    node1.bind_listener().await.unwrap();
+    let genesis_header = *sample_genesis_block::<CurrentNetwork>().header();
+
+    // Shake hands over an in-memory transport, with node0 as the initiator.
+    let (mut stream0, mut stream1) = memory_transport();
+    let node0_addr = SocketAddr::from(([127, 0, 0, 1], 50001));
+    let (result0, result1) = tokio::join!(
+        node0.handshake(node1.local_ip().unwrap(), &mut stream0, ConnectionSide::Responder, genesis_header),
+        node1.handshake(node0_addr, &mut stream1, ConnectionSide::Initiator, genesis_header),
+    );
+    let (peer_ip0, mut framed0) = result0.unwrap();
+    let (peer_ip1, mut framed1) = result1.unwrap();
+
+    // Ensure the routers are connected to each other.
+    assert_eq!(peer_ip0, node1.local_ip().unwrap());
+    assert_eq!(peer_ip1, node0.local_ip().unwrap());
+    assert!(node0.is_connected(&node1.local_ip().unwrap()));
+    assert!(node1.is_connected(&node0.local_ip().unwrap()));
+
+    // Disconnect node0 from node1.
+    framed0.send(Message::Disconnect(Disconnect { reason: DisconnectReason::NoReasonGiven })).await.unwrap();
+    node0.remove_connected_peer(peer_ip0);
+
+    // Ensure node1 receives the disconnect, and drops node0.
+    match framed1.next().await {
+        Some(Ok(Message::Disconnect(disconnect))) => assert_eq!(disconnect.reason, DisconnectReason::NoReasonGiven),
+        message => panic!("Expected a disconnect, received {message:?}"),
+    }
+    node1.remove_connected_peer(peer_ip1);
+
+    assert_eq!(node0.number_of_connected_peers(), 0);
+    assert_eq!(node1.number_of_connected_peers(), 0);
+}
+
+#[tokio::test]
+async fn test_handshake_refused_over_memory_transport() {
+    // Create 2 routers, and bind their listeners to random ports to read back their addresses.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+    node0.bind_listener().await.unwrap();
+    node1.bind_listener().await.unwrap();
+    let genesis_header = *sample_genesis_block::<CurrentNetwork>().header();
+
+    // Mark node1 as not ready.
+    node1.set_ready(false);
+
+    // Shake hands over an in-memory transport, with node0 as the initiator.
+    let (mut stream0, mut stream1) = memory_transport();
+    let node0_addr = SocketAddr::from(([127, 0, 0, 1], 50003));
+    let (result0, result1) = tokio::join!(
+        node0.handshake(node1.local_ip().unwrap(), &mut stream0, ConnectionSide::Responder, genesis_header),
+        node1.handshake(node0_addr, &mut stream1, ConnectionSide::Initiator, genesis_header),
+    );
+
+    // Ensure the handshake was refused on both sides.
+    assert!(result0.is_err());
+    assert!(result1.is_err());
+    assert_eq!(node0.number_of_connected_peers(), 0);
+    assert_eq!(node1.number_of_connected_peers(), 0);
+}
@@ -152,7 +152,7 @@ impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
             lap!(timer, "Initialize REST server");
         }
         // Initialize the routing.
-        node.initialize_routing().await;
+        node.initialize_routing().await?;
         // Initialize the block production.
         node.initialize_block_production().await;
         // Initialize the signal handler.
@@ -82,7 +82,7 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
             _phantom: PhantomData,
         };
         // Initialize the routing.
-        node.initialize_routing().await;
+        node.initialize_routing().await?;
         // Initialize the signal handler.
         node.handle_signals();
         // Return the node.
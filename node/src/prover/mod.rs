@@ -105,7 +105,7 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
             _phantom: Default::default(),
         };
         // Initialize the routing.
-        node.initialize_routing().await;
+        node.initialize_routing().await?;
         // Initialize the coinbase puzzle.
         node.initialize_coinbase_puzzle().await;
         // Initialize the signal handler.
@@ -113,7 +113,7 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         // Initialize the sync pool.
         node.initialize_sync()?;
         // Initialize the routing.
-        node.initialize_routing().await;
+        node.initialize_routing().await?;
         // Initialize the signal handler.
         node.handle_signals();
         // Return the node.
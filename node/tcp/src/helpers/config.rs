@@ -32,15 +32,11 @@ pub struct Config {
     ///
     /// note: If set to `None`, the Tcp will not listen for inbound connections at all.
     pub listener_ip: Option<IpAddr>,
-    /// The desired listening port of the Tcp. If [`Config::allow_random_port`] is set to `true`, the Tcp
-    /// will attempt to bind its listener to a different port if the desired one is not available.
+    /// The desired listening port of the Tcp. If it is not available, the listener fails to start.
     ///
-    /// note: [`Config::listener_ip`] must not be `None` in order for it to have any effect.
+    /// note: If set to `None` or `Some(0)`, the Tcp listens on a random port chosen by the OS;
+    /// [`Config::listener_ip`] must not be `None` in order for it to have any effect.
     pub desired_listening_port: Option<u16>,
-    /// Allow listening on a different port if [`Config::desired_listening_port`] is unavailable.
-    ///
-    /// note: [`Config::listener_ip`] must not be `None` in order for it to have any effect.
-    pub allow_random_port: bool,
     /// The list of IO errors considered fatal and causing the connection to be dropped.
     ///
     /// note: Tcp needs to implement the [`Reading`] and/or [`Writing`] protocol in order for it to have any effect.
@@ -52,6 +48,20 @@ pub struct Config {
     pub max_connections: u16,
     /// The maximum time (in milliseconds) allowed to establish a raw (before the [`Handshake`] protocol) TCP connection.
     pub connection_timeout_ms: u16,
+    /// The initial size (in bytes) of the per-connection buffer used to read inbound messages.
+    ///
+    /// note: If set to `None`, [`Reading::INITIAL_BUFFER_SIZE`] is used instead.
+    pub read_buffer_size: Option<usize>,
+    /// The initial size (in bytes) of the per-connection buffer used to write outbound messages.
+    ///
+    /// note: If set to `None`, the buffer is only allocated once outbound messages are written.
+    pub write_buffer_size: Option<usize>,
+    /// The maximum number of pending inbound connections the listener's accept queue can hold.
+    ///
+    /// note: If set to `None`, a backlog of 1024 is used, matching [`tokio::net::TcpListener::bind`]. The value is
+    /// also capped by the OS: `net.core.somaxconn` on Linux, `kern.ipc.somaxconn` on macOS and the BSDs, and
+    /// `SOMAXCONN` on Windows; [`Config::listener_ip`] must not be `None` in order for it to have any effect.
+    pub listen_backlog: Option<u32>,
 }
 
 impl Config {
@@ -84,10 +94,12 @@ impl Default for Config {
             name: None,
             listener_ip: default_ip(),
             desired_listening_port: None,
-            allow_random_port: true,
             fatal_io_errors: vec![ConnectionReset, ConnectionAborted, BrokenPipe, InvalidData, UnexpectedEof],
             max_connections: 100,
             connection_timeout_ms: 1_000,
+            read_buffer_size: None,
+            write_buffer_size: None,
+            listen_backlog: None,
         }
     }
 }
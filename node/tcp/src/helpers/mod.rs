@@ -21,6 +21,9 @@ pub use connections::{Connection, ConnectionSide};
 mod known_peers;
 pub use known_peers::KnownPeers;
 
+mod rate_limiter;
+pub(crate) use rate_limiter::RateLimiter;
+
 mod stats;
 pub use stats::Stats;
 
@@ -0,0 +1,48 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, Instant};
+
+/// Throttles the bytes transferred over a single connection to a maximum rate.
+pub(crate) struct RateLimiter {
+    /// The start of the current measurement window.
+    start: Instant,
+    /// The number of bytes transferred in the current measurement window.
+    bytes: u64,
+}
+
+impl RateLimiter {
+    /// Initializes a new rate limiter.
+    pub(crate) fn new() -> Self {
+        Self { start: Instant::now(), bytes: 0 }
+    }
+
+    /// Registers the given number of transferred bytes, and returns the duration to wait
+    /// before transferring more in order to stay within the given number of bytes per second.
+    pub(crate) fn register(&mut self, len: usize, bytes_per_sec: u64) -> Option<Duration> {
+        self.bytes = self.bytes.saturating_add(len as u64);
+
+        let expected = Duration::from_secs_f64(self.bytes as f64 / bytes_per_sec.max(1) as f64);
+        let elapsed = self.start.elapsed();
+
+        if expected > elapsed {
+            Some(expected - elapsed)
+        } else {
+            // The transfer is within the limit; start a new window so that idle time is not banked.
+            self.start = Instant::now();
+            self.bytes = 0;
+            None
+        }
+    }
+}
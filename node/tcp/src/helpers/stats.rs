@@ -67,4 +67,14 @@ impl Stats {
     pub fn register_failure(&self) {
         self.failures.fetch_add(1, Relaxed);
     }
+
+    /// Registers the messages, bytes, and failures recorded in the given stats.
+    pub fn register_stats(&self, stats: &Stats) {
+        let ((msgs_sent, bytes_sent), (msgs_received, bytes_received)) = (stats.sent(), stats.received());
+        self.msgs_sent.fetch_add(msgs_sent, Relaxed);
+        self.bytes_sent.fetch_add(bytes_sent, Relaxed);
+        self.msgs_received.fetch_add(msgs_received, Relaxed);
+        self.bytes_received.fetch_add(bytes_received, Relaxed);
+        self.failures.fetch_add(stats.failures(), Relaxed);
+    }
 }
@@ -66,6 +66,11 @@ where
         );
     }
 
+    /// Returns `true` if the [`Disconnect`] protocol was enabled via [`Disconnect::enable_disconnect`].
+    fn is_disconnect_enabled(&self) -> bool {
+        self.tcp().protocols.disconnect.get().is_some()
+    }
+
     /// Any extra actions to be executed during a disconnect; in order to still be able to
     /// communicate with the peer in the usual manner (i.e. via [`Writing`]), only its [`SocketAddr`]
     /// (as opposed to the related [`Connection`] object) is provided as an argument.
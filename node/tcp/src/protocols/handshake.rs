@@ -92,6 +92,11 @@ where
         assert!(self.tcp().protocols.handshake.set(hdl).is_ok(), "the Handshake protocol was enabled more than once!");
     }
 
+    /// Returns `true` if the [`Handshake`] protocol was enabled via [`Handshake::enable_handshake`].
+    fn is_handshake_enabled(&self) -> bool {
+        self.tcp().protocols.handshake.get().is_some()
+    }
+
     /// Performs the handshake; temporarily assumes control of the [`Connection`] and returns it if the handshake is
     /// successful.
     async fn perform_handshake(&self, conn: Connection) -> io::Result<Connection>;
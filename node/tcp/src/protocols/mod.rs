@@ -44,6 +44,37 @@ pub(crate) struct Protocols {
     pub(crate) disconnect: OnceBox<ProtocolHandler<SocketAddr, ()>>,
 }
 
+impl Protocols {
+    /// Returns the names of the enabled protocols.
+    pub(crate) fn enabled(&self) -> Vec<ProtocolName> {
+        [
+            (ProtocolName::Handshake, self.handshake.get().is_some()),
+            (ProtocolName::Reading, self.reading.get().is_some()),
+            (ProtocolName::Writing, self.writing.get().is_some()),
+            (ProtocolName::OnConnect, self.on_connect.get().is_some()),
+            (ProtocolName::Disconnect, self.disconnect.get().is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, is_enabled)| is_enabled.then_some(name))
+        .collect()
+    }
+}
+
+/// The name of an opt-in protocol.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ProtocolName {
+    /// The [`Handshake`] protocol.
+    Handshake,
+    /// The [`Reading`] protocol.
+    Reading,
+    /// The [`Writing`] protocol.
+    Writing,
+    /// The [`OnConnect`] protocol.
+    OnConnect,
+    /// The [`Disconnect`] protocol.
+    Disconnect,
+}
+
 /// An object sent to a protocol handler task; the task assumes control of a protocol-relevant item `T`,
 /// and when it's done with it, it returns it (possibly in a wrapper object) or another relevant object
 /// to the callsite via the counterpart [`oneshot::Receiver`].
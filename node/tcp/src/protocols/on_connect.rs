@@ -71,6 +71,11 @@ where
         assert!(self.tcp().protocols.on_connect.set(hdl).is_ok(), "the OnConnect protocol was enabled more than once!");
     }
 
+    /// Returns `true` if the [`OnConnect`] protocol was enabled via [`OnConnect::enable_on_connect`].
+    fn is_on_connect_enabled(&self) -> bool {
+        self.tcp().protocols.on_connect.get().is_some()
+    }
+
     /// Any initial actions to be executed after the handshake is concluded; in order to be able to
     /// communicate with the peer in the usual manner (i.e. via [`Writing`]), only its [`SocketAddr`]
     /// (as opposed to the related [`Connection`] object) is provided as an argument.
@@ -29,6 +29,7 @@ use crate::{protocols::Handshake, Config};
 use crate::{
     protocols::{ProtocolHandler, ReturnableConnection},
     ConnectionSide,
+    RateLimiter,
     Tcp,
     P2P,
 };
@@ -55,7 +56,7 @@ where
     /// The initial size of a per-connection buffer for reading inbound messages. Can be set to the maximum expected size
     /// of the inbound message in order to only allocate it once.
     ///
-    /// The default value is 1024KiB.
+    /// The default value is 1024KiB; it can be overridden per Tcp with [`Config::read_buffer_size`].
     const INITIAL_BUFFER_SIZE: usize = 1024 * 1024;
 
     /// The final (deserialized) type of inbound messages.
@@ -90,6 +91,11 @@ where
         assert!(self.tcp().protocols.reading.set(hdl).is_ok(), "the Reading protocol was enabled more than once!");
     }
 
+    /// Returns `true` if the [`Reading`] protocol was enabled via [`Reading::enable_reading`].
+    fn is_reading_enabled(&self) -> bool {
+        self.tcp().protocols.reading.get().is_some()
+    }
+
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
     fn codec(&self, addr: SocketAddr, side: ConnectionSide) -> Self::Codec;
@@ -125,8 +131,9 @@ impl<R: Reading> ReadingInternal for R {
         let (tx_conn_ready, rx_conn_ready) = oneshot::channel();
         conn.readiness_notifier = Some(tx_conn_ready);
 
-        if Self::INITIAL_BUFFER_SIZE != 0 {
-            framed.read_buffer_mut().reserve(Self::INITIAL_BUFFER_SIZE);
+        let buffer_size = self.tcp().config().read_buffer_size.unwrap_or(Self::INITIAL_BUFFER_SIZE);
+        if buffer_size != 0 {
+            framed.read_buffer_mut().reserve(buffer_size);
         }
 
         let (inbound_message_sender, mut inbound_message_receiver) = mpsc::channel(Self::MESSAGE_QUEUE_DEPTH);
@@ -164,6 +171,10 @@ impl<R: Reading> ReadingInternal for R {
             // this task gets aborted, so there is no need for a dedicated timeout
             let _ = rx_conn_ready.await;
 
+            // throttles the inbound messages if a receive rate limit is set
+            let mut rate_limiter = RateLimiter::new();
+            let mut bytes_received = node.known_peers().get(addr).map_or(0, |stats| stats.received().1);
+
             while let Some(bytes) = framed.next().await {
                 match bytes {
                     Ok(msg) => {
@@ -172,6 +183,15 @@ impl<R: Reading> ReadingInternal for R {
                             error!(parent: node.span(), "can't process a message from {}: {}", addr, e);
                             node.stats().register_failure();
                         }
+
+                        if let Some(limit) = node.receive_rate_limit() {
+                            let total = node.known_peers().get(addr).map_or(0, |stats| stats.received().1);
+                            let len = total.saturating_sub(bytes_received) as usize;
+                            bytes_received = total;
+                            if let Some(delay) = rate_limiter.register(len, limit) {
+                                tokio::time::sleep(delay).await;
+                            }
+                        }
                     }
                     Err(e) => {
                         error!(parent: node.span(), "can't read from {}: {}", addr, e);
@@ -30,6 +30,7 @@ use crate::{
     protocols::{Protocol, ProtocolHandler, ReturnableConnection},
     Connection,
     ConnectionSide,
+    RateLimiter,
     P2P,
 };
 
@@ -88,6 +89,11 @@ where
         assert!(self.tcp().protocols.writing.set(hdl).is_ok(), "the Writing protocol was enabled more than once!");
     }
 
+    /// Returns `true` if the [`Writing`] protocol was enabled via [`Writing::enable_writing`].
+    fn is_writing_enabled(&self) -> bool {
+        self.tcp().protocols.writing.get().is_some()
+    }
+
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` param indicates the connection side **from the node's perspective**.
     fn codec(&self, addr: SocketAddr, side: ConnectionSide) -> Self::Codec;
@@ -124,6 +130,13 @@ where
         }
     }
 
+    /// Returns the number of messages queued to be sent to the specified [`SocketAddr`], or `None` if the node
+    /// is not connected to it, or if [`Writing::enable_writing`] hadn't been called yet.
+    fn queued_messages(&self, addr: SocketAddr) -> Option<usize> {
+        let handler = self.tcp().protocols.writing.get()?;
+        handler.senders.read().get(&addr).map(|sender| sender.max_capacity() - sender.capacity())
+    }
+
     /// Broadcasts the provided message to all connected peers. Returns as soon as the message is queued to
     /// be sent to all the peers, without waiting for the actual delivery. This method doesn't provide the
     /// means to check when and if the messages actually get delivered; you can achieve that by calling
@@ -192,6 +205,10 @@ impl<W: Writing> WritingInternal for W {
         let writer = conn.writer.take().expect("missing connection writer!");
         let mut framed = FramedWrite::new(writer, codec);
 
+        if let Some(buffer_size) = self.tcp().config().write_buffer_size {
+            framed.write_buffer_mut().reserve(buffer_size);
+        }
+
         let (outbound_message_sender, mut outbound_message_receiver) = mpsc::channel(Self::MESSAGE_QUEUE_DEPTH);
 
         // register the connection's message sender with the Writing protocol handler
@@ -213,6 +230,9 @@ impl<W: Writing> WritingInternal for W {
             // move the cleanup into the task that gets aborted on disconnect
             let _auto_cleanup = auto_cleanup;
 
+            // throttles the outbound messages if a send rate limit is set
+            let mut rate_limiter = RateLimiter::new();
+
             while let Some(wrapped_msg) = outbound_message_receiver.recv().await {
                 let msg = wrapped_msg.msg.downcast().unwrap();
 
@@ -222,6 +242,12 @@ impl<W: Writing> WritingInternal for W {
                         node.known_peers().register_sent_message(addr, len);
                         node.stats().register_sent_message(len);
                         trace!(parent: node.span(), "sent {}B to {}", len, addr);
+
+                        if let Some(limit) = node.send_rate_limit() {
+                            if let Some(delay) = rate_limiter.register(len, limit) {
+                                tokio::time::sleep(delay).await;
+                            }
+                        }
                     }
                     Err(e) => {
                         node.known_peers().register_failure(addr);
@@ -19,7 +19,7 @@ use std::{
     net::{IpAddr, SocketAddr},
     ops::Deref,
     sync::{
-        atomic::{AtomicUsize, Ordering::*},
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering::*},
         Arc,
     },
     time::Duration,
@@ -29,7 +29,7 @@ use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
 use tokio::{
     io::split,
-    net::{TcpListener, TcpStream},
+    net::{TcpListener, TcpSocket, TcpStream},
     sync::oneshot,
     task::JoinHandle,
     time::timeout,
@@ -38,7 +38,7 @@ use tracing::*;
 
 use crate::{
     connections::{Connection, ConnectionSide, Connections},
-    protocols::{Protocol, Protocols},
+    protocols::{Protocol, ProtocolName, Protocols},
     Config,
     KnownPeers,
     Stats,
@@ -47,6 +47,9 @@ use crate::{
 // A sequential numeric identifier assigned to `Tcp`s that were not provided with a name.
 static SEQUENTIAL_NODE_ID: AtomicUsize = AtomicUsize::new(0);
 
+// The listen backlog used by `TcpListener::bind`, used if `Config::listen_backlog` is not set.
+const DEFAULT_LISTEN_BACKLOG: u32 = 1024;
+
 /// The central object responsible for handling connections.
 #[derive(Clone)]
 pub struct Tcp(Arc<InnerTcp>);
@@ -71,6 +74,12 @@ pub struct InnerTcp {
     pub(crate) protocols: Protocols,
     /// A list of connections that have not been finalized yet.
     connecting: Mutex<HashSet<SocketAddr>>,
+    /// If `true`, the listener rejects new inbound connections.
+    accepting_paused: AtomicBool,
+    /// The maximum number of bytes per second sent to each peer; `0` means unlimited.
+    send_rate_limit: AtomicU64,
+    /// The maximum number of bytes per second received from each peer; `0` means unlimited.
+    receive_rate_limit: AtomicU64,
     /// Contains objects related to the node's active connections.
     connections: Connections,
     /// Collects statistics related to the node's peers.
@@ -99,6 +108,9 @@ impl Tcp {
             listening_addr: Default::default(),
             protocols: Default::default(),
             connecting: Default::default(),
+            accepting_paused: Default::default(),
+            send_rate_limit: Default::default(),
+            receive_rate_limit: Default::default(),
             connections: Default::default(),
             known_peers: Default::default(),
             stats: Default::default(),
@@ -159,6 +171,45 @@ impl Tcp {
         self.connecting.lock().iter().copied().collect()
     }
 
+    /// Returns `true` if the listener is accepting new inbound connections.
+    pub fn is_accepting(&self) -> bool {
+        !self.accepting_paused.load(Relaxed)
+    }
+
+    /// Stops accepting new inbound connections; existing connections and outbound connection attempts are unaffected.
+    pub fn pause_accepting(&self) {
+        self.accepting_paused.store(true, Relaxed);
+        debug!(parent: self.span(), "Paused accepting inbound connections");
+    }
+
+    /// Resumes accepting new inbound connections.
+    pub fn resume_accepting(&self) {
+        self.accepting_paused.store(false, Relaxed);
+        debug!(parent: self.span(), "Resumed accepting inbound connections");
+    }
+
+    /// Sets the maximum number of bytes per second sent to and received from each peer; traffic exceeding
+    /// the limits is throttled rather than dropped. A limit of `None` means unlimited, which is the default.
+    pub fn set_rate_limits(&self, send_bytes_per_sec: Option<u64>, receive_bytes_per_sec: Option<u64>) {
+        self.send_rate_limit.store(send_bytes_per_sec.unwrap_or(0), Relaxed);
+        self.receive_rate_limit.store(receive_bytes_per_sec.unwrap_or(0), Relaxed);
+    }
+
+    /// Returns the maximum number of bytes per second sent to each peer, if it is limited.
+    pub fn send_rate_limit(&self) -> Option<u64> {
+        Some(self.send_rate_limit.load(Relaxed)).filter(|limit| *limit != 0)
+    }
+
+    /// Returns the maximum number of bytes per second received from each peer, if it is limited.
+    pub fn receive_rate_limit(&self) -> Option<u64> {
+        Some(self.receive_rate_limit.load(Relaxed)).filter(|limit| *limit != 0)
+    }
+
+    /// Returns the names of the enabled protocols, in the order they are applied to a new connection.
+    pub fn enabled_protocols(&self) -> Vec<ProtocolName> {
+        self.protocols.enabled()
+    }
+
     /// Returns a reference to the collection of statistics of known peers.
     #[inline]
     pub fn known_peers(&self) -> &KnownPeers {
@@ -330,40 +381,38 @@ impl Tcp {
 
     /// Creates an instance of `TcpListener` based on the node's configuration.
     async fn create_listener(&self, listener_ip: IpAddr) -> io::Result<TcpListener> {
-        let listener = if let Some(port) = self.config().desired_listening_port {
-            // Construct the desired listening IP address.
-            let desired_listening_addr = SocketAddr::new(listener_ip, port);
-            // If a desired listening port is set, try to bind to it.
-            match TcpListener::bind(desired_listening_addr).await {
-                Ok(listener) => listener,
-                Err(e) => {
-                    if self.config().allow_random_port {
-                        warn!(
-                            parent: self.span(),
-                            "Trying any listening port, as the desired port is unavailable: {e}"
-                        );
-                        let random_available_addr = SocketAddr::new(listener_ip, 0);
-                        TcpListener::bind(random_available_addr).await?
-                    } else {
-                        error!(parent: self.span(), "The desired listening port is unavailable: {e}");
-                        return Err(e);
-                    }
-                }
+        // Construct the desired listening IP address, on a random port if no port is desired.
+        let port = self.config().desired_listening_port.unwrap_or(0);
+        let desired_listening_addr = SocketAddr::new(listener_ip, port);
+        // Bind to the desired listening address, failing if it is unavailable.
+        match self.bind(desired_listening_addr) {
+            Ok(listener) => Ok(listener),
+            Err(e) => {
+                error!(parent: self.span(), "The desired listening port is unavailable: {e}");
+                Err(e)
             }
-        } else if self.config().allow_random_port {
-            let random_available_addr = SocketAddr::new(listener_ip, 0);
-            TcpListener::bind(random_available_addr).await?
-        } else {
-            panic!("As 'listener_ip' is set, either 'desired_listening_port' or 'allow_random_port' must be set");
-        };
+        }
+    }
 
-        Ok(listener)
+    /// Binds a `TcpListener` to the given address, using the configured listen backlog.
+    fn bind(&self, addr: SocketAddr) -> io::Result<TcpListener> {
+        let socket = if addr.is_ipv4() { TcpSocket::new_v4()? } else { TcpSocket::new_v6()? };
+        // Mirror `TcpListener::bind`, which allows rebinding to a port in the `TIME_WAIT` state.
+        #[cfg(not(windows))]
+        socket.set_reuseaddr(true)?;
+        socket.bind(addr)?;
+        socket.listen(self.config().listen_backlog.unwrap_or(DEFAULT_LISTEN_BACKLOG))
     }
 
     /// Handles a new inbound connection.
     fn handle_connection(&self, stream: TcpStream, addr: SocketAddr) {
         debug!(parent: self.span(), "Received a connection from {addr}");
 
+        if !self.is_accepting() {
+            debug!(parent: self.span(), "Rejecting the connection from {addr} (not accepting connections)");
+            return;
+        }
+
         if !self.can_add_connection() || self.is_self_connect(addr) {
             debug!(parent: self.span(), "Rejecting the connection from {addr}");
             return;
@@ -501,8 +550,33 @@ impl fmt::Debug for Tcp {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{protocols::Writing, P2P};
+
+    use bytes::Bytes;
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        time::Instant,
+    };
+    use tokio_util::codec::BytesCodec;
+
+    /// A node that writes raw bytes to its peers.
+    #[derive(Clone)]
+    struct RawWriter(Tcp);
+
+    impl P2P for RawWriter {
+        fn tcp(&self) -> &Tcp {
+            &self.0
+        }
+    }
 
-    use std::net::{IpAddr, Ipv4Addr};
+    impl Writing for RawWriter {
+        type Codec = BytesCodec;
+        type Message = Bytes;
+
+        fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+            Default::default()
+        }
+    }
 
     #[tokio::test]
     async fn test_new() {
@@ -520,6 +594,18 @@ mod tests {
         assert_eq!(tcp.num_connecting(), 0);
     }
 
+    #[tokio::test]
+    async fn test_listen_backlog() {
+        let tcp = Tcp::new(Config { listen_backlog: Some(4096), ..Default::default() });
+        assert_eq!(tcp.config.listen_backlog, Some(4096));
+        let node_ip = tcp.enable_listener().await.unwrap();
+
+        // Ensure the listener accepts connections.
+        let peer = Tcp::new(Config { listener_ip: None, ..Default::default() });
+        peer.connect(node_ip).await.unwrap();
+        assert_eq!(peer.num_connected(), 1);
+    }
+
     #[tokio::test]
     async fn test_connect() {
         let tcp = Tcp::new(Config::default());
@@ -676,6 +762,49 @@ mod tests {
         assert!(!tcp.is_connecting(peer2_ip));
     }
 
+    #[tokio::test]
+    async fn test_pause_accepting() {
+        let tcp = Tcp::new(Config {
+            listener_ip: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            desired_listening_port: Some(0),
+            max_connections: 1,
+            ..Default::default()
+        });
+        let node_ip = tcp.enable_listener().await.unwrap();
+
+        // Initialize the peer.
+        let peer = Tcp::new(Config {
+            listener_ip: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            desired_listening_port: Some(0),
+            max_connections: 1,
+            ..Default::default()
+        });
+        let _peer_ip = peer.enable_listener().await.unwrap();
+
+        // Pause accepting inbound connections.
+        tcp.pause_accepting();
+        assert!(!tcp.is_accepting());
+
+        // Ensure the connection from the peer is not accepted.
+        peer.connect(node_ip).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(tcp.num_connected(), 0);
+        assert_eq!(tcp.num_connecting(), 0);
+
+        // Drop the connection on the peer side.
+        peer.disconnect(node_ip).await;
+
+        // Resume accepting inbound connections.
+        tcp.resume_accepting();
+        assert!(tcp.is_accepting());
+
+        // Ensure the connection from the peer is accepted.
+        peer.connect(node_ip).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(tcp.num_connected(), 1);
+        assert_eq!(tcp.num_connecting(), 0);
+    }
+
     #[tokio::test]
     async fn test_adapt_stream() {
         let tcp = Tcp::new(Config { max_connections: 1, ..Default::default() });
@@ -704,4 +833,40 @@ mod tests {
         assert!(tcp.is_connected(peer_ip));
         assert!(!tcp.is_connecting(peer_ip));
     }
+
+    #[tokio::test]
+    async fn test_send_rate_limit() {
+        const LIMIT: u64 = 5_000;
+
+        let node = RawWriter(Tcp::new(Config::default()));
+        node.enable_writing().await;
+        node.tcp().set_rate_limits(Some(LIMIT), None);
+        assert_eq!(node.tcp().send_rate_limit(), Some(LIMIT));
+        assert_eq!(node.tcp().receive_rate_limit(), None);
+
+        // Initialize the peer.
+        let peer = Tcp::new(Config {
+            listener_ip: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            desired_listening_port: Some(0),
+            max_connections: 1,
+            ..Default::default()
+        });
+        let peer_ip = peer.enable_listener().await.unwrap();
+
+        // Connect to the peer.
+        node.tcp().connect(peer_ip).await.unwrap();
+
+        // Send twice the limit as fast as possible.
+        let start = Instant::now();
+        let mut deliveries = Vec::new();
+        for _ in 0..10 {
+            deliveries.push(node.unicast(peer_ip, Bytes::from(vec![0u8; (LIMIT / 5) as usize])).unwrap());
+        }
+        for delivery in deliveries {
+            delivery.await.unwrap().unwrap();
+        }
+
+        // Ensure the throughput was capped; the final message is delivered before its throttling delay.
+        assert!(start.elapsed() >= Duration::from_millis(1_700), "{:?}", start.elapsed());
+    }
 }
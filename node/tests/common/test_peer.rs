@@ -13,7 +13,16 @@
 // limitations under the License.
 
 use snarkos_account::Account;
-use snarkos_node_messages::{ChallengeRequest, ChallengeResponse, Data, Message, MessageCodec, MessageTrait, NodeType};
+use snarkos_node_messages::{
+    Capabilities,
+    ChallengeRequest,
+    ChallengeResponse,
+    Data,
+    Message,
+    MessageCodec,
+    MessageTrait,
+    NodeType,
+};
 use snarkos_node_router::expect_message;
 use snarkvm::prelude::{block::Block, error, Address, FromBytes, Network, TestRng, Testnet3 as CurrentNetwork};
 
@@ -131,7 +140,14 @@ impl Handshake for TestPeer {
         match node_side {
             ConnectionSide::Initiator => {
                 // Send a challenge request to the peer.
-                let our_request = ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen());
+                let our_request = ChallengeRequest::new(
+                    local_ip.port(),
+                    self.node_type(),
+                    self.address(),
+                    rng.gen(),
+                    Capabilities::NONE,
+                    String::new(),
+                );
                 framed.send(Message::ChallengeRequest(our_request)).await?;
 
                 // Receive the peer's challenge bundle.
@@ -155,7 +171,14 @@ impl Handshake for TestPeer {
                 // Send our challenge bundle.
                 let our_response = ChallengeResponse { genesis_header, signature: Data::Object(signature) };
                 framed.send(Message::ChallengeResponse(our_response)).await?;
-                let our_request = ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen());
+                let our_request = ChallengeRequest::new(
+                    local_ip.port(),
+                    self.node_type(),
+                    self.address(),
+                    rng.gen(),
+                    Capabilities::NONE,
+                    String::new(),
+                );
                 framed.send(Message::ChallengeRequest(our_request)).await?;
 
                 // Listen for the challenge response.
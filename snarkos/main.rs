@@ -34,7 +34,11 @@ fn main() -> anyhow::Result<()> {
     // Run the CLI.
     match cli.command.parse() {
         Ok(output) => println!("{output}\n"),
-        Err(error) => println!("⚠️  {error}\n"),
+        Err(error) => {
+            eprintln!("⚠️  {error}\n");
+            // Exit with a nonzero status, so that failures are detectable by scripts.
+            std::process::exit(1);
+        }
     }
     Ok(())
 }